@@ -1,29 +1,88 @@
-use battleship_engine::Engine;
+use battleship_config::SettingsFile;
+use battleship_core::{Board, FleetReport, RuleSet};
+use battleship_engine::{Engine, GameError};
 use battleship_interface::cli::CLIInterface;
-use battleship_player::InterfaceClient;
+use battleship_interface::GameInterface;
+use battleship_player::{AIPlayer, InterfaceClient, PlayerClient};
 use battleship_transport::LocalTransport;
 use futures::executor::block_on;
 
-fn main() {
+fn main() -> Result<(), GameError> {
+    // Settings come from battleship.toml and BATTLESHIP_* variables.
+    let mut file = SettingsFile::load()?;
+    // `--check` reports on the configured board and fleet instead of playing.
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let check = args.iter().any(|a| a == "--check");
+    // `--place` lets each player lay out their own fleet.
+    let place = args.iter().any(|a| a == "--place");
+    // `--ai` hands the second player to the computer.
+    let ai = args.iter().any(|a| a == "--ai");
+    args.retain(|a| a != "--check" && a != "--place" && a != "--ai");
+    // The rule set may be named as the first argument, e.g. `salvo`.
+    if let Some(arg) = args.first() {
+        match arg.parse::<RuleSet>() {
+            Ok(rules) => file.rules = Some(rules),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(2);
+            }
+        }
+    }
+    let preset = file.rules.unwrap_or_default();
+    let settings = file.resolve()?;
+    if check {
+        let report = FleetReport::analyze(&settings.game)?;
+        println!("{}", report);
+        std::process::exit(if report.is_feasible() { 0 } else { 1 });
+    }
+
     let ui1 = CLIInterface;
     let ui2 = CLIInterface;
+    // Name the rule set only if the settings file changed none of it.
+    if settings.game == preset.config() {
+        ui1.display_message(&format!("Playing by {} rules", preset));
+    }
+    let [name1, name2] = &settings.players;
+    ui1.display_message(&format!("{} vs {}", name1, name2));
 
     let (player1_transport, engine_t1) = LocalTransport::pair();
     let (player2_transport, engine_t2) = LocalTransport::pair();
 
+    let mut engine = Engine::with_config(&settings.game, engine_t1, engine_t2);
+    if place {
+        let humans = if ai { 1 } else { 2 };
+        let players = [(&ui1, name1), (&ui2, name2)].into_iter().take(humans);
+        for (player, (ui, name)) in players.enumerate() {
+            ui.display_message(&format!("{}, lay out your fleet", name));
+            let mut board = Board::with_config(&settings.game);
+            ui.place_fleet(&mut board);
+            engine = engine.with_fleet(player + 1, board);
+        }
+    }
+
     let handle1 = std::thread::spawn(move || {
         let mut client = InterfaceClient::new(ui1, player1_transport);
-        block_on(client.run());
+        block_on(client.run())
     });
 
-    let handle2 = std::thread::spawn(move || {
-        let mut client = InterfaceClient::new(ui2, player2_transport);
-        block_on(client.run());
-    });
+    let handle2 = if ai {
+        let player = AIPlayer::new().with_difficulty(settings.ai);
+        std::thread::spawn(move || block_on(PlayerClient::new(player, player2_transport).run()))
+    } else {
+        std::thread::spawn(move || {
+            let mut client = InterfaceClient::new(ui2, player2_transport);
+            block_on(client.run())
+        })
+    };
 
-    let mut engine = Engine::new(engine_t1, engine_t2);
-    block_on(engine.run());
+    let outcome = block_on(engine.run());
+    // Dropping the engine closes its transports so the clients stop waiting.
+    drop(engine);
 
-    handle1.join().unwrap();
-    handle2.join().unwrap();
+    let client1 = handle1.join().unwrap();
+    let client2 = handle2.join().unwrap();
+    outcome?;
+    client1?;
+    client2?;
+    Ok(())
 }
@@ -0,0 +1,245 @@
+use crate::BoardView;
+use std::fmt;
+use std::str::FromStr;
+
+/// A single cell on the board, addressed by zero-based row and column.
+///
+/// Coordinates are written in the familiar "A5" notation: one or more row
+/// letters followed by a one-based column number. Rows past `Z` continue as
+/// `AA`, `AB`, ... in the same way spreadsheet columns do.
+///
+/// # Example
+/// ```
+/// use battleship_common::Coord;
+/// let c: Coord = "B3".parse().unwrap();
+/// assert_eq!(c, Coord::new(1, 2));
+/// assert_eq!(c.to_string(), "B3");
+/// assert_eq!(Coord::new(26, 0).to_string(), "AA1");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Coord {
+    /// Zero-based row index
+    pub row: usize,
+    /// Zero-based column index
+    pub col: usize,
+}
+
+impl Coord {
+    /// Creates a coordinate from zero-based row and column indices.
+    pub const fn new(row: usize, col: usize) -> Self {
+        Self { row, col }
+    }
+
+    /// Returns the letter label for a zero-based row index (`0` is "A",
+    /// `25` is "Z", `26` is "AA").
+    pub fn row_label(row: usize) -> String {
+        let mut label = Vec::new();
+        let mut n = row + 1;
+        while n > 0 {
+            let rem = (n - 1) % 26;
+            label.push(b'A' + rem as u8);
+            n = (n - 1) / 26;
+        }
+        label.reverse();
+        String::from_utf8(label).expect("row labels are ASCII")
+    }
+
+    /// Returns true if the coordinate lies on a board of the given size.
+    pub fn in_bounds(&self, rows: usize, cols: usize) -> bool {
+        self.row < rows && self.col < cols
+    }
+
+    /// Returns true if the coordinate lies on the given board.
+    pub fn is_on(&self, board: &(impl BoardView + ?Sized)) -> bool {
+        self.in_bounds(board.rows(), board.cols())
+    }
+
+    /// Parses a coordinate and checks that it lies on the given board, in
+    /// open water.
+    ///
+    /// # Errors
+    /// Returns any [`ParseCoordError`] produced by parsing,
+    /// [`ParseCoordError::OutOfBounds`] if the cell is off the board, or
+    /// [`ParseCoordError::Island`] if it is an island.
+    pub fn parse_on(
+        input: &str,
+        board: &(impl BoardView + ?Sized),
+    ) -> Result<Self, ParseCoordError> {
+        let coord: Coord = input.parse()?;
+        if !coord.is_on(board) {
+            Err(ParseCoordError::OutOfBounds(coord))
+        } else if board.is_island(coord) {
+            Err(ParseCoordError::Island(coord))
+        } else {
+            Ok(coord)
+        }
+    }
+}
+
+impl From<(usize, usize)> for Coord {
+    fn from((row, col): (usize, usize)) -> Self {
+        Self::new(row, col)
+    }
+}
+
+impl From<Coord> for (usize, usize) {
+    fn from(coord: Coord) -> Self {
+        (coord.row, coord.col)
+    }
+}
+
+impl fmt::Display for Coord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", Coord::row_label(self.row), self.col + 1)
+    }
+}
+
+impl FromStr for Coord {
+    type Err = ParseCoordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(ParseCoordError::Empty);
+        }
+        let split = s
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(s.len());
+        let (letters, digits) = s.split_at(split);
+        if letters.is_empty() {
+            return Err(ParseCoordError::MissingRow);
+        }
+        if digits.is_empty() {
+            return Err(ParseCoordError::MissingColumn);
+        }
+
+        let mut row: usize = 0;
+        for b in letters.bytes() {
+            let digit = (b.to_ascii_uppercase() - b'A') as usize + 1;
+            row = row
+                .checked_mul(26)
+                .and_then(|r| r.checked_add(digit))
+                .ok_or(ParseCoordError::Overflow)?;
+        }
+
+        if !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParseCoordError::InvalidColumn);
+        }
+        let col: usize = digits.parse().map_err(|_| ParseCoordError::Overflow)?;
+        if col == 0 {
+            return Err(ParseCoordError::InvalidColumn);
+        }
+
+        Ok(Coord::new(row - 1, col - 1))
+    }
+}
+
+/// Reasons a string could not be turned into a [`Coord`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseCoordError {
+    /// The input was empty
+    Empty,
+    /// The input did not start with a row letter
+    MissingRow,
+    /// The row letters were not followed by a column number
+    MissingColumn,
+    /// The column was not a positive number
+    InvalidColumn,
+    /// The row or column is too large to represent
+    Overflow,
+    /// The coordinate is valid but does not lie on the board
+    OutOfBounds(Coord),
+    /// The coordinate is an island on the board
+    Island(Coord),
+}
+
+impl fmt::Display for ParseCoordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseCoordError::Empty => write!(f, "no coordinate given"),
+            ParseCoordError::MissingRow => write!(f, "coordinate must start with a row letter"),
+            ParseCoordError::MissingColumn => write!(f, "coordinate is missing a column number"),
+            ParseCoordError::InvalidColumn => write!(f, "column must be a number starting at 1"),
+            ParseCoordError::Overflow => write!(f, "coordinate is too large"),
+            ParseCoordError::OutOfBounds(c) => write!(f, "{} is not on the board", c),
+            ParseCoordError::Island(c) => write!(f, "{} is an island", c),
+        }
+    }
+}
+
+impl std::error::Error for ParseCoordError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn row_labels_roll_over_past_z() {
+        assert_eq!(Coord::row_label(0), "A");
+        assert_eq!(Coord::row_label(25), "Z");
+        assert_eq!(Coord::row_label(26), "AA");
+        assert_eq!(Coord::row_label(27), "AB");
+        assert_eq!(Coord::row_label(51), "AZ");
+        assert_eq!(Coord::row_label(52), "BA");
+        assert_eq!(Coord::row_label(701), "ZZ");
+        assert_eq!(Coord::row_label(702), "AAA");
+    }
+
+    #[test]
+    fn display_and_parse_round_trip() {
+        for row in 0..800 {
+            for col in [0, 9, 14] {
+                let c = Coord::new(row, col);
+                assert_eq!(c.to_string().parse::<Coord>(), Ok(c));
+            }
+        }
+    }
+
+    #[test]
+    fn parse_is_case_insensitive_and_trims() {
+        assert_eq!(" j10\n".parse::<Coord>(), Ok(Coord::new(9, 9)));
+        assert_eq!("aa3".parse::<Coord>(), Ok(Coord::new(26, 2)));
+    }
+
+    #[test]
+    fn parse_rejects_malformed_input() {
+        assert_eq!("".parse::<Coord>(), Err(ParseCoordError::Empty));
+        assert_eq!("5".parse::<Coord>(), Err(ParseCoordError::MissingRow));
+        assert_eq!("B".parse::<Coord>(), Err(ParseCoordError::MissingColumn));
+        assert_eq!("B0".parse::<Coord>(), Err(ParseCoordError::InvalidColumn));
+        assert_eq!("B-1".parse::<Coord>(), Err(ParseCoordError::InvalidColumn));
+        assert_eq!("B1x".parse::<Coord>(), Err(ParseCoordError::InvalidColumn));
+    }
+
+    #[test]
+    fn parse_on_rejects_islands() {
+        struct Atoll;
+        impl BoardView for Atoll {
+            fn rows(&self) -> usize {
+                3
+            }
+            fn cols(&self) -> usize {
+                3
+            }
+            fn is_island(&self, coord: Coord) -> bool {
+                coord == Coord::new(1, 1)
+            }
+        }
+        assert_eq!(Coord::parse_on("A1", &Atoll), Ok(Coord::new(0, 0)));
+        assert_eq!(
+            Coord::parse_on("B2", &Atoll),
+            Err(ParseCoordError::Island(Coord::new(1, 1)))
+        );
+        assert_eq!(
+            Coord::parse_on("D1", &Atoll),
+            Err(ParseCoordError::OutOfBounds(Coord::new(3, 0)))
+        );
+    }
+
+    #[test]
+    fn in_bounds_checks_both_dimensions() {
+        assert!(Coord::new(7, 11).in_bounds(8, 12));
+        assert!(!Coord::new(8, 0).in_bounds(8, 12));
+        assert!(!Coord::new(0, 12).in_bounds(8, 12));
+    }
+}
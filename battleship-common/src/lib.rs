@@ -3,12 +3,26 @@ pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + S
 pub mod board {
     /// Trait representing a read-only view of a game board.
     ///
-    /// Implementors should provide a textual display via [`std::fmt::Display`]
-    /// and report the board's dimensions through [`grid_size`].
-    pub trait BoardView: std::fmt::Display {
-        /// Return the length of one side of the square board.
-        fn grid_size(&self) -> usize;
+    /// Implementors report the board's dimensions through
+    /// [`rows`](BoardView::rows) and [`cols`](BoardView::cols), and which
+    /// cells are islands through [`is_island`](BoardView::is_island).
+    /// Drawing a board is left to the interface layer.
+    pub trait BoardView {
+        /// Return the number of rows (the board's height).
+        fn rows(&self) -> usize;
+
+        /// Return the number of columns (the board's width).
+        fn cols(&self) -> usize;
+
+        /// Return true if the cell is an island, where no ship lies and
+        /// nobody may fire. Boards without terrain have no islands.
+        fn is_island(&self, _coord: super::Coord) -> bool {
+            false
+        }
     }
 }
 
+pub mod coord;
+
 pub use board::BoardView;
+pub use coord::{Coord, ParseCoordError};
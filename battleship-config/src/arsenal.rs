@@ -0,0 +1,200 @@
+//! Special weapons for the advanced weapons mode.
+//!
+//! Besides their ordinary shots, players in this mode carry a few special
+//! weapons, each usable a limited number of times per game in place of a
+//! turn's shots. The [`Arsenal`] counts what each player starts with; in
+//! settings files it is written as a table:
+//!
+//! ```toml
+//! advanced_weapons = true
+//!
+//! [weapons]
+//! bombardments = 2
+//! torpedoes = 1
+//! sonar_pings = 3
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// A special weapon a player may use instead of firing their shots.
+///
+/// # Example
+/// ```
+/// use battleship_config::Weapon;
+/// let torpedo: Weapon = "Torpedo".parse().unwrap();
+/// assert_eq!(torpedo, Weapon::Torpedo);
+/// assert_eq!(Weapon::Bombardment.key(), "bomb");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Weapon {
+    /// Fires at every open cell of the 3x3 area around the target
+    Bombardment,
+    /// Runs east along the target's row until it strikes a ship, a mine or
+    /// an island
+    Torpedo,
+    /// Reveals whether the 3x3 area around the target holds any ship,
+    /// without damaging it
+    Sonar,
+}
+
+impl Weapon {
+    /// Every special weapon.
+    pub const ALL: [Weapon; 3] = [Weapon::Bombardment, Weapon::Torpedo, Weapon::Sonar];
+
+    /// Short name used when typing an order, e.g. `bomb B3`.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Weapon::Bombardment => "bomb",
+            Weapon::Torpedo => "torpedo",
+            Weapon::Sonar => "sonar",
+        }
+    }
+}
+
+impl fmt::Display for Weapon {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Weapon::Bombardment => "bombardment",
+            Weapon::Torpedo => "torpedo",
+            Weapon::Sonar => "sonar ping",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Weapon {
+    type Err = UnknownWeapon;
+
+    /// Parses a weapon from its [`key`](Weapon::key) or its full name,
+    /// ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = s.trim().to_ascii_lowercase();
+        Weapon::ALL
+            .into_iter()
+            .find(|w| w.key() == normalized || w.to_string() == normalized)
+            .ok_or_else(|| UnknownWeapon(s.trim().to_string()))
+    }
+}
+
+/// Text that names no [`Weapon`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownWeapon(pub String);
+
+impl fmt::Display for UnknownWeapon {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let keys: Vec<_> = Weapon::ALL.iter().map(Weapon::key).collect();
+        write!(
+            f,
+            "unknown weapon {:?}; expected one of {}",
+            self.0,
+            keys.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for UnknownWeapon {}
+
+/// How many uses of each special weapon a player has.
+///
+/// # Example
+/// ```
+/// use battleship_config::{Arsenal, Weapon};
+/// let mut arsenal = Arsenal::advanced();
+/// assert!(arsenal.take(Weapon::Torpedo));
+/// assert!(!arsenal.take(Weapon::Torpedo));
+/// assert_eq!(arsenal.to_string(), "bomb x1, sonar x2");
+/// assert!(Arsenal::none().is_empty());
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Arsenal {
+    /// Uses of [`Weapon::Bombardment`]
+    pub bombardments: usize,
+    /// Uses of [`Weapon::Torpedo`]
+    pub torpedoes: usize,
+    /// Uses of [`Weapon::Sonar`]
+    pub sonar_pings: usize,
+}
+
+impl Arsenal {
+    /// No special weapons: the classic game.
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    /// What each player carries when advanced weapons are switched on
+    /// without listing them: one bombardment, one torpedo and two sonar
+    /// pings.
+    pub fn advanced() -> Self {
+        Self {
+            bombardments: 1,
+            torpedoes: 1,
+            sonar_pings: 2,
+        }
+    }
+
+    /// Uses left of the given weapon.
+    pub fn count(&self, weapon: Weapon) -> usize {
+        match weapon {
+            Weapon::Bombardment => self.bombardments,
+            Weapon::Torpedo => self.torpedoes,
+            Weapon::Sonar => self.sonar_pings,
+        }
+    }
+
+    /// Uses up one of the given weapon.
+    ///
+    /// # Returns
+    /// * `bool` - False, leaving the arsenal unchanged, if none was left
+    pub fn take(&mut self, weapon: Weapon) -> bool {
+        let left = match weapon {
+            Weapon::Bombardment => &mut self.bombardments,
+            Weapon::Torpedo => &mut self.torpedoes,
+            Weapon::Sonar => &mut self.sonar_pings,
+        };
+        if *left == 0 {
+            return false;
+        }
+        *left -= 1;
+        true
+    }
+
+    /// Returns true if no special weapon has any use left.
+    pub fn is_empty(&self) -> bool {
+        Weapon::ALL.iter().all(|&w| self.count(w) == 0)
+    }
+
+    /// The weapons with at least one use left.
+    pub fn available(&self) -> impl Iterator<Item = Weapon> + '_ {
+        Weapon::ALL.into_iter().filter(|&w| self.count(w) > 0)
+    }
+}
+
+impl fmt::Display for Arsenal {
+    /// Lists the weapons left with their uses, e.g. `bomb x1, sonar x2`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let left: Vec<_> = self
+            .available()
+            .map(|w| format!("{} x{}", w.key(), self.count(w)))
+            .collect();
+        write!(f, "{}", left.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weapons_parse_from_keys_and_names() {
+        for weapon in Weapon::ALL {
+            assert_eq!(weapon.key().parse(), Ok(weapon));
+            assert_eq!(weapon.to_string().parse(), Ok(weapon));
+        }
+        assert_eq!(" BOMB ".parse(), Ok(Weapon::Bombardment));
+        assert_eq!("nuke".parse::<Weapon>(), Err(UnknownWeapon("nuke".into())));
+    }
+}
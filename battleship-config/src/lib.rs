@@ -1,3 +1,18 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+pub mod arsenal;
+pub mod settings;
+pub mod shape;
+pub mod terrain;
+
+pub use arsenal::{Arsenal, UnknownWeapon, Weapon};
+pub use settings::{ConfigError, Difficulty, Settings, SettingsFile, TransportSettings};
+pub use shape::{Shape, ShapeError};
+pub use terrain::{Terrain, TerrainError};
+
 pub const GRID_SIZE: usize = 10;
 
 pub const SHIPS: &[(&str, usize)] = &[
@@ -8,3 +23,663 @@ pub const SHIPS: &[(&str, usize)] = &[
     ("Destroyer", 2),
 ];
 
+/// One kind of ship in a fleet, and how many of it each player receives.
+///
+/// Ships are straight lines of `length` cells unless the class gives a
+/// [`Shape`]. In settings files a class lists either a `length` or a
+/// `shape`, whose cell count is then the length.
+///
+/// # Example
+/// ```
+/// use battleship_config::{Shape, ShipClass};
+/// let cruisers = ShipClass::new("Cruiser", 3, 2);
+/// assert_eq!((cruisers.length, cruisers.count), (3, 2));
+/// let plus = ShipClass::shaped("Plus", Shape::parse(&[".#.", "###", ".#."]).unwrap(), 1);
+/// assert_eq!(plus.length, 5);
+/// assert!(!plus.shape().is_line());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "ShipClassRepr", into = "ShipClassRepr")]
+pub struct ShipClass {
+    /// Name of the class, e.g. "Cruiser"
+    pub name: String,
+    /// Number of cells in each ship of this class
+    pub length: usize,
+    /// Number of ships of this class in the fleet
+    pub count: usize,
+    /// Outline of each ship, or `None` for a straight line of `length`
+    /// cells
+    pub shape: Option<Shape>,
+}
+
+impl ShipClass {
+    /// Creates a class of straight ships with the given name, length and
+    /// count.
+    pub fn new(name: impl Into<String>, length: usize, count: usize) -> Self {
+        Self {
+            name: name.into(),
+            length,
+            count,
+            shape: None,
+        }
+    }
+
+    /// Creates a class of ships with the given outline.
+    pub fn shaped(name: impl Into<String>, shape: Shape, count: usize) -> Self {
+        Self {
+            name: name.into(),
+            length: shape.len(),
+            count,
+            shape: Some(shape),
+        }
+    }
+
+    /// Outline of each ship of this class.
+    ///
+    /// # Panics
+    /// Panics for a straight class with a length of zero, which
+    /// [`GameConfig::validate`] rejects.
+    pub fn shape(&self) -> Shape {
+        self.shape
+            .clone()
+            .unwrap_or_else(|| Shape::line(self.length))
+    }
+}
+
+/// How a [`ShipClass`] is written in settings files.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ShipClassRepr {
+    name: String,
+    #[serde(default)]
+    length: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    shape: Option<Shape>,
+    #[serde(default = "one")]
+    count: usize,
+}
+
+fn one() -> usize {
+    1
+}
+
+impl TryFrom<ShipClassRepr> for ShipClass {
+    type Error = String;
+
+    fn try_from(repr: ShipClassRepr) -> Result<Self, Self::Error> {
+        match (repr.length, repr.shape) {
+            (Some(length), None) => Ok(ShipClass::new(repr.name, length, repr.count)),
+            (Some(length), Some(shape)) if length != shape.len() => Err(format!(
+                "{} has length {} but its shape covers {} cells",
+                repr.name,
+                length,
+                shape.len()
+            )),
+            (_, Some(shape)) => Ok(ShipClass::shaped(repr.name, shape, repr.count)),
+            (None, None) => Err(format!("{} needs a length or a shape", repr.name)),
+        }
+    }
+}
+
+impl From<ShipClass> for ShipClassRepr {
+    fn from(class: ShipClass) -> Self {
+        Self {
+            name: class.name,
+            length: Some(class.length),
+            shape: class.shape,
+            count: class.count,
+        }
+    }
+}
+
+/// Whether ships may touch each other once placed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AdjacencyRule {
+    /// Ships may touch, as in the classic Milton Bradley rules
+    #[default]
+    Allowed,
+    /// Ships may touch at corners but may not share an edge
+    NoSideContact,
+    /// Ships may not touch at all, even diagonally ("Morskoy Boy")
+    NoTouch,
+}
+
+impl AdjacencyRule {
+    /// Offsets, as `(row, col)` deltas, of the neighbouring cells another
+    /// ship may not occupy.
+    pub fn offsets(&self) -> &'static [(isize, isize)] {
+        match self {
+            AdjacencyRule::Allowed => &[],
+            AdjacencyRule::NoSideContact => &[(-1, 0), (1, 0), (0, -1), (0, 1)],
+            AdjacencyRule::NoTouch => &[
+                (-1, -1),
+                (-1, 0),
+                (-1, 1),
+                (0, -1),
+                (0, 1),
+                (1, -1),
+                (1, 0),
+                (1, 1),
+            ],
+        }
+    }
+}
+
+/// How many shots a player fires each turn.
+///
+/// In settings files this is written as a number for a fixed count or as
+/// `"surviving-ships"`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "ShotsPerTurnRepr", into = "ShotsPerTurnRepr")]
+pub enum ShotsPerTurn {
+    /// A fixed number of shots; `Fixed(1)` is the classic game
+    Fixed(usize),
+    /// One shot per ship the attacker still has afloat (classic Salvo)
+    SurvivingShips,
+}
+
+impl Default for ShotsPerTurn {
+    fn default() -> Self {
+        ShotsPerTurn::Fixed(1)
+    }
+}
+
+impl FromStr for ShotsPerTurn {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("surviving-ships") {
+            return Ok(ShotsPerTurn::SurvivingShips);
+        }
+        s.parse().map(ShotsPerTurn::Fixed).map_err(|_| {
+            format!(
+                "expected a number of shots or \"surviving-ships\", not {:?}",
+                s
+            )
+        })
+    }
+}
+
+/// How [`ShotsPerTurn`] is written in settings files.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum ShotsPerTurnRepr {
+    Fixed(usize),
+    Named(String),
+}
+
+impl TryFrom<ShotsPerTurnRepr> for ShotsPerTurn {
+    type Error = String;
+
+    fn try_from(repr: ShotsPerTurnRepr) -> Result<Self, Self::Error> {
+        match repr {
+            ShotsPerTurnRepr::Fixed(n) => Ok(ShotsPerTurn::Fixed(n)),
+            ShotsPerTurnRepr::Named(s) => s.parse(),
+        }
+    }
+}
+
+impl From<ShotsPerTurn> for ShotsPerTurnRepr {
+    fn from(shots: ShotsPerTurn) -> Self {
+        match shots {
+            ShotsPerTurn::Fixed(n) => ShotsPerTurnRepr::Fixed(n),
+            ShotsPerTurn::SurvivingShips => ShotsPerTurnRepr::Named("surviving-ships".into()),
+        }
+    }
+}
+
+/// What the attacker is told when a shot sinks a ship.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SunkAnnouncement {
+    /// The defender announces the sinking and names the ship, but not
+    /// which cells it occupied
+    #[default]
+    ShipName,
+    /// The defender names the sunk ship and reveals every cell it occupied
+    ShipPosition,
+    /// The defender announces the sinking without naming the ship or
+    /// revealing its cells
+    SunkOnly,
+    /// Sinking shots are reported as plain hits
+    HitOnly,
+}
+
+/// What happens to a player whose shot sets off one of the opponent's
+/// mines.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MinePenalty {
+    /// The shooter sits out their next turn
+    #[default]
+    LoseTurn,
+    /// The blast strikes a random unhit cell of the shooter's own fleet
+    CounterHit,
+}
+
+/// Runtime description of a game: the board dimensions and the fleet each
+/// player receives.
+///
+/// Boards need not be square; `rows` is the board's height and `cols` its
+/// width.
+///
+/// [`GameConfig::default`] reproduces the classic [`GRID_SIZE`] board with the
+/// [`SHIPS`] fleet, the same as [`RuleSet::MiltonBradley1990`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameConfig {
+    /// Number of rows on the board
+    pub rows: usize,
+    /// Number of columns on the board
+    pub cols: usize,
+    /// Every class of ship in the fleet
+    pub ships: Vec<ShipClass>,
+    /// Whether ships may touch each other
+    pub adjacency: AdjacencyRule,
+    /// How many shots a player fires each turn
+    pub shots_per_turn: ShotsPerTurn,
+    /// What the attacker learns when a ship sinks
+    pub sunk_announcement: SunkAnnouncement,
+    /// Whether straight ships may also lie along diagonals
+    pub diagonal_ships: bool,
+    /// Island `(row, col)` cells, where no ship lies and nobody fires
+    pub islands: Vec<(usize, usize)>,
+    /// Number of mines each player hides among their ships
+    pub mines: usize,
+    /// What setting off a mine costs the shooter
+    pub mine_penalty: MinePenalty,
+    /// Special weapons each player starts with; empty outside the advanced
+    /// weapons mode
+    pub arsenal: Arsenal,
+}
+
+impl GameConfig {
+    /// Creates a configuration with the given board dimensions and fleet of
+    /// one ship per `(name, length)` entry.
+    pub fn new(rows: usize, cols: usize, ships: &[(&str, usize)]) -> Self {
+        let classes = ships
+            .iter()
+            .map(|&(name, length)| ShipClass::new(name, length, 1))
+            .collect();
+        Self::with_classes(rows, cols, classes)
+    }
+
+    /// Creates a configuration with the given board dimensions and ship
+    /// classes.
+    ///
+    /// # Example
+    /// ```
+    /// use battleship_config::{GameConfig, ShipClass};
+    /// // The classic Russian fleet: 1x4, 2x3, 3x2 and 4x1.
+    /// let config = GameConfig::with_classes(
+    ///     10,
+    ///     10,
+    ///     vec![
+    ///         ShipClass::new("Battleship", 4, 1),
+    ///         ShipClass::new("Cruiser", 3, 2),
+    ///         ShipClass::new("Destroyer", 2, 3),
+    ///         ShipClass::new("Submarine", 1, 4),
+    ///     ],
+    /// );
+    /// assert_eq!(config.ship_list().len(), 10);
+    /// ```
+    pub fn with_classes(rows: usize, cols: usize, ships: Vec<ShipClass>) -> Self {
+        Self {
+            rows,
+            cols,
+            ships,
+            adjacency: AdjacencyRule::default(),
+            shots_per_turn: ShotsPerTurn::default(),
+            sunk_announcement: SunkAnnouncement::default(),
+            diagonal_ships: false,
+            islands: Vec::new(),
+            mines: 0,
+            mine_penalty: MinePenalty::default(),
+            arsenal: Arsenal::none(),
+        }
+    }
+
+    /// Returns this configuration with the given sunk-ship announcement rule.
+    pub fn with_sunk_announcement(mut self, sunk_announcement: SunkAnnouncement) -> Self {
+        self.sunk_announcement = sunk_announcement;
+        self
+    }
+
+    /// Returns this configuration with straight ships allowed, or not, to lie
+    /// along diagonals as well as rows and columns.
+    pub fn with_diagonal_ships(mut self, diagonal_ships: bool) -> Self {
+        self.diagonal_ships = diagonal_ships;
+        self
+    }
+
+    /// Returns this configuration played on the given map, which sets the
+    /// board's size and its islands.
+    ///
+    /// # Example
+    /// ```
+    /// use battleship_config::{GameConfig, Terrain};
+    /// let map = Terrain::parse(&["#.....", "......", ".....#"]).unwrap();
+    /// let config = GameConfig::default().with_terrain(&map);
+    /// assert_eq!((config.rows, config.cols), (3, 6));
+    /// assert_eq!(config.water_cells(), 16);
+    /// ```
+    pub fn with_terrain(mut self, terrain: &Terrain) -> Self {
+        self.rows = terrain.rows();
+        self.cols = terrain.cols();
+        self.islands = terrain.islands().to_vec();
+        self
+    }
+
+    /// Returns this configuration with each player hiding `mines` mines,
+    /// which cost a shooter who sets one off as `penalty` says.
+    pub fn with_mines(mut self, mines: usize, penalty: MinePenalty) -> Self {
+        self.mines = mines;
+        self.mine_penalty = penalty;
+        self
+    }
+
+    /// Returns this configuration in the advanced weapons mode, with each
+    /// player carrying `arsenal`.
+    pub fn with_arsenal(mut self, arsenal: Arsenal) -> Self {
+        self.arsenal = arsenal;
+        self
+    }
+
+    /// Number of cells on the board that are not islands.
+    pub fn water_cells(&self) -> usize {
+        (self.rows * self.cols).saturating_sub(self.islands.len())
+    }
+
+    /// Lists the name and length of every individual ship in the fleet.
+    ///
+    /// Ships whose class name occurs more than once in the fleet are
+    /// numbered so that every ship has a unique name.
+    ///
+    /// # Example
+    /// ```
+    /// use battleship_config::{GameConfig, ShipClass};
+    /// let config = GameConfig::with_classes(
+    ///     8,
+    ///     8,
+    ///     vec![ShipClass::new("Carrier", 5, 1), ShipClass::new("Submarine", 3, 2)],
+    /// );
+    /// let names: Vec<_> = config.ship_list().into_iter().map(|(name, _)| name).collect();
+    /// assert_eq!(names, ["Carrier", "Submarine 1", "Submarine 2"]);
+    /// ```
+    pub fn ship_list(&self) -> Vec<(String, usize)> {
+        self.numbered_ships()
+            .map(|(name, class)| (name, class.length))
+            .collect()
+    }
+
+    /// Lists the name and outline of every individual ship in the fleet,
+    /// named as in [`GameConfig::ship_list`].
+    ///
+    /// # Example
+    /// ```
+    /// use battleship_config::{GameConfig, Shape};
+    /// let config = GameConfig::square(10, &[("Destroyer", 2)]);
+    /// assert_eq!(config.ship_shapes(), [("Destroyer".to_string(), Shape::line(2))]);
+    /// ```
+    pub fn ship_shapes(&self) -> Vec<(String, Shape)> {
+        self.numbered_ships()
+            .map(|(name, class)| (name, class.shape()))
+            .collect()
+    }
+
+    /// Every individual ship with its unique name and its class.
+    fn numbered_ships(&self) -> impl Iterator<Item = (String, &ShipClass)> {
+        let mut numbered: HashMap<&str, usize> = HashMap::new();
+        for class in &self.ships {
+            *numbered.entry(&class.name).or_default() += class.count;
+        }
+        let mut next: HashMap<&str, usize> = HashMap::new();
+        let mut out = Vec::new();
+        for class in &self.ships {
+            for _ in 0..class.count {
+                let name = if numbered[class.name.as_str()] > 1 {
+                    let n = next.entry(&class.name).or_default();
+                    *n += 1;
+                    format!("{} {}", class.name, n)
+                } else {
+                    class.name.clone()
+                };
+                out.push((name, class));
+            }
+        }
+        out.into_iter()
+    }
+
+    /// Returns this configuration with the given adjacency rule.
+    pub fn with_adjacency(mut self, adjacency: AdjacencyRule) -> Self {
+        self.adjacency = adjacency;
+        self
+    }
+
+    /// Returns this configuration with the given number of shots per turn.
+    pub fn with_shots_per_turn(mut self, shots_per_turn: ShotsPerTurn) -> Self {
+        self.shots_per_turn = shots_per_turn;
+        self
+    }
+
+    /// Creates a configuration for a square board of the given size.
+    pub fn square(size: usize, ships: &[(&str, usize)]) -> Self {
+        Self::new(size, size, ships)
+    }
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self::square(GRID_SIZE, SHIPS)
+    }
+}
+
+/// Named, ready-made rule sets.
+///
+/// Each preset fixes the board size, fleet, adjacency rule, shots per turn
+/// and sunk-ship announcements; [`RuleSet::config`] turns it into the
+/// [`GameConfig`] the rest of the game runs on.
+///
+/// # Example
+/// ```
+/// use battleship_config::{AdjacencyRule, RuleSet, SunkAnnouncement};
+/// let rules: RuleSet = "morskoy-boy".parse().unwrap();
+/// let config = rules.config();
+/// assert_eq!(config.adjacency, AdjacencyRule::NoTouch);
+/// assert_eq!(config.sunk_announcement, SunkAnnouncement::SunkOnly);
+/// assert_eq!(config.ship_list().len(), 10);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum RuleSet {
+    /// The original Milton Bradley game, with an Aircraft Carrier
+    MiltonBradley1967,
+    /// The Milton Bradley edition the [`SHIPS`] fleet follows
+    #[default]
+    MiltonBradley1990,
+    /// Hasbro's fleet, with a Destroyer of three and a Patrol Boat of two
+    Hasbro2002,
+    /// Russian "Morskoy Boy": 1x4, 2x3, 3x2, 4x1, ships may not touch and
+    /// sinkings are announced without naming the ship
+    MorskoyBoy,
+    /// The classic fleet, firing one shot per surviving ship each turn
+    Salvo,
+    /// The classic fleet joined by a cross-shaped aircraft
+    Aircraft,
+    /// One each of the I, O, T, S and L tetrominoes
+    Tetris,
+}
+
+impl RuleSet {
+    /// Every preset, in the order they are listed to players.
+    pub const ALL: [RuleSet; 7] = [
+        RuleSet::MiltonBradley1967,
+        RuleSet::MiltonBradley1990,
+        RuleSet::Hasbro2002,
+        RuleSet::MorskoyBoy,
+        RuleSet::Salvo,
+        RuleSet::Aircraft,
+        RuleSet::Tetris,
+    ];
+
+    /// Short identifier used to pick the preset on the command line or in a
+    /// configuration file.
+    pub fn key(&self) -> &'static str {
+        match self {
+            RuleSet::MiltonBradley1967 => "mb1967",
+            RuleSet::MiltonBradley1990 => "mb1990",
+            RuleSet::Hasbro2002 => "hasbro2002",
+            RuleSet::MorskoyBoy => "morskoy-boy",
+            RuleSet::Salvo => "salvo",
+            RuleSet::Aircraft => "aircraft",
+            RuleSet::Tetris => "tetris",
+        }
+    }
+
+    /// Human-readable name of the preset.
+    pub fn name(&self) -> &'static str {
+        match self {
+            RuleSet::MiltonBradley1967 => "Milton Bradley (1967)",
+            RuleSet::MiltonBradley1990 => "Milton Bradley (1990)",
+            RuleSet::Hasbro2002 => "Hasbro (2002)",
+            RuleSet::MorskoyBoy => "Morskoy Boy",
+            RuleSet::Salvo => "Salvo",
+            RuleSet::Aircraft => "Battleship with aircraft",
+            RuleSet::Tetris => "Tetris fleet",
+        }
+    }
+
+    /// Builds the game configuration for this preset.
+    pub fn config(&self) -> GameConfig {
+        match self {
+            RuleSet::MiltonBradley1967 => GameConfig::square(
+                GRID_SIZE,
+                &[
+                    ("Aircraft Carrier", 5),
+                    ("Battleship", 4),
+                    ("Cruiser", 3),
+                    ("Submarine", 3),
+                    ("Destroyer", 2),
+                ],
+            ),
+            RuleSet::MiltonBradley1990 => GameConfig::square(GRID_SIZE, SHIPS),
+            RuleSet::Hasbro2002 => GameConfig::square(
+                GRID_SIZE,
+                &[
+                    ("Carrier", 5),
+                    ("Battleship", 4),
+                    ("Destroyer", 3),
+                    ("Submarine", 3),
+                    ("Patrol Boat", 2),
+                ],
+            ),
+            RuleSet::MorskoyBoy => GameConfig::with_classes(
+                GRID_SIZE,
+                GRID_SIZE,
+                vec![
+                    ShipClass::new("Battleship", 4, 1),
+                    ShipClass::new("Cruiser", 3, 2),
+                    ShipClass::new("Destroyer", 2, 3),
+                    ShipClass::new("Submarine", 1, 4),
+                ],
+            )
+            .with_adjacency(AdjacencyRule::NoTouch)
+            // "Killed!" says a ship sank, not which one.
+            .with_sunk_announcement(SunkAnnouncement::SunkOnly),
+            RuleSet::Salvo => GameConfig::square(GRID_SIZE, SHIPS)
+                .with_shots_per_turn(ShotsPerTurn::SurvivingShips),
+            RuleSet::Aircraft => {
+                let mut config = GameConfig::square(GRID_SIZE, SHIPS);
+                let aircraft = shape(&[".#.", "###", ".#.", ".#."]);
+                config
+                    .ships
+                    .push(ShipClass::shaped("Aircraft", aircraft, 1));
+                config
+            }
+            RuleSet::Tetris => GameConfig::with_classes(
+                GRID_SIZE,
+                GRID_SIZE,
+                vec![
+                    ShipClass::shaped("I-Block", shape(&["####"]), 1),
+                    ShipClass::shaped("O-Block", shape(&["##", "##"]), 1),
+                    ShipClass::shaped("T-Block", shape(&["###", ".#."]), 1),
+                    ShipClass::shaped("S-Block", shape(&[".##", "##."]), 1),
+                    ShipClass::shaped("L-Block", shape(&["#..", "###"]), 1),
+                ],
+            ),
+        }
+    }
+}
+
+/// Parses a built-in shape drawing.
+fn shape(rows: &[&str]) -> Shape {
+    Shape::parse(rows).expect("built-in shapes are valid")
+}
+
+impl fmt::Display for RuleSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for RuleSet {
+    type Err = UnknownRuleSet;
+
+    /// Parses a preset from its [`key`](RuleSet::key), ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        RuleSet::ALL
+            .into_iter()
+            .find(|rules| rules.key().eq_ignore_ascii_case(s))
+            .ok_or_else(|| UnknownRuleSet(s.to_string()))
+    }
+}
+
+impl TryFrom<String> for RuleSet {
+    type Error = UnknownRuleSet;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<RuleSet> for String {
+    fn from(rules: RuleSet) -> Self {
+        rules.key().to_string()
+    }
+}
+
+/// A rule set name that matches no preset.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownRuleSet(pub String);
+
+impl fmt::Display for UnknownRuleSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let keys: Vec<_> = RuleSet::ALL.iter().map(RuleSet::key).collect();
+        write!(
+            f,
+            "unknown rule set {:?}; expected one of {}",
+            self.0,
+            keys.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for UnknownRuleSet {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_rule_set_announces_sinkings_its_own_way() {
+        for rules in RuleSet::ALL {
+            let expected = match rules {
+                RuleSet::MorskoyBoy => SunkAnnouncement::SunkOnly,
+                _ => SunkAnnouncement::ShipName,
+            };
+            assert_eq!(rules.config().sunk_announcement, expected, "{}", rules);
+        }
+    }
+}
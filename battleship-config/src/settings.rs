@@ -0,0 +1,1090 @@
+//! Loading game settings from files and the environment.
+//!
+//! Team-standard settings live in a `battleship.toml` (or `.json`) file. The
+//! file is read into a [`SettingsFile`], in which every field is optional,
+//! environment variables are layered on top with [`SettingsFile::apply_env`],
+//! and [`SettingsFile::resolve`] fills in defaults and validates the result.
+//!
+//! ```toml
+//! rules = "hasbro2002"
+//! adjacency = "no-touch"
+//! shots_per_turn = 1
+//! diagonal_ships = true
+//! mines = 3
+//! mine_penalty = "counter-hit"
+//! advanced_weapons = true
+//!
+//! [board]
+//! rows = 10
+//! cols = 12
+//! # or draw the board, with `#` for islands:
+//! # map = ["..........", "...##.....", ".........."]
+//!
+//! [weapons]
+//! bombardments = 2
+//!
+//! [[fleet]]
+//! name = "Cruiser"
+//! length = 3
+//! count = 2
+//!
+//! [[fleet]]
+//! name = "Tee"
+//! shape = ["###", ".#."]
+//!
+//! [ai]
+//! difficulty = "hard"
+//!
+//! [players]
+//! names = ["Alice", "Bob"]
+//!
+//! [transport]
+//! listen = "0.0.0.0:7878"
+//! ```
+//!
+//! Settings from the file override the chosen `rules` preset, and environment
+//! variables override the file:
+//!
+//! | Variable | Setting |
+//! |---|---|
+//! | `BATTLESHIP_RULES` | `rules` |
+//! | `BATTLESHIP_ROWS`, `BATTLESHIP_COLS` | `board.rows`, `board.cols` |
+//! | `BATTLESHIP_MAP` | `board.map`, rows separated by `/` |
+//! | `BATTLESHIP_ADJACENCY` | `adjacency` |
+//! | `BATTLESHIP_SHOTS_PER_TURN` | `shots_per_turn` |
+//! | `BATTLESHIP_SUNK_ANNOUNCEMENT` | `sunk_announcement` |
+//! | `BATTLESHIP_DIAGONAL_SHIPS` | `diagonal_ships` |
+//! | `BATTLESHIP_MINES`, `BATTLESHIP_MINE_PENALTY` | `mines`, `mine_penalty` |
+//! | `BATTLESHIP_ADVANCED_WEAPONS` | `advanced_weapons` |
+//! | `BATTLESHIP_AI_DIFFICULTY` | `ai.difficulty` |
+//! | `BATTLESHIP_PLAYER1_NAME`, `BATTLESHIP_PLAYER2_NAME` | `players.names` |
+//! | `BATTLESHIP_LISTEN`, `BATTLESHIP_CONNECT` | `transport.listen`, `transport.connect` |
+//!
+//! `BATTLESHIP_CONFIG` names the settings file to load in place of
+//! [`DEFAULT_PATH`].
+
+use crate::{
+    AdjacencyRule, Arsenal, GameConfig, MinePenalty, RuleSet, Shape, ShipClass, ShotsPerTurn,
+    SunkAnnouncement, Terrain,
+};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Settings file looked for in the working directory.
+pub const DEFAULT_PATH: &str = "battleship.toml";
+
+/// Environment variable naming the settings file to load.
+pub const CONFIG_PATH_VAR: &str = "BATTLESHIP_CONFIG";
+
+/// How strong the computer opponent plays.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Difficulty {
+    /// Shots are spread widely, often ignoring promising cells
+    Easy,
+    /// Shots follow the probability of each cell holding a ship
+    #[default]
+    Normal,
+    /// Shots strongly favour the most likely cells
+    Hard,
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "easy" => Ok(Difficulty::Easy),
+            "normal" => Ok(Difficulty::Normal),
+            "hard" => Ok(Difficulty::Hard),
+            other => Err(format!("expected easy, normal or hard, not {:?}", other)),
+        }
+    }
+}
+
+/// Network addresses for games between two machines.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TransportSettings {
+    /// Address to accept an opponent's connection on, e.g. `0.0.0.0:7878`
+    pub listen: Option<String>,
+    /// Address of an opponent to connect to
+    pub connect: Option<String>,
+}
+
+/// Fully resolved and validated settings.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Settings {
+    /// Board, fleet and rules of the game
+    pub game: GameConfig,
+    /// Strength of computer players
+    pub ai: Difficulty,
+    /// Display names of the two players
+    pub players: [String; 2],
+    /// Network addresses, if the game is played over a network
+    pub transport: TransportSettings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            game: GameConfig::default(),
+            ai: Difficulty::default(),
+            players: default_names(),
+            transport: TransportSettings::default(),
+        }
+    }
+}
+
+fn default_names() -> [String; 2] {
+    ["Player 1".to_string(), "Player 2".to_string()]
+}
+
+impl Settings {
+    /// Loads settings the way the game does at start-up: the settings file
+    /// with environment overrides, resolved and validated.
+    ///
+    /// # Errors
+    /// Returns any error from [`SettingsFile::load`] or
+    /// [`SettingsFile::resolve`].
+    pub fn load() -> Result<Self, ConfigError> {
+        SettingsFile::load()?.resolve()
+    }
+}
+
+/// Board dimensions as written in a settings file.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BoardSettings {
+    /// Number of rows on the board
+    pub rows: Option<usize>,
+    /// Number of columns on the board
+    pub cols: Option<usize>,
+    /// Drawing of the board with its islands; sets the board's size
+    pub map: Option<Terrain>,
+}
+
+/// Computer player options as written in a settings file.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AiSettings {
+    /// Strength of computer players
+    pub difficulty: Option<Difficulty>,
+}
+
+/// Player options as written in a settings file.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PlayerSettings {
+    /// Display names, first player first; missing names keep their default
+    pub names: Vec<String>,
+}
+
+/// Settings as written in a file, before defaults are filled in.
+///
+/// Every field is optional so that a file only needs to mention what it
+/// changes. Unknown keys are rejected to catch typos.
+///
+/// # Example
+/// ```
+/// use battleship_config::{AdjacencyRule, SettingsFile};
+/// let file = SettingsFile::from_toml_str(
+///     r#"
+///     rules = "morskoy-boy"
+///     [board]
+///     rows = 12
+///     "#,
+/// )
+/// .unwrap();
+/// let env = [("BATTLESHIP_COLS".to_string(), "14".to_string())];
+/// let settings = file.apply_env(env).unwrap().resolve().unwrap();
+/// assert_eq!((settings.game.rows, settings.game.cols), (12, 14));
+/// assert_eq!(settings.game.adjacency, AdjacencyRule::NoTouch);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SettingsFile {
+    /// Preset the other settings start from
+    pub rules: Option<RuleSet>,
+    /// Board dimensions
+    pub board: BoardSettings,
+    /// Ship classes, replacing the preset's fleet when non-empty
+    pub fleet: Vec<ShipClass>,
+    /// Whether ships may touch each other
+    pub adjacency: Option<AdjacencyRule>,
+    /// How many shots a player fires each turn
+    pub shots_per_turn: Option<ShotsPerTurn>,
+    /// What the attacker learns when a ship sinks
+    pub sunk_announcement: Option<SunkAnnouncement>,
+    /// Whether straight ships may lie along diagonals
+    pub diagonal_ships: Option<bool>,
+    /// Number of mines each player hides
+    pub mines: Option<usize>,
+    /// What setting off a mine costs the shooter
+    pub mine_penalty: Option<MinePenalty>,
+    /// Whether players carry special weapons; with no `weapons` table they
+    /// get [`Arsenal::advanced`]
+    pub advanced_weapons: Option<bool>,
+    /// Special weapons each player carries
+    pub weapons: Option<Arsenal>,
+    /// Computer player options
+    pub ai: AiSettings,
+    /// Player options
+    pub players: PlayerSettings,
+    /// Network addresses
+    pub transport: TransportSettings,
+}
+
+impl SettingsFile {
+    /// Reads the file named by `BATTLESHIP_CONFIG`, or [`DEFAULT_PATH`] if it
+    /// is unset, and applies the process's environment overrides.
+    ///
+    /// A missing default file is not an error and leaves every setting
+    /// unset.
+    ///
+    /// # Errors
+    /// Returns a [`ConfigError`] if the file cannot be read or parsed, or an
+    /// environment variable holds an invalid value.
+    pub fn load() -> Result<Self, ConfigError> {
+        let file = match std::env::var_os(CONFIG_PATH_VAR) {
+            Some(path) => Self::from_path(path)?,
+            None if Path::new(DEFAULT_PATH).exists() => Self::from_path(DEFAULT_PATH)?,
+            None => Self::default(),
+        };
+        file.apply_env(std::env::vars())
+    }
+
+    /// Reads a settings file, choosing the format from its extension
+    /// (`.toml` or `.json`).
+    ///
+    /// # Errors
+    /// Returns [`ConfigError::Io`] if the file cannot be read,
+    /// [`ConfigError::UnsupportedFormat`] for any other extension, or
+    /// [`ConfigError::Parse`] if the contents are malformed.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let format = match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("toml") => Format::Toml,
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Format::Json,
+            _ => return Err(ConfigError::UnsupportedFormat(path.to_path_buf())),
+        };
+        let text = std::fs::read_to_string(path).map_err(|source| ConfigError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        format.parse(&text).map_err(|message| ConfigError::Parse {
+            path: Some(path.to_path_buf()),
+            message,
+        })
+    }
+
+    /// Parses settings written as TOML.
+    ///
+    /// # Errors
+    /// Returns [`ConfigError::Parse`] if the text is malformed.
+    pub fn from_toml_str(text: &str) -> Result<Self, ConfigError> {
+        Format::Toml
+            .parse(text)
+            .map_err(|message| ConfigError::Parse {
+                path: None,
+                message,
+            })
+    }
+
+    /// Parses settings written as JSON.
+    ///
+    /// # Errors
+    /// Returns [`ConfigError::Parse`] if the text is malformed.
+    pub fn from_json_str(text: &str) -> Result<Self, ConfigError> {
+        Format::Json
+            .parse(text)
+            .map_err(|message| ConfigError::Parse {
+                path: None,
+                message,
+            })
+    }
+
+    /// Overrides settings from `BATTLESHIP_*` environment variables.
+    ///
+    /// Variables are passed in rather than read so that callers decide where
+    /// they come from; pass `std::env::vars()` for the process environment.
+    /// Unrelated variables are ignored.
+    ///
+    /// # Arguments
+    /// * `vars` - `(name, value)` pairs
+    ///
+    /// # Errors
+    /// Returns [`ConfigError::InvalidEnv`] for a recognised variable whose
+    /// value cannot be parsed.
+    pub fn apply_env(
+        mut self,
+        vars: impl IntoIterator<Item = (String, String)>,
+    ) -> Result<Self, ConfigError> {
+        for (var, value) in vars {
+            match var.as_str() {
+                "BATTLESHIP_RULES" => self.rules = Some(env_value(&var, &value)?),
+                "BATTLESHIP_ROWS" => self.board.rows = Some(env_value(&var, &value)?),
+                "BATTLESHIP_COLS" => self.board.cols = Some(env_value(&var, &value)?),
+                "BATTLESHIP_MAP" => self.board.map = Some(env_value(&var, &value)?),
+                "BATTLESHIP_ADJACENCY" => {
+                    self.adjacency = Some(env_enum(&var, &value)?);
+                }
+                "BATTLESHIP_SHOTS_PER_TURN" => {
+                    self.shots_per_turn = Some(env_value(&var, &value)?);
+                }
+                "BATTLESHIP_SUNK_ANNOUNCEMENT" => {
+                    self.sunk_announcement = Some(env_enum(&var, &value)?);
+                }
+                "BATTLESHIP_DIAGONAL_SHIPS" => {
+                    self.diagonal_ships = Some(env_value(&var, &value)?);
+                }
+                "BATTLESHIP_MINES" => self.mines = Some(env_value(&var, &value)?),
+                "BATTLESHIP_MINE_PENALTY" => {
+                    self.mine_penalty = Some(env_enum(&var, &value)?);
+                }
+                "BATTLESHIP_ADVANCED_WEAPONS" => {
+                    self.advanced_weapons = Some(env_value(&var, &value)?);
+                }
+                "BATTLESHIP_AI_DIFFICULTY" => self.ai.difficulty = Some(env_value(&var, &value)?),
+                "BATTLESHIP_PLAYER1_NAME" => self.set_name(0, value),
+                "BATTLESHIP_PLAYER2_NAME" => self.set_name(1, value),
+                "BATTLESHIP_LISTEN" => self.transport.listen = Some(value),
+                "BATTLESHIP_CONNECT" => self.transport.connect = Some(value),
+                _ => {}
+            }
+        }
+        Ok(self)
+    }
+
+    fn set_name(&mut self, player: usize, name: String) {
+        let names = &mut self.players.names;
+        if names.len() <= player {
+            let defaults = default_names();
+            names.extend(defaults[names.len()..=player].iter().cloned());
+        }
+        names[player] = name;
+    }
+
+    /// Fills in defaults from the chosen preset and validates the result.
+    ///
+    /// # Errors
+    /// Returns [`ConfigError::TooManyPlayers`] if more than two names are
+    /// given, [`ConfigError::MapSize`] if the board's rows or columns
+    /// disagree with its map, or any error from [`GameConfig::validate`].
+    pub fn resolve(self) -> Result<Settings, ConfigError> {
+        let mut game = self.rules.unwrap_or_default().config();
+        if let Some(map) = &self.board.map {
+            let rows = self.board.rows.unwrap_or(map.rows());
+            let cols = self.board.cols.unwrap_or(map.cols());
+            if (rows, cols) != (map.rows(), map.cols()) {
+                return Err(ConfigError::MapSize {
+                    rows,
+                    cols,
+                    map_rows: map.rows(),
+                    map_cols: map.cols(),
+                });
+            }
+            game = game.with_terrain(map);
+        }
+        if let Some(rows) = self.board.rows {
+            game.rows = rows;
+        }
+        if let Some(cols) = self.board.cols {
+            game.cols = cols;
+        }
+        if !self.fleet.is_empty() {
+            game.ships = self.fleet;
+        }
+        if let Some(adjacency) = self.adjacency {
+            game.adjacency = adjacency;
+        }
+        if let Some(shots_per_turn) = self.shots_per_turn {
+            game.shots_per_turn = shots_per_turn;
+        }
+        if let Some(sunk_announcement) = self.sunk_announcement {
+            game.sunk_announcement = sunk_announcement;
+        }
+        if let Some(diagonal_ships) = self.diagonal_ships {
+            game.diagonal_ships = diagonal_ships;
+        }
+        if let Some(mines) = self.mines {
+            game.mines = mines;
+        }
+        if let Some(mine_penalty) = self.mine_penalty {
+            game.mine_penalty = mine_penalty;
+        }
+        match (self.advanced_weapons, self.weapons) {
+            (Some(false), _) => game.arsenal = Arsenal::none(),
+            (_, Some(weapons)) => game.arsenal = weapons,
+            (Some(true), None) => game.arsenal = Arsenal::advanced(),
+            (None, None) => {}
+        }
+        game.validate()?;
+
+        if self.players.names.len() > 2 {
+            return Err(ConfigError::TooManyPlayers(self.players.names.len()));
+        }
+        let mut players = default_names();
+        for (slot, name) in players.iter_mut().zip(self.players.names) {
+            *slot = name;
+        }
+
+        Ok(Settings {
+            game,
+            ai: self.ai.difficulty.unwrap_or_default(),
+            players,
+            transport: self.transport,
+        })
+    }
+}
+
+/// Parses an environment variable's value with `FromStr`.
+fn env_value<T>(var: &str, value: &str) -> Result<T, ConfigError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    value
+        .trim()
+        .parse()
+        .map_err(|e: T::Err| ConfigError::InvalidEnv {
+            var: var.to_string(),
+            value: value.to_string(),
+            message: e.to_string(),
+        })
+}
+
+/// Parses an environment variable's value as the kebab-case name of a
+/// unit enum variant, the same spelling the settings file uses.
+fn env_enum<T: DeserializeOwned>(var: &str, value: &str) -> Result<T, ConfigError> {
+    serde_json::from_value(serde_json::Value::String(value.trim().to_string())).map_err(|e| {
+        ConfigError::InvalidEnv {
+            var: var.to_string(),
+            value: value.to_string(),
+            message: e.to_string(),
+        }
+    })
+}
+
+/// Formats a settings file may be written in.
+#[derive(Clone, Copy)]
+enum Format {
+    Toml,
+    Json,
+}
+
+impl Format {
+    fn parse(self, text: &str) -> Result<SettingsFile, String> {
+        match self {
+            Format::Toml => toml::from_str(text).map_err(|e| e.to_string()),
+            Format::Json => serde_json::from_str(text).map_err(|e| e.to_string()),
+        }
+    }
+}
+
+impl GameConfig {
+    /// Checks that a game can be played with this configuration.
+    ///
+    /// # Errors
+    /// Returns a [`ConfigError`] if the board has no cells, the fleet is
+    /// empty, a ship class is unnamed or has a zero length or count, a
+    /// shaped class's length differs from its cell count, an island lies off
+    /// the board, a ship fits the board in no orientation, two ships end up
+    /// with the same name, the fleet has more cells than the board has water, the water left around the fleet
+    /// cannot hold the mines, or zero shots are allowed per turn.
+    ///
+    /// # Example
+    /// ```
+    /// use battleship_config::{ConfigError, GameConfig};
+    /// assert!(GameConfig::default().validate().is_ok());
+    /// let too_small = GameConfig::square(4, &[("Carrier", 5)]);
+    /// assert!(matches!(too_small.validate(), Err(ConfigError::ShipTooLong { .. })));
+    /// ```
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.rows == 0 || self.cols == 0 {
+            return Err(ConfigError::EmptyBoard {
+                rows: self.rows,
+                cols: self.cols,
+            });
+        }
+        if self.ships.is_empty() {
+            return Err(ConfigError::EmptyFleet);
+        }
+        if let Some(&(row, col)) = self
+            .islands
+            .iter()
+            .find(|&&(row, col)| row >= self.rows || col >= self.cols)
+        {
+            return Err(ConfigError::IslandOffBoard { row, col });
+        }
+        for class in &self.ships {
+            if class.name.trim().is_empty() {
+                return Err(ConfigError::UnnamedShip);
+            }
+            if class.length == 0 || class.count == 0 {
+                return Err(ConfigError::EmptyShipClass(class.name.clone()));
+            }
+            if let Some(shape) = &class.shape {
+                if shape.len() != class.length {
+                    return Err(ConfigError::ShapeMismatch {
+                        ship: class.name.clone(),
+                        length: class.length,
+                        cells: shape.len(),
+                    });
+                }
+            }
+            let fits = |s: &Shape| s.height() <= self.rows && s.width() <= self.cols;
+            if !class.shape().orientations().iter().any(fits) {
+                return Err(ConfigError::ShipTooLong {
+                    ship: class.name.clone(),
+                    length: class.length,
+                    rows: self.rows,
+                    cols: self.cols,
+                });
+            }
+        }
+        let mut names = HashSet::new();
+        if let Some((name, _)) = self
+            .ship_list()
+            .into_iter()
+            .find(|(name, _)| !names.insert(name.clone()))
+        {
+            return Err(ConfigError::DuplicateShipName(name));
+        }
+        let cells: usize = self.ships.iter().map(|c| c.length * c.count).sum();
+        if cells > self.water_cells() {
+            return Err(ConfigError::FleetTooLarge {
+                cells,
+                board_cells: self.water_cells(),
+            });
+        }
+        if cells + self.mines > self.water_cells() {
+            return Err(ConfigError::TooManyMines {
+                mines: self.mines,
+                free_cells: self.water_cells() - cells,
+            });
+        }
+        if self.shots_per_turn == ShotsPerTurn::Fixed(0) {
+            return Err(ConfigError::NoShots);
+        }
+        Ok(())
+    }
+}
+
+/// Reasons settings could not be loaded.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The settings file could not be read
+    Io { path: PathBuf, source: io::Error },
+    /// The settings file is not a `.toml` or `.json` file
+    UnsupportedFormat(PathBuf),
+    /// The settings are malformed; `path` is `None` when parsing a string
+    Parse {
+        path: Option<PathBuf>,
+        message: String,
+    },
+    /// An environment variable holds a value that cannot be parsed
+    InvalidEnv {
+        var: String,
+        value: String,
+        message: String,
+    },
+    /// More than two player names were given
+    TooManyPlayers(usize),
+    /// The board has no cells
+    EmptyBoard { rows: usize, cols: usize },
+    /// The fleet has no ships
+    EmptyFleet,
+    /// A ship class has an empty name
+    UnnamedShip,
+    /// The named ship class has a length or count of zero
+    EmptyShipClass(String),
+    /// A shaped ship class gives a length other than its shape's cell count
+    ShapeMismatch {
+        ship: String,
+        length: usize,
+        cells: usize,
+    },
+    /// The named ship does not fit on the board in any orientation
+    ShipTooLong {
+        ship: String,
+        length: usize,
+        rows: usize,
+        cols: usize,
+    },
+    /// Two ships in the fleet end up with the given name
+    DuplicateShipName(String),
+    /// The map's size disagrees with the rows or columns given alongside it
+    MapSize {
+        rows: usize,
+        cols: usize,
+        map_rows: usize,
+        map_cols: usize,
+    },
+    /// An island lies outside the board
+    IslandOffBoard { row: usize, col: usize },
+    /// The fleet has more cells than the board has open water
+    FleetTooLarge { cells: usize, board_cells: usize },
+    /// The water the fleet leaves free cannot hold every mine
+    TooManyMines { mines: usize, free_cells: usize },
+    /// A fixed count of zero shots per turn was given
+    NoShots,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io { path, source } => {
+                write!(f, "could not read {}: {}", path.display(), source)
+            }
+            ConfigError::UnsupportedFormat(path) => write!(
+                f,
+                "{} is not a settings file; expected a .toml or .json extension",
+                path.display()
+            ),
+            ConfigError::Parse {
+                path: Some(path),
+                message,
+            } => write!(f, "invalid settings in {}: {}", path.display(), message),
+            ConfigError::Parse {
+                path: None,
+                message,
+            } => write!(f, "invalid settings: {}", message),
+            ConfigError::InvalidEnv {
+                var,
+                value,
+                message,
+            } => write!(f, "invalid value {:?} for {}: {}", value, var, message),
+            ConfigError::TooManyPlayers(n) => {
+                write!(f, "{} player names given, but a game has two players", n)
+            }
+            ConfigError::EmptyBoard { rows, cols } => {
+                write!(f, "a {}x{} board has no cells", rows, cols)
+            }
+            ConfigError::EmptyFleet => write!(f, "the fleet has no ships"),
+            ConfigError::UnnamedShip => write!(f, "every ship class needs a name"),
+            ConfigError::EmptyShipClass(ship) => {
+                write!(f, "{} needs a length and count of at least 1", ship)
+            }
+            ConfigError::ShapeMismatch {
+                ship,
+                length,
+                cells,
+            } => write!(
+                f,
+                "{} has length {} but its shape covers {} cells",
+                ship, length, cells
+            ),
+            ConfigError::ShipTooLong {
+                ship,
+                length,
+                rows,
+                cols,
+            } => write!(
+                f,
+                "{} is {} cells long and does not fit on a {}x{} board",
+                ship, length, rows, cols
+            ),
+            ConfigError::DuplicateShipName(ship) => {
+                write!(f, "more than one ship would be named {}", ship)
+            }
+            ConfigError::MapSize {
+                rows,
+                cols,
+                map_rows,
+                map_cols,
+            } => write!(
+                f,
+                "the board is {}x{} but its map is {}x{}",
+                rows, cols, map_rows, map_cols
+            ),
+            ConfigError::IslandOffBoard { row, col } => write!(
+                f,
+                "the island at row {}, column {} is off the board",
+                row + 1,
+                col + 1
+            ),
+            ConfigError::FleetTooLarge { cells, board_cells } => write!(
+                f,
+                "the fleet covers {} cells but the board only has {} of open water",
+                cells, board_cells
+            ),
+            ConfigError::TooManyMines { mines, free_cells } => write!(
+                f,
+                "{} mines do not fit in the {} cells the fleet leaves free",
+                mines, free_cells
+            ),
+            ConfigError::NoShots => write!(f, "players must fire at least one shot per turn"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|&(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn empty_file_gives_default_settings() {
+        let settings = SettingsFile::from_toml_str("").unwrap().resolve().unwrap();
+        assert_eq!(settings, Settings::default());
+    }
+
+    #[test]
+    fn toml_file_overrides_preset() {
+        let file = SettingsFile::from_toml_str(
+            r#"
+            rules = "salvo"
+            adjacency = "no-side-contact"
+            sunk_announcement = "hit-only"
+
+            [board]
+            rows = 8
+            cols = 12
+
+            [[fleet]]
+            name = "Cruiser"
+            length = 3
+            count = 2
+
+            [[fleet]]
+            name = "Destroyer"
+            length = 2
+
+            [ai]
+            difficulty = "hard"
+
+            [players]
+            names = ["Alice"]
+
+            [transport]
+            connect = "10.0.0.2:7878"
+            "#,
+        )
+        .unwrap();
+        let settings = file.resolve().unwrap();
+        let game = &settings.game;
+        assert_eq!((game.rows, game.cols), (8, 12));
+        assert_eq!(
+            game.ships,
+            vec![
+                ShipClass::new("Cruiser", 3, 2),
+                ShipClass::new("Destroyer", 2, 1)
+            ]
+        );
+        assert_eq!(game.adjacency, AdjacencyRule::NoSideContact);
+        assert_eq!(game.shots_per_turn, ShotsPerTurn::SurvivingShips);
+        assert_eq!(game.sunk_announcement, SunkAnnouncement::HitOnly);
+        assert_eq!(settings.ai, Difficulty::Hard);
+        assert_eq!(
+            settings.players,
+            ["Alice".to_string(), "Player 2".to_string()]
+        );
+        assert_eq!(settings.transport.connect.as_deref(), Some("10.0.0.2:7878"));
+    }
+
+    #[test]
+    fn json_file_is_read_the_same_way() {
+        let file = SettingsFile::from_json_str(
+            r#"{"rules": "morskoy-boy", "shots_per_turn": 3, "board": {"rows": 12}}"#,
+        )
+        .unwrap();
+        let game = file.resolve().unwrap().game;
+        assert_eq!(game.rows, 12);
+        assert_eq!(game.shots_per_turn, ShotsPerTurn::Fixed(3));
+        assert_eq!(game.adjacency, AdjacencyRule::NoTouch);
+    }
+
+    #[test]
+    fn environment_overrides_file() {
+        let file = SettingsFile::from_toml_str("rules = \"salvo\"\n[board]\nrows = 8").unwrap();
+        let settings = file
+            .apply_env(env(&[
+                ("BATTLESHIP_RULES", "hasbro2002"),
+                ("BATTLESHIP_ROWS", "11"),
+                ("BATTLESHIP_ADJACENCY", "no-touch"),
+                ("BATTLESHIP_SHOTS_PER_TURN", "surviving-ships"),
+                ("BATTLESHIP_SUNK_ANNOUNCEMENT", "ship-position"),
+                ("BATTLESHIP_DIAGONAL_SHIPS", "true"),
+                ("BATTLESHIP_MINES", "4"),
+                ("BATTLESHIP_MINE_PENALTY", "counter-hit"),
+                ("BATTLESHIP_AI_DIFFICULTY", "Easy"),
+                ("BATTLESHIP_PLAYER2_NAME", "Bob"),
+                ("BATTLESHIP_LISTEN", "0.0.0.0:7878"),
+                ("PATH", "/usr/bin"),
+            ]))
+            .unwrap()
+            .resolve()
+            .unwrap();
+        assert_eq!(settings.game.rows, 11);
+        assert_eq!(settings.game.ships, RuleSet::Hasbro2002.config().ships);
+        assert_eq!(settings.game.adjacency, AdjacencyRule::NoTouch);
+        assert_eq!(settings.game.shots_per_turn, ShotsPerTurn::SurvivingShips);
+        assert_eq!(
+            settings.game.sunk_announcement,
+            SunkAnnouncement::ShipPosition
+        );
+        assert!(settings.game.diagonal_ships);
+        assert_eq!(settings.game.mines, 4);
+        assert_eq!(settings.game.mine_penalty, MinePenalty::CounterHit);
+        assert_eq!(settings.ai, Difficulty::Easy);
+        assert_eq!(
+            settings.players,
+            ["Player 1".to_string(), "Bob".to_string()]
+        );
+        assert_eq!(settings.transport.listen.as_deref(), Some("0.0.0.0:7878"));
+    }
+
+    #[test]
+    fn fleet_entries_give_a_length_or_a_shape() {
+        let file = SettingsFile::from_toml_str(
+            r#"
+            [[fleet]]
+            name = "Tee"
+            shape = ['###', '.#.']
+            count = 2
+
+            [[fleet]]
+            name = "Destroyer"
+            length = 2
+            "#,
+        )
+        .unwrap();
+        let game = file.resolve().unwrap().game;
+        let tee = Shape::parse(&["###", ".#."]).unwrap();
+        assert_eq!(game.ships[0], ShipClass::shaped("Tee", tee, 2));
+        assert_eq!(game.ships[1].shape, None);
+
+        for fleet in [
+            "[[fleet]]\nname = \"Tee\"",
+            "[[fleet]]\nname = \"Tee\"\nlength = 3\nshape = [\"###\", \".#.\"]",
+            "[[fleet]]\nname = \"Gap\"\nshape = [\"#.#\"]",
+        ] {
+            assert!(matches!(
+                SettingsFile::from_toml_str(fleet),
+                Err(ConfigError::Parse { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn maps_set_the_board_and_its_islands() {
+        let file = SettingsFile::from_toml_str(
+            r#"
+            [board]
+            map = [
+                "......",
+                ".##...",
+                "......",
+            ]
+
+            [[fleet]]
+            name = "Destroyer"
+            length = 2
+            "#,
+        )
+        .unwrap();
+        let settings = file.clone().resolve().unwrap();
+        assert_eq!((settings.game.rows, settings.game.cols), (3, 6));
+        assert_eq!(settings.game.islands, [(1, 1), (1, 2)]);
+
+        let mut wide = file.clone();
+        wide.board.cols = Some(8);
+        assert!(matches!(
+            wide.resolve(),
+            Err(ConfigError::MapSize {
+                cols: 8,
+                map_cols: 6,
+                ..
+            })
+        ));
+
+        let settings = file
+            .apply_env(env(&[("BATTLESHIP_MAP", "#.../..../...#/....")]))
+            .unwrap()
+            .resolve()
+            .unwrap();
+        assert_eq!((settings.game.rows, settings.game.cols), (4, 4));
+        assert_eq!(settings.game.islands, [(0, 0), (2, 3)]);
+    }
+
+    #[test]
+    fn advanced_weapons_give_each_player_an_arsenal() {
+        let on = SettingsFile::from_toml_str("advanced_weapons = true").unwrap();
+        assert_eq!(on.resolve().unwrap().game.arsenal, Arsenal::advanced());
+
+        let listed = SettingsFile::from_toml_str("[weapons]\ntorpedoes = 3").unwrap();
+        let arsenal = listed.clone().resolve().unwrap().game.arsenal;
+        assert_eq!(
+            (arsenal.bombardments, arsenal.torpedoes, arsenal.sonar_pings),
+            (0, 3, 0)
+        );
+
+        let off = listed
+            .apply_env(env(&[("BATTLESHIP_ADVANCED_WEAPONS", "false")]))
+            .unwrap();
+        assert!(off.resolve().unwrap().game.arsenal.is_empty());
+        assert!(SettingsFile::default()
+            .resolve()
+            .unwrap()
+            .game
+            .arsenal
+            .is_empty());
+    }
+
+    #[test]
+    fn bad_input_is_reported() {
+        assert!(matches!(
+            SettingsFile::from_toml_str("[board]\nrowz = 8"),
+            Err(ConfigError::Parse { path: None, .. })
+        ));
+        assert!(matches!(
+            SettingsFile::from_toml_str("rules = \"checkers\""),
+            Err(ConfigError::Parse { .. })
+        ));
+        let err = SettingsFile::default()
+            .apply_env(env(&[("BATTLESHIP_COLS", "wide")]))
+            .unwrap_err();
+        assert!(matches!(err, ConfigError::InvalidEnv { ref var, .. } if var == "BATTLESHIP_COLS"));
+        assert!(SettingsFile::default()
+            .apply_env(env(&[("BATTLESHIP_ADJACENCY", "sometimes")]))
+            .is_err());
+        assert!(matches!(
+            SettingsFile::from_path("battleship.yaml"),
+            Err(ConfigError::UnsupportedFormat(_))
+        ));
+        assert!(matches!(
+            SettingsFile::from_path("no/such/battleship.toml"),
+            Err(ConfigError::Io { .. })
+        ));
+    }
+
+    #[test]
+    fn validation_catches_unplayable_games() {
+        let ships = |classes: &[(&str, usize, usize)]| -> Vec<ShipClass> {
+            classes
+                .iter()
+                .map(|&(name, length, count)| ShipClass::new(name, length, count))
+                .collect()
+        };
+        let check = |rows, cols, classes: &[(&str, usize, usize)]| {
+            GameConfig::with_classes(rows, cols, ships(classes)).validate()
+        };
+
+        assert!(check(1, 5, &[("Carrier", 5, 1)]).is_ok());
+        assert!(matches!(
+            check(0, 10, &[("Carrier", 5, 1)]),
+            Err(ConfigError::EmptyBoard { .. })
+        ));
+        assert!(matches!(check(10, 10, &[]), Err(ConfigError::EmptyFleet)));
+        assert!(matches!(
+            check(10, 10, &[(" ", 2, 1)]),
+            Err(ConfigError::UnnamedShip)
+        ));
+        assert!(matches!(
+            check(10, 10, &[("Raft", 0, 1)]),
+            Err(ConfigError::EmptyShipClass(ship)) if ship == "Raft"
+        ));
+        assert!(matches!(
+            check(4, 3, &[("Carrier", 5, 1)]),
+            Err(ConfigError::ShipTooLong { length: 5, .. })
+        ));
+        assert!(matches!(
+            check(3, 3, &[("Cruiser", 3, 4)]),
+            Err(ConfigError::FleetTooLarge {
+                cells: 12,
+                board_cells: 9
+            })
+        ));
+        // Two "Submarine"s are numbered, and the first collides with the
+        // class named "Submarine 1".
+        assert!(matches!(
+            check(10, 10, &[("Submarine", 1, 2), ("Submarine 1", 1, 1)]),
+            Err(ConfigError::DuplicateShipName(ship)) if ship == "Submarine 1"
+        ));
+        let plus = Shape::parse(&[".#.", "###", ".#."]).unwrap();
+        let planes = vec![ShipClass::shaped("Plane", plus.clone(), 1)];
+        assert!(GameConfig::with_classes(3, 3, planes.clone())
+            .validate()
+            .is_ok());
+        assert!(matches!(
+            GameConfig::with_classes(2, 8, planes).validate(),
+            Err(ConfigError::ShipTooLong { length: 5, .. })
+        ));
+        let mut bad = ShipClass::shaped("Plane", plus, 1);
+        bad.length = 4;
+        assert!(matches!(
+            GameConfig::with_classes(5, 5, vec![bad]).validate(),
+            Err(ConfigError::ShapeMismatch {
+                length: 4,
+                cells: 5,
+                ..
+            })
+        ));
+
+        let mut stray = GameConfig::square(3, &[("Destroyer", 2)]);
+        stray.islands = vec![(1, 3)];
+        assert!(matches!(
+            stray.validate(),
+            Err(ConfigError::IslandOffBoard { row: 1, col: 3 })
+        ));
+        let crowded = GameConfig::square(3, &[("Cruiser", 3), ("Destroyer", 2)])
+            .with_terrain(&Terrain::parse(&["###", "##.", "..."]).unwrap());
+        assert!(matches!(
+            crowded.validate(),
+            Err(ConfigError::FleetTooLarge {
+                cells: 5,
+                board_cells: 4
+            })
+        ));
+
+        let mined = GameConfig::square(3, &[("Cruiser", 3)]).with_mines(7, MinePenalty::LoseTurn);
+        assert!(matches!(
+            mined.validate(),
+            Err(ConfigError::TooManyMines {
+                mines: 7,
+                free_cells: 6
+            })
+        ));
+
+        let no_shots = GameConfig::default().with_shots_per_turn(ShotsPerTurn::Fixed(0));
+        assert!(matches!(no_shots.validate(), Err(ConfigError::NoShots)));
+
+        let too_many = SettingsFile {
+            players: PlayerSettings {
+                names: vec!["A".into(), "B".into(), "C".into()],
+            },
+            ..SettingsFile::default()
+        };
+        assert!(matches!(
+            too_many.resolve(),
+            Err(ConfigError::TooManyPlayers(3))
+        ));
+    }
+
+    #[test]
+    fn checked_in_settings_file_is_valid() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../battleship.toml");
+        SettingsFile::from_path(path).unwrap().resolve().unwrap();
+    }
+}
@@ -0,0 +1,320 @@
+//! Ship outlines beyond straight lines.
+//!
+//! A [`Shape`] is a connected set of cells, stored relative to the top-left
+//! corner of its bounding box. Straight ships are the special case
+//! [`Shape::line`]; anything else (an L, a T, a plus) is written in settings
+//! files as rows of `#` for a ship cell and `.` for a gap:
+//!
+//! ```toml
+//! [[fleet]]
+//! name = "Aircraft"
+//! shape = [".#.", "###", ".#.", ".#."]
+//! ```
+//!
+//! A ship may be laid out in any rotation or reflection of its shape; see
+//! [`Shape::orientations`].
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// The cells a ship covers, relative to the top-left of its bounding box.
+///
+/// Two shapes are equal only if they are laid out the same way; use
+/// [`Shape::orientations`] to compare them up to rotation and reflection.
+///
+/// # Example
+/// ```
+/// use battleship_config::Shape;
+/// let ell = Shape::parse(&["#.", "#.", "##"]).unwrap();
+/// assert_eq!(ell.len(), 4);
+/// assert_eq!((ell.height(), ell.width()), (3, 2));
+/// assert_eq!(ell.orientations().len(), 8);
+/// assert_eq!(Shape::line(3).orientations().len(), 2);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "Vec<String>", into = "Vec<String>")]
+pub struct Shape {
+    /// Occupied `(row, col)` offsets, sorted, with the smallest row and the
+    /// smallest column both zero
+    cells: Vec<(usize, usize)>,
+}
+
+impl Shape {
+    /// A straight, horizontal ship of `length` cells.
+    ///
+    /// # Panics
+    /// Panics if `length` is zero.
+    pub fn line(length: usize) -> Self {
+        assert!(length > 0, "a ship needs at least one cell");
+        Self {
+            cells: (0..length).map(|col| (0, col)).collect(),
+        }
+    }
+
+    /// Builds a shape from the cells it covers, wherever they lie.
+    ///
+    /// # Arguments
+    /// * `cells` - `(row, col)` of every cell; duplicates are ignored
+    ///
+    /// # Errors
+    /// Returns `ShapeError::Empty` if no cells are given and
+    /// `ShapeError::Disconnected` if the cells do not form one piece joined
+    /// along their edges.
+    pub fn from_cells(cells: impl IntoIterator<Item = (usize, usize)>) -> Result<Self, ShapeError> {
+        let mut cells: Vec<(usize, usize)> = cells.into_iter().collect();
+        let top = cells
+            .iter()
+            .map(|&(r, _)| r)
+            .min()
+            .ok_or(ShapeError::Empty)?;
+        let left = cells
+            .iter()
+            .map(|&(_, c)| c)
+            .min()
+            .ok_or(ShapeError::Empty)?;
+        for cell in cells.iter_mut() {
+            *cell = (cell.0 - top, cell.1 - left);
+        }
+        cells.sort_unstable();
+        cells.dedup();
+        let shape = Self { cells };
+        if !shape.is_connected() {
+            return Err(ShapeError::Disconnected);
+        }
+        Ok(shape)
+    }
+
+    /// Parses a shape drawn as rows of `#` (ship) and `.` (gap).
+    ///
+    /// Spaces count as gaps, so rows may be of different lengths.
+    ///
+    /// # Errors
+    /// Fails like [`Shape::from_cells`], or with `ShapeError::BadChar` for
+    /// any character other than `#`, `.` or a space.
+    pub fn parse<S: AsRef<str>>(rows: &[S]) -> Result<Self, ShapeError> {
+        let mut cells = Vec::new();
+        for (r, row) in rows.iter().enumerate() {
+            for (c, ch) in row.as_ref().chars().enumerate() {
+                match ch {
+                    '#' => cells.push((r, c)),
+                    '.' | ' ' => {}
+                    other => return Err(ShapeError::BadChar(other)),
+                }
+            }
+        }
+        Self::from_cells(cells)
+    }
+
+    /// `(row, col)` offsets of the cells, sorted.
+    pub fn cells(&self) -> &[(usize, usize)] {
+        &self.cells
+    }
+
+    /// Number of cells the shape covers.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Always false; every shape covers at least one cell.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Number of rows the shape spans.
+    pub fn height(&self) -> usize {
+        self.cells.iter().map(|&(r, _)| r + 1).max().unwrap_or(0)
+    }
+
+    /// Number of columns the shape spans.
+    pub fn width(&self) -> usize {
+        self.cells.iter().map(|&(_, c)| c + 1).max().unwrap_or(0)
+    }
+
+    /// Returns true for a straight ship, horizontal or vertical.
+    pub fn is_line(&self) -> bool {
+        self.height() == 1 || self.width() == 1
+    }
+
+    /// The shape turned a quarter turn clockwise.
+    pub fn rotated(&self) -> Self {
+        let height = self.height();
+        self.remap(|(r, c)| (c, height - 1 - r))
+    }
+
+    /// The shape mirrored left to right.
+    pub fn reflected(&self) -> Self {
+        let width = self.width();
+        self.remap(|(r, c)| (r, width - 1 - c))
+    }
+
+    /// Every distinct way the shape can be laid on the board: its four
+    /// rotations and their mirror images, without repeats.
+    ///
+    /// The shape itself comes first, followed by its quarter turn, so a
+    /// straight ship gives horizontal then vertical.
+    pub fn orientations(&self) -> Vec<Shape> {
+        let mut out: Vec<Shape> = Vec::with_capacity(8);
+        for start in [self.clone(), self.reflected()] {
+            let mut shape = start;
+            for _ in 0..4 {
+                let next = shape.rotated();
+                if !out.contains(&shape) {
+                    out.push(shape);
+                }
+                shape = next;
+            }
+        }
+        out
+    }
+
+    /// Returns true if `other` is this shape in some rotation or reflection.
+    pub fn matches(&self, other: &Shape) -> bool {
+        self.len() == other.len() && self.orientations().contains(other)
+    }
+
+    /// Draws the shape as rows of `#` and `.`, the form [`Shape::parse`]
+    /// reads.
+    pub fn rows(&self) -> Vec<String> {
+        let mut rows = vec![vec!['.'; self.width()]; self.height()];
+        for &(r, c) in &self.cells {
+            rows[r][c] = '#';
+        }
+        rows.into_iter()
+            .map(|row| row.into_iter().collect())
+            .collect()
+    }
+
+    /// Applies `f` to every cell; `f` must keep the cells within the
+    /// bounding box.
+    fn remap(&self, f: impl Fn((usize, usize)) -> (usize, usize)) -> Self {
+        let mut cells: Vec<_> = self.cells.iter().map(|&cell| f(cell)).collect();
+        cells.sort_unstable();
+        Self { cells }
+    }
+
+    /// Returns true if every cell can be reached from the first through
+    /// edge-sharing neighbours.
+    fn is_connected(&self) -> bool {
+        let mut seen = vec![false; self.cells.len()];
+        let mut stack = vec![0];
+        seen[0] = true;
+        while let Some(i) = stack.pop() {
+            let (r, c) = self.cells[i];
+            for (j, &(r2, c2)) in self.cells.iter().enumerate() {
+                if !seen[j] && r.abs_diff(r2) + c.abs_diff(c2) == 1 {
+                    seen[j] = true;
+                    stack.push(j);
+                }
+            }
+        }
+        seen.into_iter().all(|s| s)
+    }
+}
+
+impl fmt::Display for Shape {
+    /// Writes the rows separated by `/`, e.g. `.#./###`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.rows().join("/"))
+    }
+}
+
+impl FromStr for Shape {
+    type Err = ShapeError;
+
+    /// Parses rows separated by `/`, as written by the `Display` impl.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rows: Vec<&str> = s.trim().split('/').collect();
+        Self::parse(&rows)
+    }
+}
+
+impl TryFrom<Vec<String>> for Shape {
+    type Error = ShapeError;
+
+    fn try_from(rows: Vec<String>) -> Result<Self, Self::Error> {
+        Self::parse(&rows)
+    }
+}
+
+impl From<Shape> for Vec<String> {
+    fn from(shape: Shape) -> Self {
+        shape.rows()
+    }
+}
+
+/// Reasons a drawing does not describe a ship.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ShapeError {
+    /// The drawing has no ship cells
+    Empty,
+    /// The ship cells do not form one piece
+    Disconnected,
+    /// The drawing uses a character other than `#`, `.` or a space
+    BadChar(char),
+}
+
+impl fmt::Display for ShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShapeError::Empty => write!(f, "a ship shape needs at least one '#' cell"),
+            ShapeError::Disconnected => {
+                write!(
+                    f,
+                    "the cells of a ship shape must be joined along their edges"
+                )
+            }
+            ShapeError::BadChar(ch) => {
+                write!(f, "unexpected {:?} in a ship shape; use '#' and '.'", ch)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ShapeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orientations_cover_rotations_and_reflections() {
+        let count = |rows: &[&str]| Shape::parse(rows).unwrap().orientations().len();
+        assert_eq!(count(&["#"]), 1);
+        assert_eq!(count(&["##", "##"]), 1);
+        assert_eq!(count(&[".#.", "###", ".#."]), 1);
+        assert_eq!(count(&["####"]), 2);
+        assert_eq!(count(&["###", ".#."]), 4);
+        assert_eq!(count(&[".##", "##."]), 4);
+        assert_eq!(count(&["#..", "###"]), 8);
+
+        let tee = Shape::parse(&["###", ".#."]).unwrap();
+        assert_eq!(tee.rotated().rows(), [".#", "##", ".#"]);
+        assert_eq!(tee.orientations()[0], tee);
+        assert!(tee.matches(&Shape::parse(&[".#.", "###"]).unwrap()));
+        assert!(!tee.matches(&Shape::parse(&["#..", "###"]).unwrap()));
+    }
+
+    #[test]
+    fn drawings_round_trip() {
+        let plane: Shape = ".#./###/.#./.#.".parse().unwrap();
+        assert_eq!(plane.len(), 6);
+        assert_eq!(plane.to_string(), ".#./###/.#./.#.");
+        let json = serde_json::to_string(&plane).unwrap();
+        assert_eq!(
+            json,
+            serde_json::to_string(&[".#.", "###", ".#.", ".#."]).unwrap()
+        );
+        assert_eq!(serde_json::from_str::<Shape>(&json).unwrap(), plane);
+        // Leading gaps are trimmed away.
+        assert_eq!(Shape::parse(&["..", ".#"]).unwrap(), Shape::line(1));
+    }
+
+    #[test]
+    fn bad_drawings_are_rejected() {
+        assert_eq!(Shape::parse(&["..", ".."]), Err(ShapeError::Empty));
+        assert_eq!(Shape::parse(&["#.#"]), Err(ShapeError::Disconnected));
+        assert_eq!(Shape::parse(&["#.", ".#"]), Err(ShapeError::Disconnected));
+        assert_eq!(Shape::parse(&["#x"]), Err(ShapeError::BadChar('x')));
+    }
+}
@@ -0,0 +1,223 @@
+//! Maps with islands.
+//!
+//! A [`Terrain`] marks the cells of a board that are land rather than open
+//! water. No ship may be placed on an island and nobody fires at one; both
+//! players see every island from the start of the game. In settings files a
+//! map is drawn as rows of `.` for water and `#` for land, and sets the
+//! board's size:
+//!
+//! ```toml
+//! [board]
+//! map = [
+//!     "..........",
+//!     "...##.....",
+//!     "...##.....",
+//!     "..........",
+//! ]
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Board dimensions together with the island cells on the board.
+///
+/// # Example
+/// ```
+/// use battleship_config::Terrain;
+/// let map = Terrain::parse(&["....", ".##.", "...."]).unwrap();
+/// assert_eq!((map.rows(), map.cols()), (3, 4));
+/// assert_eq!(map.islands(), [(1, 1), (1, 2)]);
+/// assert_eq!(map.water_cells(), 10);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "Vec<String>", into = "Vec<String>")]
+pub struct Terrain {
+    rows: usize,
+    cols: usize,
+    /// Island `(row, col)` cells, sorted
+    islands: Vec<(usize, usize)>,
+}
+
+impl Terrain {
+    /// An all-water board of the given size.
+    pub fn open(rows: usize, cols: usize) -> Self {
+        Self {
+            rows,
+            cols,
+            islands: Vec::new(),
+        }
+    }
+
+    /// Parses a map drawn as rows of `.` (water) and `#` (island).
+    ///
+    /// # Errors
+    /// Returns `TerrainError::Empty` for a map with no cells,
+    /// `TerrainError::Ragged` if the rows differ in length and
+    /// `TerrainError::BadChar` for any character other than `.` or `#`.
+    pub fn parse<S: AsRef<str>>(rows: &[S]) -> Result<Self, TerrainError> {
+        let cols = rows.first().map_or(0, |row| row.as_ref().chars().count());
+        if cols == 0 {
+            return Err(TerrainError::Empty);
+        }
+        let mut islands = Vec::new();
+        for (r, row) in rows.iter().enumerate() {
+            let row = row.as_ref();
+            let found = row.chars().count();
+            if found != cols {
+                return Err(TerrainError::Ragged {
+                    row: r,
+                    expected: cols,
+                    found,
+                });
+            }
+            for (c, ch) in row.chars().enumerate() {
+                match ch {
+                    '#' => islands.push((r, c)),
+                    '.' => {}
+                    other => return Err(TerrainError::BadChar(other)),
+                }
+            }
+        }
+        Ok(Self {
+            rows: rows.len(),
+            cols,
+            islands,
+        })
+    }
+
+    /// Number of rows on the map.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Number of columns on the map.
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// `(row, col)` of every island cell, sorted.
+    pub fn islands(&self) -> &[(usize, usize)] {
+        &self.islands
+    }
+
+    /// Number of cells that are open water.
+    pub fn water_cells(&self) -> usize {
+        self.rows * self.cols - self.islands.len()
+    }
+
+    /// Draws the map as rows of `.` and `#`, the form [`Terrain::parse`]
+    /// reads.
+    pub fn map_rows(&self) -> Vec<String> {
+        let mut rows = vec![vec!['.'; self.cols]; self.rows];
+        for &(r, c) in &self.islands {
+            rows[r][c] = '#';
+        }
+        rows.into_iter()
+            .map(|row| row.into_iter().collect())
+            .collect()
+    }
+}
+
+impl fmt::Display for Terrain {
+    /// Writes the rows separated by `/`, e.g. `..../.##./....`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.map_rows().join("/"))
+    }
+}
+
+impl FromStr for Terrain {
+    type Err = TerrainError;
+
+    /// Parses rows separated by `/`, as written by the `Display` impl.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rows: Vec<&str> = s.trim().split('/').collect();
+        Self::parse(&rows)
+    }
+}
+
+impl TryFrom<Vec<String>> for Terrain {
+    type Error = TerrainError;
+
+    fn try_from(rows: Vec<String>) -> Result<Self, Self::Error> {
+        Self::parse(&rows)
+    }
+}
+
+impl From<Terrain> for Vec<String> {
+    fn from(terrain: Terrain) -> Self {
+        terrain.map_rows()
+    }
+}
+
+/// Reasons a drawing does not describe a map.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TerrainError {
+    /// The map has no cells
+    Empty,
+    /// A row is a different length from the first
+    Ragged {
+        /// Index of the offending row
+        row: usize,
+        /// Length of the first row
+        expected: usize,
+        /// Length of the offending row
+        found: usize,
+    },
+    /// The map uses a character other than `.` or `#`
+    BadChar(char),
+}
+
+impl fmt::Display for TerrainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TerrainError::Empty => write!(f, "a map needs at least one cell"),
+            TerrainError::Ragged {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "map row {} has {} cells but the first row has {}",
+                row + 1,
+                found,
+                expected
+            ),
+            TerrainError::BadChar(ch) => {
+                write!(f, "unexpected {:?} in a map; use '.' and '#'", ch)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TerrainError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_round_trip() {
+        let map: Terrain = "#../.../..#".parse().unwrap();
+        assert_eq!(map.islands(), [(0, 0), (2, 2)]);
+        assert_eq!(map.to_string(), "#../.../..#");
+        let json = serde_json::to_string(&map).unwrap();
+        assert_eq!(serde_json::from_str::<Terrain>(&json).unwrap(), map);
+        assert_eq!(Terrain::open(2, 3).to_string(), ".../...");
+    }
+
+    #[test]
+    fn bad_maps_are_rejected() {
+        assert_eq!(Terrain::parse::<&str>(&[]), Err(TerrainError::Empty));
+        assert_eq!(Terrain::parse(&[""]), Err(TerrainError::Empty));
+        assert_eq!(
+            Terrain::parse(&["...", ".."]),
+            Err(TerrainError::Ragged {
+                row: 1,
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(Terrain::parse(&[".~."]), Err(TerrainError::BadChar('~')));
+    }
+}
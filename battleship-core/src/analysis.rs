@@ -0,0 +1,229 @@
+//! Feasibility and density analysis of a board and fleet.
+//!
+//! [`FleetReport::analyze`] answers the questions worth asking before a
+//! custom configuration is used in earnest: can the fleet be laid out at all,
+//! how many layouts are there, how crowded is the board, and how long does a
+//! game last against an opponent who fires at random.
+
+use crate::bitboard::Bitboard;
+use crate::board::Board;
+use crate::placement::{self, Candidate};
+use crate::rng;
+use battleship_config::{ConfigError, GameConfig, Shape};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Partial layouts examined before exact counting gives way to an estimate.
+const COUNT_BUDGET: usize = 20_000;
+
+/// Random descents averaged when estimating the number of layouts.
+const ESTIMATE_SAMPLES: usize = 2_000;
+
+/// Number of distinct fleet layouts a configuration allows.
+///
+/// Ships of the same class are interchangeable, so swapping two of them does
+/// not make a new layout.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LayoutCount {
+    /// Counted exactly
+    Exact(u128),
+    /// Too many to count; estimated by random sampling
+    Estimated(f64),
+}
+
+impl LayoutCount {
+    /// The count as a float, exact or not.
+    pub fn approx(&self) -> f64 {
+        match *self {
+            LayoutCount::Exact(n) => n as f64,
+            LayoutCount::Estimated(n) => n,
+        }
+    }
+}
+
+impl fmt::Display for LayoutCount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayoutCount::Exact(n) => write!(f, "{}", n),
+            LayoutCount::Estimated(n) => write!(f, "about {:.3e}", n),
+        }
+    }
+}
+
+/// What a board and fleet allow, computed by [`FleetReport::analyze`].
+///
+/// # Example
+/// ```
+/// use battleship_core::{FleetReport, GameConfig, LayoutCount, ShipClass};
+/// // Two length-4 ships fill a 2x4 board one row each.
+/// let config = GameConfig::with_classes(2, 4, vec![ShipClass::new("Cruiser", 4, 2)]);
+/// let report = FleetReport::analyze(&config).unwrap();
+/// assert!(report.is_feasible());
+/// assert_eq!(report.layouts, LayoutCount::Exact(1));
+/// assert_eq!(report.density, 1.0);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct FleetReport {
+    /// Number of open-water cells on the board
+    pub board_cells: usize,
+    /// Number of cells the fleet occupies
+    pub fleet_cells: usize,
+    /// Fraction of the board the fleet occupies
+    pub density: f64,
+    /// Number of distinct legal layouts of the fleet
+    pub layouts: LayoutCount,
+    /// Expected number of shots a player firing at random unguessed cells
+    /// needs to sink the whole fleet
+    pub expected_random_shots: f64,
+}
+
+impl FleetReport {
+    /// Analyses the board and fleet of a configuration.
+    ///
+    /// Layouts are counted exactly when that is cheap, as it is for small or
+    /// crowded boards, and estimated otherwise. An impossible fleet is
+    /// always reported as `LayoutCount::Exact(0)`. Estimates are drawn from
+    /// a fixed seed, so the same configuration always gives the same report.
+    ///
+    /// # Arguments
+    /// * `config` - Board size, fleet and adjacency rule to analyse
+    ///
+    /// # Errors
+    /// Returns any error from [`GameConfig::validate`].
+    pub fn analyze(config: &GameConfig) -> Result<Self, ConfigError> {
+        config.validate()?;
+        let board = Board::with_config(config);
+        let blocked = Bitboard::new(config.rows, config.cols);
+        let candidates: Vec<Vec<Candidate>> = config
+            .ship_shapes()
+            .iter()
+            .map(|(_, shape)| board.candidate_placements(shape, &blocked))
+            .collect();
+
+        // Layouts are counted with every ship distinct; each unordered layout
+        // appears once per ordering of the ships within a class.
+        let mut class_sizes: HashMap<(&str, Shape), u32> = HashMap::new();
+        for class in &config.ships {
+            *class_sizes.entry((&class.name, class.shape())).or_default() += class.count as u32;
+        }
+        let orderings: f64 = class_sizes
+            .values()
+            .map(|&n| (1..=n).map(f64::from).product::<f64>())
+            .product();
+
+        let layouts = if !placement::exists(&candidates, &blocked) {
+            LayoutCount::Exact(0)
+        } else if let Some(n) = placement::count(&candidates, &blocked, COUNT_BUDGET) {
+            LayoutCount::Exact(n / orderings as u128)
+        } else {
+            let mut rng = rng::seeded(0);
+            let n = placement::estimate_count(&candidates, &blocked, ESTIMATE_SAMPLES, &mut rng);
+            LayoutCount::Estimated(n / orderings)
+        };
+
+        let board_cells = config.water_cells();
+        let fleet_cells: usize = config.ships.iter().map(|c| c.length * c.count).sum();
+        Ok(Self {
+            board_cells,
+            fleet_cells,
+            density: fleet_cells as f64 / board_cells as f64,
+            layouts,
+            expected_random_shots: expected_random_shots(board_cells, fleet_cells),
+        })
+    }
+
+    /// Returns true if at least one legal layout exists.
+    pub fn is_feasible(&self) -> bool {
+        self.layouts != LayoutCount::Exact(0)
+    }
+}
+
+impl fmt::Display for FleetReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Fleet covers {} of {} cells ({:.1}%)",
+            self.fleet_cells,
+            self.board_cells,
+            self.density * 100.0
+        )?;
+        if self.is_feasible() {
+            writeln!(f, "Distinct layouts: {}", self.layouts)?;
+        } else {
+            writeln!(f, "No legal layout exists")?;
+        }
+        write!(
+            f,
+            "Expected game length against random shots: {:.1}",
+            self.expected_random_shots
+        )
+    }
+}
+
+/// Expected number of shots to hit all `ship_cells` of a `board_cells` board
+/// when every shot is drawn uniformly from the cells not yet fired at.
+///
+/// The game ends on the last ship cell in a random ordering of the board,
+/// whose expected position is `k(n + 1)/(k + 1)`.
+fn expected_random_shots(board_cells: usize, ship_cells: usize) -> f64 {
+    let (n, k) = (board_cells as f64, ship_cells as f64);
+    k * (n + 1.0) / (k + 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AdjacencyRule, RuleSet, ShipClass};
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn classic_game_statistics() {
+        let report = FleetReport::analyze(&GameConfig::default()).unwrap();
+        assert_eq!((report.fleet_cells, report.board_cells), (17, 100));
+        assert_approx_eq!(report.density, 0.17);
+        assert_approx_eq!(report.expected_random_shots, 17.0 * 101.0 / 18.0);
+        // The classic fleet has about 3.0e10 layouts.
+        let LayoutCount::Estimated(n) = report.layouts else {
+            panic!("expected an estimate, got {:?}", report.layouts);
+        };
+        assert!((2.5e10..3.5e10).contains(&n), "estimated {:e} layouts", n);
+    }
+
+    #[test]
+    fn interchangeable_ships_are_counted_once() {
+        // Four length-4 ships fill a 4x4 board as four rows or four columns.
+        let distinct = GameConfig::square(4, &[("A", 4), ("B", 4), ("C", 4), ("D", 4)]);
+        let report = FleetReport::analyze(&distinct).unwrap();
+        assert_eq!(report.layouts, LayoutCount::Exact(48));
+
+        let same = GameConfig::with_classes(4, 4, vec![ShipClass::new("Cruiser", 4, 4)]);
+        let report = FleetReport::analyze(&same).unwrap();
+        assert_eq!(report.layouts, LayoutCount::Exact(2));
+        assert_approx_eq!(report.expected_random_shots, 16.0);
+    }
+
+    #[test]
+    fn impossible_fleets_are_reported() {
+        // Three length-3 ships fit on a 3x3 board only if they may touch.
+        let config = GameConfig::with_classes(3, 3, vec![ShipClass::new("Cruiser", 3, 3)]);
+        assert!(FleetReport::analyze(&config).unwrap().is_feasible());
+        let apart = config.with_adjacency(AdjacencyRule::NoTouch);
+        let report = FleetReport::analyze(&apart).unwrap();
+        assert!(!report.is_feasible());
+        assert!(report.to_string().contains("No legal layout exists"));
+
+        let too_big = GameConfig::square(3, &[("Carrier", 5)]);
+        assert!(matches!(
+            FleetReport::analyze(&too_big),
+            Err(ConfigError::ShipTooLong { .. })
+        ));
+    }
+
+    #[test]
+    fn every_rule_set_is_feasible() {
+        for rules in RuleSet::ALL {
+            let report = FleetReport::analyze(&rules.config()).unwrap();
+            assert!(report.is_feasible(), "{} has no layout", rules);
+        }
+    }
+}
@@ -0,0 +1,411 @@
+use battleship_common::Coord;
+use std::fmt;
+use std::ops::{BitAnd, BitOr, Not, Sub};
+
+/// Number of 64-bit words stored inline before falling back to the heap.
+const INLINE_WORDS: usize = 4;
+
+/// Backing storage for a [`Bitboard`].
+///
+/// Boards of up to `INLINE_WORDS * 64` cells (16×16) live entirely on the
+/// stack; larger boards spill to a boxed slice.
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum Words {
+    Inline([u64; INLINE_WORDS]),
+    Heap(Box<[u64]>),
+}
+
+impl Words {
+    fn zeroed(n_words: usize) -> Self {
+        if n_words <= INLINE_WORDS {
+            Words::Inline([0; INLINE_WORDS])
+        } else {
+            Words::Heap(vec![0; n_words].into_boxed_slice())
+        }
+    }
+
+    fn as_slice(&self) -> &[u64] {
+        match self {
+            Words::Inline(w) => w,
+            Words::Heap(w) => w,
+        }
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [u64] {
+        match self {
+            Words::Inline(w) => w,
+            Words::Heap(w) => w,
+        }
+    }
+}
+
+/// Set of cells on a board, stored as one bit per cell.
+///
+/// Bit `row * cols + col` is set for each cell in the set. Set operations
+/// between bitboards are word-wise and allocation-free for boards of up to
+/// 256 cells, which keeps the hot loops of simulations and the AI cheap.
+///
+/// Both operands of a binary operation must have the same dimensions.
+///
+/// # Example
+/// ```
+/// use battleship_core::Bitboard;
+/// let mut hits = Bitboard::new(10, 10);
+/// hits.insert((0, 0));
+/// hits.insert((0, 1));
+/// let ship = Bitboard::from_coords(10, 10, [(0, 0), (0, 1), (0, 2)]);
+/// assert!(hits.is_subset(&ship));
+/// assert_eq!(ship.difference(&hits).iter().collect::<Vec<_>>(), vec![(0, 2)]);
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Bitboard {
+    rows: usize,
+    cols: usize,
+    words: Words,
+}
+
+impl Bitboard {
+    /// Creates an empty bitboard for a board of the given dimensions.
+    pub fn new(rows: usize, cols: usize) -> Self {
+        Self {
+            rows,
+            cols,
+            words: Words::zeroed((rows * cols).div_ceil(64)),
+        }
+    }
+
+    /// Creates a bitboard with every cell of the board set.
+    pub fn full(rows: usize, cols: usize) -> Self {
+        let mut board = Self::new(rows, cols);
+        let n_cells = rows * cols;
+        for (i, word) in board.words.as_mut_slice().iter_mut().enumerate() {
+            let start = i * 64;
+            if start + 64 <= n_cells {
+                *word = u64::MAX;
+            } else if start < n_cells {
+                *word = (1u64 << (n_cells - start)) - 1;
+            }
+        }
+        board
+    }
+
+    /// Creates a bitboard containing the given coordinates.
+    ///
+    /// # Panics
+    /// Panics if any coordinate lies outside the board.
+    pub fn from_coords<C: Into<Coord>>(
+        rows: usize,
+        cols: usize,
+        coords: impl IntoIterator<Item = C>,
+    ) -> Self {
+        let mut board = Self::new(rows, cols);
+        for c in coords {
+            let c = c.into();
+            assert!(
+                c.in_bounds(rows, cols),
+                "{} is outside a {}x{} board",
+                c,
+                rows,
+                cols
+            );
+            board.insert(c);
+        }
+        board
+    }
+
+    /// Number of rows on the board this bitboard covers.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Number of columns on the board this bitboard covers.
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    fn index(&self, coord: Coord) -> Option<usize> {
+        coord
+            .in_bounds(self.rows, self.cols)
+            .then_some(coord.row * self.cols + coord.col)
+    }
+
+    /// Returns true if the cell is in the set. Cells off the board are never
+    /// in the set.
+    pub fn contains(&self, coord: impl Into<Coord>) -> bool {
+        match self.index(coord.into()) {
+            Some(i) => (self.words.as_slice()[i / 64] >> (i % 64)) & 1 == 1,
+            None => false,
+        }
+    }
+
+    /// Adds a cell to the set, returning true if it was not already present.
+    ///
+    /// # Panics
+    /// Panics if the cell lies outside the board.
+    pub fn insert(&mut self, coord: impl Into<Coord>) -> bool {
+        let coord = coord.into();
+        let i = self
+            .index(coord)
+            .unwrap_or_else(|| panic!("{} is outside a {}x{} board", coord, self.rows, self.cols));
+        let word = &mut self.words.as_mut_slice()[i / 64];
+        let bit = 1u64 << (i % 64);
+        let added = *word & bit == 0;
+        *word |= bit;
+        added
+    }
+
+    /// Removes a cell from the set, returning true if it was present.
+    pub fn remove(&mut self, coord: impl Into<Coord>) -> bool {
+        match self.index(coord.into()) {
+            Some(i) => {
+                let word = &mut self.words.as_mut_slice()[i / 64];
+                let bit = 1u64 << (i % 64);
+                let present = *word & bit != 0;
+                *word &= !bit;
+                present
+            }
+            None => false,
+        }
+    }
+
+    /// Removes every cell from the set.
+    pub fn clear(&mut self) {
+        self.words.as_mut_slice().fill(0);
+    }
+
+    /// Number of cells in the set.
+    pub fn len(&self) -> usize {
+        self.words
+            .as_slice()
+            .iter()
+            .map(|w| w.count_ones() as usize)
+            .sum()
+    }
+
+    /// Returns true if the set holds no cells.
+    pub fn is_empty(&self) -> bool {
+        self.words.as_slice().iter().all(|&w| w == 0)
+    }
+
+    /// Iterates over the cells in the set in row-major order.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            board: self,
+            word_index: 0,
+            current: self.words.as_slice().first().copied().unwrap_or(0),
+        }
+    }
+
+    fn zip_with(&self, other: &Bitboard, f: impl Fn(u64, u64) -> u64) -> Bitboard {
+        self.assert_same_dims(other);
+        let mut out = self.clone();
+        for (a, &b) in out
+            .words
+            .as_mut_slice()
+            .iter_mut()
+            .zip(other.words.as_slice())
+        {
+            *a = f(*a, b);
+        }
+        out
+    }
+
+    fn assert_same_dims(&self, other: &Bitboard) {
+        assert!(
+            self.rows == other.rows && self.cols == other.cols,
+            "bitboard dimensions differ: {}x{} vs {}x{}",
+            self.rows,
+            self.cols,
+            other.rows,
+            other.cols
+        );
+    }
+
+    /// Cells in either set.
+    pub fn union(&self, other: &Bitboard) -> Bitboard {
+        self.zip_with(other, |a, b| a | b)
+    }
+
+    /// Cells in both sets.
+    pub fn intersection(&self, other: &Bitboard) -> Bitboard {
+        self.zip_with(other, |a, b| a & b)
+    }
+
+    /// Cells in `self` but not in `other`.
+    pub fn difference(&self, other: &Bitboard) -> Bitboard {
+        self.zip_with(other, |a, b| a & !b)
+    }
+
+    /// Returns true if every cell of `self` is also in `other`.
+    pub fn is_subset(&self, other: &Bitboard) -> bool {
+        self.assert_same_dims(other);
+        self.words
+            .as_slice()
+            .iter()
+            .zip(other.words.as_slice())
+            .all(|(&a, &b)| a & !b == 0)
+    }
+
+    /// Returns true if the two sets share no cells.
+    pub fn is_disjoint(&self, other: &Bitboard) -> bool {
+        self.assert_same_dims(other);
+        self.words
+            .as_slice()
+            .iter()
+            .zip(other.words.as_slice())
+            .all(|(&a, &b)| a & b == 0)
+    }
+
+    /// Returns the set grown by the given `(row, col)` offsets: every cell of
+    /// the set plus every on-board cell reachable by one of the offsets.
+    ///
+    /// # Example
+    /// ```
+    /// use battleship_core::{AdjacencyRule, Bitboard};
+    /// let ship = Bitboard::from_coords(10, 10, [(0, 0), (0, 1)]);
+    /// let zone = ship.expand(AdjacencyRule::NoTouch.offsets());
+    /// assert_eq!(zone.len(), 6);
+    /// ```
+    pub fn expand(&self, offsets: &[(isize, isize)]) -> Bitboard {
+        let mut out = self.clone();
+        for (row, col) in self.iter() {
+            for &(dr, dc) in offsets {
+                let (Some(r), Some(c)) = (row.checked_add_signed(dr), col.checked_add_signed(dc))
+                else {
+                    continue;
+                };
+                if r < self.rows && c < self.cols {
+                    out.insert((r, c));
+                }
+            }
+        }
+        out
+    }
+
+    /// Adds every cell of `other` to `self`.
+    pub fn union_with(&mut self, other: &Bitboard) {
+        self.assert_same_dims(other);
+        for (a, &b) in self
+            .words
+            .as_mut_slice()
+            .iter_mut()
+            .zip(other.words.as_slice())
+        {
+            *a |= b;
+        }
+    }
+}
+
+impl BitOr for &Bitboard {
+    type Output = Bitboard;
+
+    fn bitor(self, rhs: &Bitboard) -> Bitboard {
+        self.union(rhs)
+    }
+}
+
+impl BitAnd for &Bitboard {
+    type Output = Bitboard;
+
+    fn bitand(self, rhs: &Bitboard) -> Bitboard {
+        self.intersection(rhs)
+    }
+}
+
+impl Sub for &Bitboard {
+    type Output = Bitboard;
+
+    fn sub(self, rhs: &Bitboard) -> Bitboard {
+        self.difference(rhs)
+    }
+}
+
+impl Not for &Bitboard {
+    type Output = Bitboard;
+
+    fn not(self) -> Bitboard {
+        Bitboard::full(self.rows, self.cols).difference(self)
+    }
+}
+
+impl fmt::Debug for Bitboard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<'a> IntoIterator for &'a Bitboard {
+    type Item = (usize, usize);
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+/// Iterator over the `(row, col)` cells of a [`Bitboard`].
+pub struct Iter<'a> {
+    board: &'a Bitboard,
+    word_index: usize,
+    current: u64,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<(usize, usize)> {
+        let words = self.board.words.as_slice();
+        while self.current == 0 {
+            self.word_index += 1;
+            if self.word_index >= words.len() {
+                return None;
+            }
+            self.current = words[self.word_index];
+        }
+        let bit = self.current.trailing_zeros() as usize;
+        self.current &= self.current - 1;
+        let i = self.word_index * 64 + bit;
+        Some((i / self.board.cols, i % self.board.cols))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full_board_covers_every_cell_once() {
+        for (rows, cols) in [(1, 1), (8, 8), (10, 10), (12, 8), (16, 16), (26, 30)] {
+            let full = Bitboard::full(rows, cols);
+            assert_eq!(full.len(), rows * cols);
+            assert!((!&full).is_empty());
+            let cells: Vec<_> = full.iter().collect();
+            assert_eq!(cells.first(), Some(&(0, 0)));
+            assert_eq!(cells.last(), Some(&(rows - 1, cols - 1)));
+        }
+    }
+
+    #[test]
+    fn heap_backed_boards_behave_like_inline_ones() {
+        let mut b = Bitboard::new(30, 30);
+        assert!(b.insert((29, 29)));
+        assert!(!b.insert((29, 29)));
+        assert!(b.insert((0, 0)));
+        assert!(b.contains((29, 29)));
+        assert!(!b.contains((30, 0)));
+        assert_eq!(b.iter().collect::<Vec<_>>(), vec![(0, 0), (29, 29)]);
+        assert!(b.remove((0, 0)));
+        assert_eq!(b.len(), 1);
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = Bitboard::from_coords(3, 4, [(0, 0), (1, 1), (2, 3)]);
+        let b = Bitboard::from_coords(3, 4, [(1, 1), (2, 2)]);
+        assert_eq!((&a & &b).iter().collect::<Vec<_>>(), vec![(1, 1)]);
+        assert_eq!((&a | &b).len(), 4);
+        assert_eq!((&a - &b).iter().collect::<Vec<_>>(), vec![(0, 0), (2, 3)]);
+        assert!(!a.is_disjoint(&b));
+        assert!((&a & &b).is_subset(&a));
+    }
+}
@@ -1,26 +1,65 @@
+use crate::bitboard::Bitboard;
 use crate::constants::Cell;
 use crate::constants::GameplayError;
 use crate::constants::GuessError;
 use crate::constants::PlayerState;
 use crate::fleet::Fleet;
-use crate::ship::Ship;
+use crate::orientation::Orientation;
+use crate::placement::{self, Candidate, PlacementStrategy};
+use crate::ship::{Ship, ShipId};
+use crate::weapon::{self, Ping};
 use crate::GuessResult;
-use battleship_config::GRID_SIZE;
-use rand::{seq::IteratorRandom, thread_rng, Rng};
-use std::collections::HashSet;
+use battleship_common::Coord;
+use battleship_config::{AdjacencyRule, Arsenal, GameConfig, Shape, SunkAnnouncement};
+use rand::{seq::IteratorRandom, seq::SliceRandom, thread_rng, Rng};
 use std::fmt;
 
 // Represents the game board for Battleship, managing ship placement, guessing,
 // and game state tracking.
 pub struct Board {
-    /// Size of the grid (typically 10x10)
-    gridsize: usize,
+    /// Number of rows on the grid (typically 10)
+    rows: usize,
+    /// Number of columns on the grid (typically 10)
+    cols: usize,
     /// Collection of ships on the board
     fleet: Fleet,
-    /// Set of all valid coordinates on the board
-    coordinates: HashSet<(usize, usize)>,
+    /// Set of all open-water coordinates on the board
+    coordinates: Bitboard,
+    /// Island cells, where no ship lies and nobody fires
+    islands: Bitboard,
     /// Set of coordinates that have been guessed
-    guessed: HashSet<(usize, usize)>,
+    guessed: Bitboard,
+    /// Ship cells struck by the owner's own mine blasts; kept out of the
+    /// shot log and hidden from the attacker until fired at
+    blasted: Bitboard,
+    /// Whether ships may touch each other
+    adjacency: AdjacencyRule,
+    /// What an attacker learns when one of these ships sinks
+    sunk_announcement: SunkAnnouncement,
+    /// Whether straight ships may also lie diagonally
+    diagonal_ships: bool,
+    /// Every shot taken so far, oldest first
+    history: Vec<Shot>,
+    /// Shots taken back with [`Board::undo_last_guess`], most recent last
+    undone: Vec<Coord>,
+    /// Sonar pings taken at the board, oldest first
+    pings: Vec<Ping>,
+    /// Whether the fleet layout has been locked in with
+    /// [`Board::confirm_fleet`]
+    confirmed: bool,
+}
+
+/// `(row, col)` offsets of a ship's cells from the top-left corner of its
+/// bounding box.
+type Offsets = Vec<(usize, usize)>;
+
+/// A shot fired at a board and what it did.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Shot {
+    /// Cell that was fired at
+    pub target: Coord,
+    /// Outcome of the shot
+    pub result: GuessResult,
 }
 
 // /// Represents the current state of a board, including ship positions,
@@ -60,6 +99,9 @@ impl Board {
     /// Creates a new empty game board with initialized coordinate space
     /// and empty fleet.
     ///
+    /// Uses the default [`GameConfig`]; see [`Board::with_config`] for other
+    /// board sizes and fleets.
+    ///
     /// # Returns
     /// * `Board` - A new board instance ready for ship placement
     ///
@@ -70,34 +112,62 @@ impl Board {
     /// assert_eq!(board.player_state(), PlayerState::Setup);
     /// ```
     pub fn new() -> Self {
-        let coordinates: HashSet<(usize, usize)> = (0..GRID_SIZE)
-            .flat_map(|row| (0..GRID_SIZE).map(move |col| (row, col)))
-            .collect();
+        Self::with_config(&GameConfig::default())
+    }
+
+    /// Creates a new empty game board sized and equipped according to the
+    /// given configuration.
+    ///
+    /// # Arguments
+    /// * `config` - Board size and fleet to use
+    ///
+    /// # Returns
+    /// * `Board` - A new board instance ready for ship placement
+    ///
+    /// # Example
+    /// ```
+    /// use battleship_common::BoardView;
+    /// use battleship_core::{Board, GameConfig};
+    /// let config = GameConfig::new(8, 12, &[("Cruiser", 3), ("Destroyer", 2)]);
+    /// let board = Board::with_config(&config);
+    /// assert_eq!((board.rows(), board.cols()), (8, 12));
+    /// assert_eq!(board.unguessed().len(), 96);
+    /// ```
+    pub fn with_config(config: &GameConfig) -> Self {
+        let (rows, cols) = (config.rows, config.cols);
+        let islands = Bitboard::from_coords(rows, cols, config.islands.iter().copied());
         Self {
-            gridsize: GRID_SIZE,
-            fleet: Fleet::new(),
-            coordinates: coordinates,
-            guessed: HashSet::with_capacity(GRID_SIZE * GRID_SIZE),
+            rows,
+            cols,
+            fleet: Fleet::from_config(config),
+            coordinates: Bitboard::full(rows, cols).difference(&islands),
+            islands,
+            guessed: Bitboard::new(rows, cols),
+            blasted: Bitboard::new(rows, cols),
+            adjacency: config.adjacency,
+            sunk_announcement: config.sunk_announcement,
+            diagonal_ships: config.diagonal_ships,
+            history: Vec::new(),
+            undone: Vec::new(),
+            pings: Vec::new(),
+            confirmed: false,
         }
     }
 
     /// Returns a reference to the set of guessed coordinates.
     ///
     /// # Returns
-    /// * `&HashSet<(usize,usize)>` - Reference to the set of guessed coordinates
-    pub fn guessed(&self) -> &HashSet<(usize, usize)> {
+    /// * `&Bitboard` - Reference to the set of guessed coordinates
+    pub fn guessed(&self) -> &Bitboard {
         &self.guessed
     }
 
     /// Returns a set of coordinates that haven't been guessed yet.
     ///
     /// # Returns
-    /// * `HashSet<(usize,usize)>` - Set of unguessed coordinates
-    pub fn unguessed(&self) -> HashSet<(usize, usize)> {
-        self.coordinates
-            .difference(&self.guessed)
-            .cloned()
-            .collect()
+    /// * `Bitboard` - Set of unguessed coordinates
+    pub fn unguessed(&self) -> Bitboard {
+        self.coordinates.difference(&self.guessed)
     }
 
     /// Returns coordinates where ships have been hit.
@@ -107,54 +177,163 @@ impl Board {
     /// * `sunk` - Include hits on ships that have been sunk
     ///
     /// # Returns
-    /// * `HashSet<(usize,usize)>` - Set of hit coordinates matching the criteria
-    pub fn hit_coords(&self, unsunk: bool, sunk: bool) -> HashSet<(usize, usize)> {
+    /// * `Bitboard` - Set of hit coordinates matching the criteria
+    pub fn hit_coords(&self, unsunk: bool, sunk: bool) -> Bitboard {
         self.fleet.hit_coords(unsunk, sunk)
     }
 
     /// Returns coordinates of missed shots.
     ///
     /// # Returns
-    /// * `HashSet<(usize,usize)>` - Set of coordinates where shots missed
-    pub fn miss_coords(&self) -> HashSet<(usize, usize)> {
-        self.guessed
-            .difference(&self.hit_coords(true, true))
-            .cloned()
-            .collect()
+    /// * `Bitboard` - Set of coordinates where shots missed
+    pub fn miss_coords(&self) -> Bitboard {
+        self.guessed.difference(&self.hit_coords(true, true))
     }
 
-    fn ship_coords(&self, unsunk: bool, sunk: bool) -> HashSet<(usize, usize)> {
+    fn ship_coords(&self, unsunk: bool, sunk: bool) -> Bitboard {
         self.fleet.ship_coords(unsunk, sunk)
     }
 
-    /// Calculates the coordinates a ship would occupy given a starting position,
-    /// length, and orientation.
+    /// Returns the adjacency rule ships on this board must obey.
+    ///
+    /// # Example
+    /// ```
+    /// use battleship_core::{AdjacencyRule, Board, Coord, GameConfig, Orientation, SHIPS};
+    /// let config = GameConfig::square(10, SHIPS).with_adjacency(AdjacencyRule::NoTouch);
+    /// let mut board = Board::with_config(&config);
+    /// board.place_ship("Carrier", Coord::new(0, 0), Orientation::Horizontal).unwrap();
+    /// // Diagonally touching the carrier's last cell is not allowed...
+    /// assert!(board.place_ship("Destroyer", Coord::new(1, 5), Orientation::Horizontal).is_err());
+    /// // ...but leaving a gap is.
+    /// assert!(board.place_ship("Destroyer", Coord::new(1, 6), Orientation::Horizontal).is_ok());
+    /// ```
+    pub fn adjacency(&self) -> AdjacencyRule {
+        self.adjacency
+    }
+
+    /// Returns what an attacker is told when one of this board's ships sinks.
+    pub fn sunk_announcement(&self) -> SunkAnnouncement {
+        self.sunk_announcement
+    }
+
+    /// Returns the island cells, where no ship lies and nobody fires.
+    ///
+    /// # Example
+    /// ```
+    /// use battleship_core::{Board, GameConfig, Terrain};
+    /// let map = Terrain::parse(&["....", ".#..", "...."]).unwrap();
+    /// let board = Board::with_config(&GameConfig::square(4, &[("Destroyer", 2)]).with_terrain(&map));
+    /// assert!(board.islands().contains((1, 1)));
+    /// assert_eq!(board.unguessed().len(), 11);
+    /// ```
+    pub fn islands(&self) -> &Bitboard {
+        &self.islands
+    }
+
+    /// Returns true if straight ships on this board may lie diagonally.
+    pub fn diagonal_ships(&self) -> bool {
+        self.diagonal_ships
+    }
+
+    /// Returns the cells holding this board's mines.
+    pub fn mines(&self) -> &Bitboard {
+        self.fleet.mines()
+    }
+
+    /// Returns how many mines this board hides once they are all laid.
+    pub fn mine_count(&self) -> usize {
+        self.fleet.mine_count()
+    }
+
+    /// Returns the cells no other ship may occupy given ships at `cells`:
+    /// the cells themselves plus any neighbours the adjacency rule forbids.
+    ///
+    /// # Example
+    /// ```
+    /// use battleship_core::{AdjacencyRule, Bitboard, Board, GameConfig, SHIPS};
+    /// let config = GameConfig::square(10, SHIPS).with_adjacency(AdjacencyRule::NoTouch);
+    /// let board = Board::with_config(&config);
+    /// let corner = Bitboard::from_coords(10, 10, [(0, 0)]);
+    /// assert_eq!(board.exclusion_zone(&corner).len(), 4);
+    /// ```
+    pub fn exclusion_zone(&self, cells: &Bitboard) -> Bitboard {
+        cells.expand(self.adjacency.offsets())
+    }
+
+    /// Calculates the coordinates a straight ship would occupy given a
+    /// starting position, length, and orientation.
     ///
     /// # Arguments
-    /// * `start` - Starting coordinate (row, col)
+    /// * `start` - Starting coordinate (row, col); the ship extends from here
+    ///   in the direction of [`Orientation::step`]
     /// * `length` - Length of the ship
-    /// * `horizontal` - If true, ship extends horizontally; if false, vertically
+    /// * `orientation` - Direction the ship lies in
     ///
     /// # Returns
-    /// * `HashSet<(usize,usize)>` - Set of coordinates the ship would occupy
+    /// * `Option<Bitboard>` - Set of coordinates the ship would occupy, or
+    ///   `None` if the ship would extend off the board
+    ///
+    /// # Example
+    /// ```
+    /// use battleship_core::{Board, Orientation};
+    /// let board = Board::new();
+    /// let up = board.calc_placement((4, 0), 3, Orientation::DiagonalUp).unwrap();
+    /// assert!(up.contains((4, 0)) && up.contains((3, 1)) && up.contains((2, 2)));
+    /// assert!(board.calc_placement((1, 0), 3, Orientation::DiagonalUp).is_none());
+    /// ```
     pub fn calc_placement(
         &self,
         start: (usize, usize),
         length: usize,
-        horizontal: bool,
-    ) -> HashSet<(usize, usize)> {
-        (0..length)
-            .map(|i| {
-                if horizontal {
-                    (start.0, start.1 + i)
-                } else {
-                    (start.0 + i, start.1)
-                }
-            })
-            .collect()
+        orientation: Orientation,
+    ) -> Option<Bitboard> {
+        let (row_step, col_step) = orientation.step();
+        let mut placement = Bitboard::new(self.rows, self.cols);
+        for i in 0..length as isize {
+            let row = start.0.checked_add_signed(i * row_step)?;
+            let col = start.1.checked_add_signed(i * col_step)?;
+            if row >= self.rows || col >= self.cols {
+                return None;
+            }
+            placement.insert((row, col));
+        }
+        Some(placement)
     }
 
-    /// Validates whether a ship placement is legal.
+    /// Calculates the coordinates a ship of the given shape would occupy with
+    /// the top-left corner of its bounding box at `start`.
+    ///
+    /// # Arguments
+    /// * `start` - Top-left corner of the shape's bounding box (row, col)
+    /// * `shape` - Outline of the ship, in the orientation to place it
+    ///
+    /// # Returns
+    /// * `Option<Bitboard>` - Set of coordinates the ship would occupy, or
+    ///   `None` if the ship would extend off the board
+    ///
+    /// # Example
+    /// ```
+    /// use battleship_core::{Board, Shape};
+    /// let board = Board::new();
+    /// let tee = Shape::parse(&["###", ".#."]).unwrap();
+    /// let cells = board.calc_shape_placement((0, 0), &tee).unwrap();
+    /// assert!(cells.contains((1, 1)) && !cells.contains((1, 0)));
+    /// assert!(board.calc_shape_placement((9, 0), &tee).is_none());
+    /// ```
+    pub fn calc_shape_placement(&self, start: (usize, usize), shape: &Shape) -> Option<Bitboard> {
+        let mut placement = Bitboard::new(self.rows, self.cols);
+        for &(row, col) in shape.cells() {
+            let coord = (start.0 + row, start.1 + col);
+            if coord.0 >= self.rows || coord.1 >= self.cols {
+                return None;
+            }
+            placement.insert(coord);
+        }
+        Some(placement)
+    }
+
+    /// Validates whether a ship placement is legal: on open water and clear
+    /// of `invalid_coords`.
     ///
     /// # Arguments
     /// * `coords` - Set of coordinates where ship would be placed
@@ -162,129 +341,760 @@ impl Board {
     ///
     /// # Returns
     /// * `bool` - True if placement is valid, false otherwise
-    pub fn valid_placement(
-        &self,
-        coords: &HashSet<(usize, usize)>,
-        invalid_coords: &HashSet<(usize, usize)>,
-    ) -> bool {
-        coords.is_subset(&self.coordinates) && coords.is_disjoint(&invalid_coords)
+    pub fn valid_placement(&self, coords: &Bitboard, invalid_coords: &Bitboard) -> bool {
+        coords.is_subset(&self.coordinates) && coords.is_disjoint(invalid_coords)
+    }
+
+    /// Returns the exclusion zone of every placed ship other than `id`.
+    fn zone_of_other_ships(&self, id: ShipId) -> Bitboard {
+        let mut others = Bitboard::new(self.rows, self.cols);
+        for ship in self.other_placed_ships(id) {
+            others.union_with(ship.coords());
+        }
+        self.exclusion_zone(&others)
+    }
+
+    /// Iterates over every placed ship other than `id`.
+    fn other_placed_ships(&self, id: ShipId) -> impl Iterator<Item = &Ship> {
+        self.fleet
+            .ids()
+            .filter(move |&other| other != id)
+            .filter_map(|other| self.fleet.ship(other))
+            .filter(|ship| ship.is_placed())
+    }
+
+    /// Returns an error once the fleet has been confirmed.
+    fn ensure_setup(&self) -> Result<(), GameplayError> {
+        if self.confirmed {
+            Err(GameplayError::FleetLocked)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Lists every placement a ship of the given shape could take, in any
+    /// rotation or reflection, without leaving the board or touching
+    /// `blocked`. Straight ships also lie diagonally when the board allows
+    /// it.
+    ///
+    /// # Arguments
+    /// * `shape` - Outline of the ship
+    /// * `blocked` - Set of coordinates the ship can't use (e.g., the
+    ///   exclusion zone of other ships); mines are always blocked
+    ///
+    /// # Returns
+    /// * `Vec<Candidate>` - Each legal placement with its exclusion zone,
+    ///   without duplicates
+    pub fn candidate_placements(&self, shape: &Shape, blocked: &Bitboard) -> Vec<Candidate> {
+        let blocked = blocked.union(self.mines());
+        let orientations = shape.orientations();
+        let diagonals = if self.diagonal_ships && shape.is_line() && shape.len() > 1 {
+            &Orientation::ALL[2..]
+        } else {
+            &[]
+        };
+        let mut out = Vec::new();
+        for start in Bitboard::full(self.rows, self.cols).iter() {
+            let straight = orientations
+                .iter()
+                .map(|orientation| self.calc_shape_placement(start, orientation));
+            let diagonal = diagonals
+                .iter()
+                .map(|&orientation| self.calc_placement(start, shape.len(), orientation));
+            for cells in straight.chain(diagonal).flatten() {
+                if self.valid_placement(&cells, &blocked) {
+                    out.push(Candidate {
+                        zone: self.exclusion_zone(&cells),
+                        cells,
+                    });
+                }
+            }
+        }
+        out
     }
 
     /// Attempts to place a ship on the board.
     ///
+    /// The ship must stay on the board, must not overlap another ship, and
+    /// must respect the board's [`AdjacencyRule`]. Placing a ship that is
+    /// already on the board moves it; if the new position is invalid the
+    /// ship stays where it was.
+    ///
+    /// A straight ship extends from `start` in the given direction; the
+    /// diagonal ones are only allowed when the board's rules let ships lie
+    /// diagonally. A shaped ship is laid out as its class draws it when
+    /// `Horizontal` and turned a quarter turn clockwise when `Vertical`, with
+    /// its bounding box starting at `start`; use [`Board::place_ship_cells`]
+    /// for other orientations.
+    ///
     /// # Arguments
     /// * `name` - Name of the ship to place
-    /// * `start` - Starting coordinate (row, col)
-    /// * `horizontal` - If true, ship extends horizontally; if false, vertically
+    /// * `start` - Starting coordinate
+    /// * `orientation` - Direction the ship lies in
     ///
     /// # Returns
     /// * `Result<(), GameplayError>` - Ok(()) if successful, Error if placement invalid
     ///
+    /// # Errors
+    /// Returns `OutOfBounds` if the ship would run off the board,
+    /// `Aground(ship)` if it would cover an island, `OverMine(ship)` if it
+    /// would cover a mine, `DiagonalNotAllowed(ship)` for a diagonal the rules forbid,
+    /// `Overlaps(ship)` if it would cross another ship and `TooClose(ship)` if
+    /// it would touch one the adjacency rule keeps it away from
+    ///
     /// # Example
     /// ```
-    /// use battleship_core::Board;
+    /// use battleship_core::{Board, Coord, GameplayError, Orientation};
     /// let mut board = Board::new();
-    /// let result = board.place_ship("Carrier", (0, 0), true);
+    /// let result = board.place_ship("Carrier", Coord::new(0, 0), Orientation::Horizontal);
     /// assert!(result.is_ok());
+    /// let result = board.place_ship("Cruiser", Coord::new(0, 2), Orientation::Vertical);
+    /// assert_eq!(result, Err(GameplayError::Overlaps("Carrier".into())));
+    /// let result = board.place_ship("Destroyer", Coord::new(9, 9), Orientation::Horizontal);
+    /// assert_eq!(result, Err(GameplayError::OutOfBounds("Destroyer".into())));
+    /// let result = board.place_ship("Destroyer", Coord::new(5, 5), Orientation::DiagonalDown);
+    /// assert_eq!(result, Err(GameplayError::DiagonalNotAllowed("Destroyer".into())));
     /// ```
     pub fn place_ship(
         &mut self,
         name: &str,
-        start: (usize, usize),
-        horizontal: bool,
+        start: Coord,
+        orientation: Orientation,
     ) -> Result<(), GameplayError> {
-        let existing_ships: HashSet<(usize, usize)> = self.ship_coords(true, true);
-        let length = match self.fleet.get_ship(name) {
-            Ok(ship) => ship.length(),
-            Err(e) => return Err(e),
+        self.ensure_setup()?;
+        let ship = self.fleet.get_ship(name)?;
+        let proposed = match orientation {
+            Orientation::Horizontal => self.calc_shape_placement(start.into(), ship.shape()),
+            Orientation::Vertical => {
+                self.calc_shape_placement(start.into(), &ship.shape().rotated())
+            }
+            _ if !self.diagonal_ships || !ship.shape().is_line() => {
+                return Err(GameplayError::DiagonalNotAllowed(ship.name().to_string()));
+            }
+            _ => self.calc_placement(start.into(), ship.length(), orientation),
         };
-        let proposed = self.calc_placement(start, length, horizontal);
-        if !self.valid_placement(&proposed, &existing_ships) {
-            return Err(GameplayError::InvalidPlacement);
+        let proposed =
+            proposed.ok_or_else(|| GameplayError::OutOfBounds(ship.name().to_string()))?;
+        self.place_ship_cells(name, proposed)
+    }
+
+    /// Places a ship on exactly the given cells.
+    ///
+    /// The cells must form the ship's shape in some rotation or reflection;
+    /// otherwise this checks the same rules as [`Board::place_ship`].
+    ///
+    /// # Errors
+    /// Returns `OutOfBounds` if the cells do not lie on this board, `Aground`
+    /// if any is an island, `WrongLength`, `NotContiguous` or `WrongShape` if
+    /// they do not form the ship, and `DiagonalNotAllowed`, `Overlaps`,
+    /// `TooClose` or `OverMine` like [`Board::place_ship`].
+    ///
+    /// # Example
+    /// ```
+    /// use battleship_core::{Bitboard, Board, GameConfig, GameplayError, Shape, ShipClass};
+    /// let ell = Shape::parse(&["#.", "##"]).unwrap();
+    /// let config = GameConfig::with_classes(4, 4, vec![ShipClass::shaped("Ell", ell, 1)]);
+    /// let mut board = Board::with_config(&config);
+    /// let mirrored = Bitboard::from_coords(4, 4, [(0, 1), (1, 0), (1, 1)]);
+    /// assert!(board.place_ship_cells("Ell", mirrored).is_ok());
+    /// let straight = Bitboard::from_coords(4, 4, [(0, 0), (0, 1), (0, 2)]);
+    /// assert_eq!(
+    ///     board.place_ship_cells("Ell", straight),
+    ///     Err(GameplayError::WrongShape("Ell".into()))
+    /// );
+    /// ```
+    pub fn place_ship_cells(&mut self, name: &str, cells: Bitboard) -> Result<(), GameplayError> {
+        self.ensure_setup()?;
+        let id = self.fleet.id_of(name)?;
+        let ship = self.fleet.get_ship(name)?;
+        if (cells.rows(), cells.cols()) != (self.rows, self.cols) {
+            return Err(GameplayError::OutOfBounds(ship.name().to_string()));
         }
-        self.fleet.place_ship(name, proposed)
+        if !cells.is_disjoint(&self.islands) {
+            return Err(GameplayError::Aground(ship.name().to_string()));
+        }
+        // A diagonal the rules forbid is reported ahead of any mismatch with
+        // the ship's length or shape, which the fleet checks below.
+        let diagonal = Orientation::of_line(&cells).is_some_and(|o| o.is_diagonal());
+        if diagonal && !self.diagonal_ships {
+            return Err(GameplayError::DiagonalNotAllowed(ship.name().to_string()));
+        }
+        self.check_clearance(id, &cells)?;
+        self.fleet.place_ship_by_id(id, cells)
     }
 
-    pub fn randomly_place_ship(&mut self, name: &str) -> Result<(), GameplayError> {
-        let mut rng = thread_rng();
-        for _ in 0..GRID_SIZE * GRID_SIZE * 1000 {
-            // Prevent infinite loop
-            if let Some(start) = self.coordinates.iter().choose(&mut rng) {
-                let horizontal = rng.gen_bool(0.5);
-                if self.place_ship(name, *start, horizontal).is_ok() {
-                    return Ok(());
-                }
+    /// Checks that `cells` neither overlap nor, under the adjacency rule,
+    /// touch any placed ship other than `id`.
+    fn check_clearance(&self, id: ShipId, cells: &Bitboard) -> Result<(), GameplayError> {
+        for ship in self.other_placed_ships(id) {
+            if !ship.coords().is_disjoint(cells) {
+                return Err(GameplayError::Overlaps(ship.name().to_string()));
+            }
+            if !self.exclusion_zone(ship.coords()).is_disjoint(cells) {
+                return Err(GameplayError::TooClose(ship.name().to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Takes a placed ship back off the board.
+    ///
+    /// # Errors
+    /// Returns `FleetLocked` once the fleet is confirmed, `ShipNotFound` for
+    /// an unknown name and `ShipNotPlaced` if the ship is not on the board.
+    pub fn unplace_ship(&mut self, name: &str) -> Result<(), GameplayError> {
+        self.ensure_setup()?;
+        let ship = self.fleet.get_ship(name)?;
+        if !ship.is_placed() {
+            return Err(GameplayError::ShipNotPlaced(ship.name().to_string()));
+        }
+        self.fleet.unplace_ship(name)
+    }
+
+    /// Hides one of the board's mines on an open-water cell.
+    ///
+    /// # Errors
+    /// Returns `FleetLocked` once the fleet is confirmed, `NoMinesLeft` if
+    /// every mine is laid and `BadMineCell` for an island or a cell off the
+    /// board or already holding a ship or mine.
+    ///
+    /// # Example
+    /// ```
+    /// use battleship_core::{Board, Coord, GameConfig, GameplayError, MinePenalty, SHIPS};
+    /// let config = GameConfig::square(10, SHIPS).with_mines(1, MinePenalty::LoseTurn);
+    /// let mut board = Board::with_config(&config);
+    /// board.place_mine(Coord::new(4, 4)).unwrap();
+    /// assert_eq!(board.place_mine(Coord::new(5, 5)), Err(GameplayError::NoMinesLeft));
+    /// ```
+    pub fn place_mine(&mut self, cell: Coord) -> Result<(), GameplayError> {
+        self.ensure_setup()?;
+        if self.islands.contains(cell) {
+            return Err(GameplayError::BadMineCell(cell));
+        }
+        self.fleet.place_mine(cell.into())
+    }
+
+    /// Moves a placed ship so that the top-left corner of its bounding box is
+    /// at `start`, keeping its orientation.
+    ///
+    /// # Errors
+    /// Fails like [`Board::unplace_ship`], or like [`Board::place_ship`] if the
+    /// new position is not legal, in which case the ship is not moved.
+    ///
+    /// # Example
+    /// ```
+    /// use battleship_core::{Board, Coord, Orientation};
+    /// let mut board = Board::new();
+    /// board.place_ship("Destroyer", Coord::new(0, 0), Orientation::Horizontal).unwrap();
+    /// board.move_ship("Destroyer", Coord::new(5, 5)).unwrap();
+    /// assert!(board.place_ship("Carrier", Coord::new(0, 0), Orientation::Horizontal).is_ok());
+    /// ```
+    pub fn move_ship(&mut self, name: &str, start: Coord) -> Result<(), GameplayError> {
+        self.ensure_setup()?;
+        let (_, offsets) = self.ship_position(name)?;
+        self.place_offsets(name, start.into(), &offsets)
+    }
+
+    /// Turns a placed ship a quarter turn clockwise, keeping the top-left
+    /// corner of its bounding box in place. A straight ship swaps between
+    /// horizontal and vertical, pivoting on its top-left cell, and a
+    /// diagonal one between its two diagonals.
+    ///
+    /// # Errors
+    /// Fails like [`Board::move_ship`].
+    pub fn rotate_ship(&mut self, name: &str) -> Result<(), GameplayError> {
+        self.ensure_setup()?;
+        let (start, offsets) = self.ship_position(name)?;
+        let height = offsets.iter().map(|&(row, _)| row + 1).max().unwrap_or(0);
+        let turned: Vec<_> = offsets
+            .iter()
+            .map(|&(row, col)| (col, height - 1 - row))
+            .collect();
+        self.place_offsets(name, start, &turned)
+    }
+
+    /// Places a ship on the cells `offsets` away from `start`.
+    fn place_offsets(
+        &mut self,
+        name: &str,
+        start: (usize, usize),
+        offsets: &[(usize, usize)],
+    ) -> Result<(), GameplayError> {
+        let mut proposed = Bitboard::new(self.rows, self.cols);
+        for &(row, col) in offsets {
+            let coord = (start.0 + row, start.1 + col);
+            if coord.0 >= self.rows || coord.1 >= self.cols {
+                return Err(GameplayError::OutOfBounds(name.to_string()));
             }
+            proposed.insert(coord);
+        }
+        self.place_ship_cells(name, proposed)
+    }
+
+    /// Returns the top-left corner of a placed ship's bounding box and the
+    /// offsets of its cells from that corner.
+    fn ship_position(&self, name: &str) -> Result<((usize, usize), Offsets), GameplayError> {
+        let ship = self.fleet.get_ship(name)?;
+        if !ship.is_placed() {
+            return Err(GameplayError::ShipNotPlaced(ship.name().to_string()));
         }
-        Err(GameplayError::CantFindValidPlacement)
+        let cells = ship.coords();
+        let top = cells.iter().map(|(row, _)| row).min().unwrap_or(0);
+        let left = cells.iter().map(|(_, col)| col).min().unwrap_or(0);
+        let offsets = cells
+            .iter()
+            .map(|(row, col)| (row - top, col - left))
+            .collect();
+        Ok(((top, left), offsets))
     }
 
+    /// Locks in the fleet layout and starts the game for this board.
+    ///
+    /// Once confirmed, ships can no longer be placed, moved, rotated or
+    /// removed, and [`Board::player_state`] leaves `Setup`.
+    ///
+    /// # Errors
+    /// Returns `FleetIncomplete` if any ship or mine has not been placed.
+    ///
+    /// # Example
+    /// ```
+    /// use battleship_core::{Board, GameplayError, PlayerState};
+    /// let mut board = Board::new();
+    /// assert!(matches!(board.confirm_fleet(), Err(GameplayError::FleetIncomplete)));
+    /// board.randomly_place_fleet().unwrap();
+    /// assert_eq!(board.player_state(), PlayerState::Setup);
+    /// board.confirm_fleet().unwrap();
+    /// assert_eq!(board.player_state(), PlayerState::Alive);
+    /// assert!(matches!(board.rotate_ship("Carrier"), Err(GameplayError::FleetLocked)));
+    /// ```
+    pub fn confirm_fleet(&mut self) -> Result<(), GameplayError> {
+        if self.fleet.unplaced_ships().count() > 0 || self.fleet.unlaid_mines() > 0 {
+            return Err(GameplayError::FleetIncomplete);
+        }
+        self.confirmed = true;
+        Ok(())
+    }
+
+    /// Returns true once the fleet layout has been confirmed.
+    pub fn is_confirmed(&self) -> bool {
+        self.confirmed
+    }
+
+    /// Places a ship at a position chosen uniformly from all legal positions.
+    ///
+    /// # Errors
+    /// Returns `CantFindValidPlacement` if the ship fits nowhere.
+    pub fn randomly_place_ship(&mut self, name: &str) -> Result<(), GameplayError> {
+        self.randomly_place_ship_with_rng(name, &mut thread_rng())
+    }
+
+    /// Places a ship at a random valid position drawn from `rng`.
+    pub fn randomly_place_ship_with_rng<R: Rng + ?Sized>(
+        &mut self,
+        name: &str,
+        rng: &mut R,
+    ) -> Result<(), GameplayError> {
+        self.ensure_setup()?;
+        let id = self.fleet.id_of(name)?;
+        let shape = self.fleet.get_ship(name)?.shape().clone();
+        let existing_ships = self.zone_of_other_ships(id);
+        let placement = self
+            .candidate_placements(&shape, &existing_ships)
+            .choose(rng)
+            .cloned()
+            .ok_or(GameplayError::CantFindValidPlacement)?;
+        self.fleet.place_ship_by_id(id, placement.cells)
+    }
+
+    /// Places every unplaced ship at random using
+    /// [`PlacementStrategy::Backtracking`].
+    ///
+    /// # Errors
+    /// Returns `CantFindValidPlacement` only if no legal layout exists.
     pub fn randomly_place_fleet(&mut self) -> Result<(), GameplayError> {
-        // Collect the names of the unplaced ships into a vector
-        let unplaced_ships: Vec<String> = self
+        self.randomly_place_fleet_with_rng(&mut thread_rng())
+    }
+
+    /// Places every unplaced ship at random, drawing all choices from `rng`.
+    ///
+    /// # Example
+    /// ```
+    /// use battleship_core::{rng, Board};
+    /// let mut a = Board::new();
+    /// let mut b = Board::new();
+    /// a.randomly_place_fleet_with_rng(&mut rng::seeded(7)).unwrap();
+    /// b.randomly_place_fleet_with_rng(&mut rng::seeded(7)).unwrap();
+    /// assert_eq!(a.to_string(), b.to_string());
+    /// ```
+    pub fn randomly_place_fleet_with_rng<R: Rng + ?Sized>(
+        &mut self,
+        rng: &mut R,
+    ) -> Result<(), GameplayError> {
+        self.randomly_place_fleet_using(PlacementStrategy::Backtracking, rng)
+    }
+
+    /// Places every unplaced ship at random around any ships and mines
+    /// already placed, then hides any mines still to be laid on open water
+    /// the ships left free.
+    ///
+    /// # Arguments
+    /// * `strategy` - How to choose among the legal layouts
+    /// * `rng` - Source of randomness
+    ///
+    /// # Errors
+    /// Returns `CantFindValidPlacement` only if no legal layout exists.
+    ///
+    /// # Example
+    /// ```
+    /// use battleship_core::{rng, Board, GameConfig, PlacementStrategy, PlayerState};
+    /// // Four length-4 ships exactly fill a 4x4 board.
+    /// let config = GameConfig::square(4, &[("A", 4), ("B", 4), ("C", 4), ("D", 4)]);
+    /// let mut board = Board::with_config(&config);
+    /// board
+    ///     .randomly_place_fleet_using(PlacementStrategy::Uniform, &mut rng::seeded(3))
+    ///     .unwrap();
+    /// board.confirm_fleet().unwrap();
+    /// assert_eq!(board.player_state(), PlayerState::Alive);
+    /// ```
+    pub fn randomly_place_fleet_using<R: Rng + ?Sized>(
+        &mut self,
+        strategy: PlacementStrategy,
+        rng: &mut R,
+    ) -> Result<(), GameplayError> {
+        self.ensure_setup()?;
+        let unplaced_ships: Vec<(ShipId, Shape)> = self
             .fleet
-            .unplaced_ships()
-            .map(|ship| ship.name().to_string())
+            .ids()
+            .filter_map(|id| Some((id, self.fleet.ship(id)?)))
+            .filter(|(_, ship)| !ship.is_placed())
+            .map(|(id, ship)| (id, ship.shape().clone()))
             .collect();
-        // Now place each ship
-        for ship_name in unplaced_ships {
-            match self.randomly_place_ship(&ship_name) {
-                Ok(_) => continue,
-                Err(e) => return Err(e),
-            }
+        let existing_ships = self
+            .exclusion_zone(&self.ship_coords(true, true))
+            .union(self.mines());
+        let candidates: Vec<Vec<Candidate>> = unplaced_ships
+            .iter()
+            .map(|(_, shape)| self.candidate_placements(shape, &existing_ships))
+            .collect();
+        let layout = placement::sample(&candidates, &existing_ships, strategy, rng)
+            .ok_or(GameplayError::CantFindValidPlacement)?;
+        for ((id, _), cells) in unplaced_ships.iter().zip(layout) {
+            self.fleet.place_ship_by_id(*id, cells)?;
+        }
+        let free = self
+            .coordinates
+            .difference(&self.ship_coords(true, true))
+            .difference(self.mines());
+        let unlaid = self.fleet.unlaid_mines();
+        for cell in free.iter().choose_multiple(rng, unlaid) {
+            self.fleet.place_mine(cell)?;
+        }
+        if self.fleet.unlaid_mines() > 0 {
+            return Err(GameplayError::CantFindValidPlacement);
         }
         Ok(())
     }
 
     /// Makes a guess at the given coordinates.
     ///
+    /// A sinking is reported as the board's [`SunkAnnouncement`] rule
+    /// allows, and the shot log records the same result.
+    ///
     /// # Arguments
-    /// * `target` - Coordinate to attack (row, col)
+    /// * `target` - Coordinate to attack
     ///
     /// # Returns
     /// * `Result<GuessResult, GuessError>` - Result of the guess or error if invalid
     ///
     /// # Example
     /// ```
-    /// use battleship_core::{Board, GuessResult};
+    /// use battleship_core::{Board, Coord, GuessResult, Orientation};
     /// let mut board = Board::new();
-    /// board.place_ship("Carrier", (0, 0), true).unwrap();
-    /// let result = board.guess((0, 0));
+    /// board.place_ship("Carrier", Coord::new(0, 0), Orientation::Horizontal).unwrap();
+    /// let result = board.guess("A1".parse().unwrap());
     /// assert_eq!(result.unwrap(), GuessResult::Hit);
     /// ```
-    pub fn guess(&mut self, target: (usize, usize)) -> Result<GuessResult, GuessError> {
-        if !self.is_valid_target(target) {
-            return Err(GuessError::InvalidTarget);
+    pub fn guess(&mut self, target: Coord) -> Result<GuessResult, GuessError> {
+        let result = self.fire(target)?;
+        self.undone.clear();
+        Ok(result)
+    }
+
+    /// Applies a guess and records it in the shot log.
+    fn fire(&mut self, target: Coord) -> Result<GuessResult, GuessError> {
+        let cell: (usize, usize) = target.into();
+        if self.islands.contains(cell) {
+            return Err(GuessError::Island(target));
+        }
+        if !self.is_valid_target(cell) {
+            return Err(GuessError::InvalidTarget(target));
+        }
+        if !self.guessed.insert(cell) {
+            return Err(GuessError::AlreadyGuessed(target));
+        }
+        // A cell a mine blast already struck takes no more damage.
+        let result = if self.blasted.contains(cell) {
+            GuessResult::Hit
+        } else {
+            match self.fleet.guess(cell) {
+                Ok(result) => result,
+                Err(err) => {
+                    self.guessed.remove(cell);
+                    return Err(err);
+                }
+            }
+        };
+        let result = match result {
+            GuessResult::Hit | GuessResult::Sunk { .. } => self.strike_result(cell),
+            other => other,
+        };
+        self.history.push(Shot {
+            target,
+            result: result.clone(),
+        });
+        Ok(result)
+    }
+
+    /// What the attacker is told about a shot that struck a ship at `cell`:
+    /// the ship sinks, as far as they know, once they have fired at every
+    /// one of its cells, even if a mine blast struck some of them first, and
+    /// the sinking is announced by the board's [`SunkAnnouncement`] rule.
+    fn strike_result(&self, cell: (usize, usize)) -> GuessResult {
+        match self
+            .fleet
+            .get_ships(true, true)
+            .find(|ship| ship.coords().contains(cell))
+        {
+            Some(ship) if self.sunk_in_sight(ship) => GuessResult::Sunk {
+                name: Some(ship.name().to_string()),
+                cells: Some(ship.coords().iter().map(Coord::from).collect()),
+            }
+            .announced(self.sunk_announcement),
+            _ => GuessResult::Hit,
+        }
+    }
+
+    /// Returns true if `ship` is sunk and the attacker has fired at every
+    /// cell of it.
+    fn sunk_in_sight(&self, ship: &Ship) -> bool {
+        ship.is_sunk() && ship.coords().is_subset(&self.guessed)
+    }
+
+    /// Returns every shot fired at this board, oldest first, with the
+    /// results as they were announced.
+    pub fn shot_log(&self) -> &[Shot] {
+        &self.history
+    }
+
+    /// Takes back the most recent shot, restoring the guessed cells and the
+    /// damage to the fleet exactly as they were before it.
+    ///
+    /// # Returns
+    /// * `Option<Shot>` - The shot taken back, or `None` if no shots remain
+    ///
+    /// # Example
+    /// ```
+    /// use battleship_core::{Board, Coord, GuessResult};
+    /// let mut board = Board::new();
+    /// board.randomly_place_fleet().unwrap();
+    /// let target = Coord::new(4, 4);
+    /// let result = board.guess(target).unwrap();
+    /// assert_eq!(board.undo_last_guess().unwrap().target, target);
+    /// assert!(board.shot_log().is_empty());
+    /// assert_eq!(board.redo().unwrap().result, result);
+    /// ```
+    pub fn undo_last_guess(&mut self) -> Option<Shot> {
+        let shot = self.history.pop()?;
+        let cell: (usize, usize) = shot.target.into();
+        self.guessed.remove(cell);
+        // Damage from a mine blast stays when the shot is taken back.
+        if !self.blasted.contains(cell) {
+            self.fleet.undo_guess(cell);
+        }
+        self.undone.push(shot.target);
+        Some(shot)
+    }
+
+    /// Replays the most recently undone shot.
+    ///
+    /// Any new guess discards the shots that could be redone.
+    ///
+    /// # Returns
+    /// * `Option<Shot>` - The shot replayed, or `None` if nothing was undone
+    ///   or the shot can no longer be fired, in which case it is kept to be
+    ///   redone
+    pub fn redo(&mut self) -> Option<Shot> {
+        let target = *self.undone.last()?;
+        let result = self.fire(target).ok()?;
+        self.undone.pop();
+        Some(Shot { target, result })
+    }
+
+    /// Fires at every open cell not yet fired at in the 3x3 area around
+    /// `center`, row by row.
+    ///
+    /// # Returns
+    /// * `Result<Vec<(Coord, GuessResult)>, GuessError>` - Each cell fired
+    ///   at with its result
+    ///
+    /// # Errors
+    /// Returns `InvalidTarget` if `center` is off the board and
+    /// `AlreadyGuessed` if the area holds nothing left to fire at.
+    pub fn bombard(&mut self, center: Coord) -> Result<Vec<(Coord, GuessResult)>, GuessError> {
+        if center.row >= self.rows || center.col >= self.cols {
+            return Err(GuessError::InvalidTarget(center));
         }
-        if !self.guessed.insert(target) {
-            return Err(GuessError::AlreadyGuessed);
+        let targets =
+            weapon::area_around(center, self.rows, self.cols).intersection(&self.unguessed());
+        if targets.is_empty() {
+            return Err(GuessError::AlreadyGuessed(center));
         }
-        self.fleet.guess(target)
+        targets
+            .iter()
+            .map(|cell| {
+                let target = Coord::from(cell);
+                self.guess(target).map(|result| (target, result))
+            })
+            .collect()
+    }
+
+    /// Launches a torpedo from `start` that runs east along its row, firing
+    /// at each open cell it reaches and passing over cells already fired at.
+    /// It stops at the first shot that is not a miss, at an island or at
+    /// the edge of the board.
+    ///
+    /// # Returns
+    /// * `Result<Vec<(Coord, GuessResult)>, GuessError>` - Each cell fired
+    ///   at with its result; only the last can be anything but a miss
+    ///
+    /// # Errors
+    /// Returns `InvalidTarget` if `start` is off the board, `Island` if it
+    /// is an island and `AlreadyGuessed` if the torpedo reaches nothing left
+    /// to fire at.
+    pub fn torpedo(&mut self, start: Coord) -> Result<Vec<(Coord, GuessResult)>, GuessError> {
+        if self.islands.contains(start) {
+            return Err(GuessError::Island(start));
+        }
+        if !self.is_valid_target(start.into()) {
+            return Err(GuessError::InvalidTarget(start));
+        }
+        let mut strike = Vec::new();
+        for col in start.col..self.cols {
+            let target = Coord::new(start.row, col);
+            if self.islands.contains(target) {
+                break;
+            }
+            if self.guessed.contains(target) {
+                continue;
+            }
+            let result = self.guess(target)?;
+            let struck = result != GuessResult::Miss;
+            strike.push((target, result));
+            if struck {
+                break;
+            }
+        }
+        if strike.is_empty() {
+            return Err(GuessError::AlreadyGuessed(start));
+        }
+        Ok(strike)
+    }
+
+    /// Searches the 3x3 area around `center` for ships without damaging
+    /// them, and records the ping so the attacker keeps what it learned.
+    ///
+    /// # Returns
+    /// * `Result<bool, GuessError>` - True if any ship, hit or not, lies in
+    ///   the area
+    ///
+    /// # Errors
+    /// Returns `InvalidTarget` if `center` is off the board.
+    pub fn sonar_ping(&mut self, center: Coord) -> Result<bool, GuessError> {
+        if center.row >= self.rows || center.col >= self.cols {
+            return Err(GuessError::InvalidTarget(center));
+        }
+        let area = weapon::area_around(center, self.rows, self.cols);
+        let contact = !area.is_disjoint(&self.ship_coords(true, true));
+        self.pings.push(Ping { center, contact });
+        Ok(contact)
+    }
+
+    /// Returns every sonar ping taken at this board, oldest first.
+    pub fn pings(&self) -> &[Ping] {
+        &self.pings
     }
 
     fn is_valid_target(&self, target: (usize, usize)) -> bool {
-        self.coordinates.contains(&target)
+        self.coordinates.contains(target)
     }
 
     /// Makes a random guess on the board
     pub fn random_guess(&mut self) -> Result<GuessResult, GuessError> {
-        let mut rng = thread_rng();
+        self.random_guess_with_rng(&mut thread_rng())
+    }
+
+    /// Makes a random guess on the board, choosing the target with `rng`.
+    pub fn random_guess_with_rng<R: Rng + ?Sized>(
+        &mut self,
+        rng: &mut R,
+    ) -> Result<GuessResult, GuessError> {
         let unguessed = self.unguessed();
-        if unguessed.len() == 0 {
+        if unguessed.is_empty() {
             return Err(GuessError::NoValidCoordinates);
         }
-        match unguessed.iter().choose(&mut rng) {
-            Some(guess) => self.guess(*guess),
+        match unguessed.iter().choose(rng) {
+            Some(guess) => self.guess(Coord::from(guess)),
             None => Err(GuessError::RandomGuessFailed),
         }
     }
 
+    /// Strikes a random unhit cell of this board's own fleet, as when a mine
+    /// blows up in the face of the player who set it off.
+    ///
+    /// The blast is not a shot: it stays out of the shot log, and the
+    /// attacker learns of the damaged cell only by firing at it, which then
+    /// reports a hit.
+    ///
+    /// # Returns
+    /// * `Result<GuessResult, GuessError>` - What the blast did to the fleet
+    ///
+    /// # Errors
+    /// Returns `NoValidCoordinates` if every ship cell is already hit.
+    ///
+    /// # Example
+    /// ```
+    /// use battleship_core::{rng, Board, GuessResult};
+    /// let mut board = Board::new();
+    /// board.randomly_place_fleet().unwrap();
+    /// let result = board.counter_hit_with_rng(&mut rng::seeded(1)).unwrap();
+    /// assert_eq!(result, GuessResult::Hit);
+    /// assert_eq!(board.hits_remaining(), 16);
+    /// assert!(board.shot_log().is_empty());
+    /// ```
+    pub fn counter_hit_with_rng<R: Rng + ?Sized>(
+        &mut self,
+        rng: &mut R,
+    ) -> Result<GuessResult, GuessError> {
+        let intact = self
+            .ship_coords(true, false)
+            .difference(&self.hit_coords(true, false));
+        let cell = intact
+            .iter()
+            .choose(rng)
+            .ok_or(GuessError::NoValidCoordinates)?;
+        let result = self.fleet.guess(cell)?;
+        self.blasted.insert(cell);
+        Ok(result)
+    }
+
     /// Gets the current state of the player (Setup, Alive, or Dead).
     ///
+    /// A board stays in `Setup` until its fleet is confirmed.
+    ///
     /// # Returns
     /// * `PlayerState` - Current state of the player
     pub fn player_state(&self) -> PlayerState {
-        if self.fleet.unplaced_ships().count() > 0 {
+        if !self.confirmed {
             PlayerState::Setup
         } else if self.fleet.n_ships(true, false) > 0 {
             PlayerState::Alive
@@ -302,6 +1112,25 @@ impl Board {
         self.fleet.get_ships(unsunk, sunk)
     }
 
+    /// Looks up the stable id of the ship with the given name.
+    ///
+    /// # Example
+    /// ```
+    /// use battleship_core::{Board, GameConfig, ShipClass};
+    /// let config = GameConfig::with_classes(10, 10, vec![ShipClass::new("Submarine", 3, 2)]);
+    /// let board = Board::with_config(&config);
+    /// let second = board.ship_id("Submarine 2").unwrap();
+    /// assert_eq!(board.ship(second).unwrap().length(), 3);
+    /// ```
+    pub fn ship_id(&self, name: &str) -> Result<ShipId, GameplayError> {
+        self.fleet.id_of(name)
+    }
+
+    /// Gets a ship by id, or `None` if the id belongs to another fleet.
+    pub fn ship(&self, id: ShipId) -> Option<&Ship> {
+        self.fleet.ship(id)
+    }
+
     pub fn hits_remaining(&self) -> usize {
         self.fleet.hits_remaining()
     }
@@ -311,58 +1140,62 @@ impl Board {
     }
 
     /// Returns status information for each ship on the board.
-    pub fn ship_statuses(&self) -> Vec<(&'static str, usize, bool)> {
+    pub fn ship_statuses(&self) -> Vec<(&str, usize, bool)> {
         self.fleet.ship_statuses()
     }
 
-    /// Formats ship status into a human readable string.
-    pub fn format_ship_status(&self) -> String {
-        self.ship_statuses()
-            .into_iter()
-            .map(|(name, len, sunk)| {
-                let icon = if sunk { "☒" } else { "☐" };
-                format!("{}({}):{}", name, len, icon)
-            })
-            .collect::<Vec<_>>()
-            .join(" ")
-    }
-
-    pub fn unguessed_iter(&self) -> impl Iterator<Item = &(usize, usize)> {
-        self.coordinates.difference(&self.guessed)
+    pub fn unguessed_iter(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.coordinates
+            .iter()
+            .filter(move |&c| !self.guessed.contains(c))
     }
 
     /// Formats the board into a string. When `reveal_ships` is false the
-    /// underlying ship positions are hidden and only guesses are shown.
+    /// underlying ship and mine positions are hidden and only guesses,
+    /// mines that went off and islands are shown.
     pub fn format_board(&self, reveal_ships: bool) -> String {
         use std::fmt::Write as _;
 
         let ships = if reveal_ships {
             self.fleet.ship_coords(true, true)
         } else {
-            HashSet::new()
+            Bitboard::new(self.rows, self.cols)
         };
         let hits = self.fleet.hit_coords(true, true);
 
+        let label_width = Coord::row_label(self.rows.saturating_sub(1)).len();
+
         let mut out = String::new();
         // header
-        out.push_str("   ");
-        for col in 1..=self.gridsize {
+        out.push_str(&" ".repeat(label_width + 2));
+        for col in 1..=self.cols {
             let _ = write!(out, " {} ", col);
         }
         out.push('\n');
 
         // rows
-        for row in 0..self.gridsize {
-            let _ = write!(out, "{} ", (b'A' + row as u8) as char);
-            for col in 0..self.gridsize {
+        for row in 0..self.rows {
+            let _ = write!(
+                out,
+                "{:<width$} ",
+                Coord::row_label(row),
+                width = label_width
+            );
+            for col in 0..self.cols {
                 let coord = (row, col);
-                let icon = if self.guessed.contains(&coord) {
-                    if hits.contains(&coord) {
+                let icon = if self.islands.contains(coord) {
+                    Cell::Island.icon()
+                } else if self.mines().contains(coord)
+                    && (reveal_ships || self.guessed.contains(coord))
+                {
+                    Cell::Mine.icon()
+                } else if self.guessed.contains(coord) {
+                    if hits.contains(coord) {
                         Cell::Hit.icon()
                     } else {
                         Cell::Miss.icon()
                     }
-                } else if reveal_ships && ships.contains(&coord) {
+                } else if reveal_ships && ships.contains(coord) {
                     Cell::Ship.icon()
                 } else {
                     Cell::Empty.icon()
@@ -380,15 +1213,15 @@ impl Board {
     //     let hits = self.fleet.hit_coords(true, true);
     //     // Print header
     //     print!("   "); // Initial space for row labels
-    //     for col in 1..=self.gridsize {
+    //     for col in 1..=self.cols {
     //         print!(" {} ", col);
     //     }
     //     println!();
     //     // Print rows
-    //     for row in 0..self.gridsize {
+    //     for row in 0..self.rows {
     //         // Print row letter
     //         print!("{} ", (b'A' + row as u8) as char);
-    //         for col in 0..self.gridsize {
+    //         for col in 0..self.cols {
     //             let coord = (row, col);
     //             let icon = if self.guessed.contains(&coord) {
     //                 if hits.contains(&coord) {
@@ -408,6 +1241,12 @@ impl Board {
     // }
 }
 
+impl Default for Board {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl fmt::Display for Board {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.format_board(true))
@@ -415,44 +1254,758 @@ impl fmt::Display for Board {
 }
 
 impl battleship_common::BoardView for Board {
-    fn grid_size(&self) -> usize {
-        self.gridsize
+    fn rows(&self) -> usize {
+        self.rows
     }
+
+    fn cols(&self) -> usize {
+        self.cols
+    }
+
+    fn is_island(&self, coord: Coord) -> bool {
+        self.islands.contains(coord)
+    }
+}
+
+/// How one ship stands, as seen in a [`BoardState`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShipStatus {
+    /// Name of the ship
+    pub name: String,
+    /// Number of cells the ship occupies
+    pub length: usize,
+    /// Outline of the ship, as its class draws it
+    pub shape: Shape,
+    /// Whether the ship is known to be sunk
+    pub sunk: bool,
+    /// Cells the ship occupies; empty when the snapshot hides the fleet
+    pub cells: Vec<Coord>,
 }
 
-/// Lightweight snapshot of a board used for transport between components.
-#[derive(Clone, Debug)]
+/// Snapshot of a board used for transport between components.
+///
+/// A snapshot either reveals the fleet, for the board's owner, or shows
+/// only what the attacker has learned from their shots.
+///
+/// # Example
+/// ```
+/// use battleship_core::{Board, BoardState, Cell, Coord};
+/// let mut board = Board::new();
+/// board.randomly_place_fleet().unwrap();
+/// board.confirm_fleet().unwrap();
+/// let carrier = board.ship(board.ship_id("Carrier").unwrap()).unwrap();
+/// let target = Coord::from(carrier.coords().iter().next().unwrap());
+/// board.guess(target).unwrap();
+///
+/// let theirs = BoardState::new(&board, false);
+/// assert_eq!(theirs.cell(target), Cell::Hit);
+/// assert_eq!(theirs.history.len(), 1);
+/// assert!(theirs.ships.iter().all(|ship| ship.cells.is_empty()));
+///
+/// let mine = BoardState::new(&board, true);
+/// assert_eq!(mine.ships.iter().map(|ship| ship.cells.len()).sum::<usize>(), 17);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
 pub struct BoardState {
-    /// Grid dimension (always square)
-    pub grid_size: usize,
-    /// Preformatted board view
-    pub board: String,
-    /// Human readable ship status line
-    pub ships: String,
+    /// Number of rows on the grid
+    pub rows: usize,
+    /// Number of columns on the grid
+    pub cols: usize,
+    /// Whether ships on this board may touch each other
+    pub adjacency: AdjacencyRule,
+    /// Whether straight ships on this board may lie diagonally
+    pub diagonal_ships: bool,
+    /// Number of mines hidden on the board, set off or not
+    pub mines: usize,
+    /// What is known about each cell, indexed as `cells[row][col]`
+    pub cells: Vec<Vec<Cell>>,
+    /// Every ship in the fleet, in fleet order
+    pub ships: Vec<ShipStatus>,
+    /// Every shot taken at the board so far, oldest first
+    pub history: Vec<Shot>,
+    /// Every sonar ping taken at the board so far, oldest first
+    pub pings: Vec<Ping>,
     /// Current state of the player
     pub state: PlayerState,
+    /// Shots the receiving player may fire now; zero while they wait for
+    /// the opponent
+    pub shots: usize,
+    /// Special weapons the receiving player has left; empty outside the
+    /// advanced weapons mode
+    pub arsenal: Arsenal,
 }
 
 impl BoardState {
     /// Create a new snapshot from the given board.
+    ///
+    /// Snapshots that hide the ships tell the attacker only what the board's
+    /// [`SunkAnnouncement`] rule allows: whether each ship has sunk, and
+    /// where the sunk ones lay.
     pub fn new(board: &Board, reveal_ships: bool) -> Self {
+        let rule = if reveal_ships {
+            SunkAnnouncement::ShipPosition
+        } else {
+            board.sunk_announcement
+        };
+        // Under `SunkOnly` the attacker learns that a ship sank but not
+        // which, so no ship is marked sunk.
+        let reveal_sunk = matches!(
+            rule,
+            SunkAnnouncement::ShipName | SunkAnnouncement::ShipPosition
+        );
+        // Under `ShipName` an attacker told a ship sank learns its name, not
+        // which of their hits belonged to it.
+        let reveal_sunk_cells = rule == SunkAnnouncement::ShipPosition;
+        // Hidden snapshots leave out the damage of the owner's own mine
+        // blasts until the attacker fires there.
+        let is_sunk = |ship: &Ship| {
+            if reveal_ships {
+                ship.is_sunk()
+            } else {
+                board.sunk_in_sight(ship)
+            }
+        };
+        let mut sunk = Bitboard::new(board.rows, board.cols);
+        if reveal_sunk_cells {
+            for ship in board.get_ships(true, true).filter(|ship| is_sunk(ship)) {
+                sunk.union_with(ship.coords());
+            }
+        }
+        let ships = board.ship_coords(true, true);
+        let hits = if reveal_ships {
+            board.hit_coords(true, true)
+        } else {
+            board.hit_coords(true, true).intersection(&board.guessed)
+        };
+        let mines = board.mines();
+
+        let cells = (0..board.rows)
+            .map(|row| {
+                (0..board.cols)
+                    .map(|col| {
+                        let coord = (row, col);
+                        if board.islands.contains(coord) {
+                            Cell::Island
+                        } else if mines.contains(coord)
+                            && (reveal_ships || board.guessed.contains(coord))
+                        {
+                            Cell::Mine
+                        } else if sunk.contains(coord) && hits.contains(coord) {
+                            Cell::Sunk
+                        } else if hits.contains(coord) {
+                            Cell::Hit
+                        } else if board.guessed.contains(coord) {
+                            Cell::Miss
+                        } else if reveal_ships && ships.contains(coord) {
+                            Cell::Ship
+                        } else {
+                            Cell::Empty
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let ships = board
+            .get_ships(true, true)
+            .map(|ship| ShipStatus {
+                name: ship.name().to_string(),
+                length: ship.length(),
+                shape: ship.shape().clone(),
+                sunk: reveal_sunk && is_sunk(ship),
+                cells: if reveal_ships || (reveal_sunk_cells && is_sunk(ship)) {
+                    ship.coords().iter().map(Coord::from).collect()
+                } else {
+                    Vec::new()
+                },
+            })
+            .collect();
+
+        let history = board
+            .history
+            .iter()
+            .map(|shot| Shot {
+                target: shot.target,
+                result: shot.result.clone().announced(rule),
+            })
+            .collect();
+
         Self {
-            grid_size: board.gridsize,
-            board: board.format_board(reveal_ships),
-            ships: board.format_ship_status(),
+            rows: board.rows,
+            cols: board.cols,
+            adjacency: board.adjacency,
+            diagonal_ships: board.diagonal_ships,
+            mines: board.mine_count(),
+            cells,
+            ships,
+            history,
+            pings: board.pings.clone(),
             state: board.player_state(),
+            shots: 0,
+            arsenal: Arsenal::none(),
         }
     }
-}
 
-impl std::fmt::Display for BoardState {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.board)
+    /// Returns this snapshot with the number of shots its recipient may fire.
+    pub fn with_shots(mut self, shots: usize) -> Self {
+        self.shots = shots;
+        self
+    }
+
+    /// Returns this snapshot with the special weapons its recipient has
+    /// left.
+    pub fn with_arsenal(mut self, arsenal: Arsenal) -> Self {
+        self.arsenal = arsenal;
+        self
+    }
+
+    /// Returns what is known about a cell; cells off the board are
+    /// [`Cell::Empty`].
+    pub fn cell(&self, coord: Coord) -> Cell {
+        self.cells
+            .get(coord.row)
+            .and_then(|row| row.get(coord.col))
+            .copied()
+            .unwrap_or(Cell::Empty)
     }
 }
 
 impl battleship_common::BoardView for BoardState {
-    fn grid_size(&self) -> usize {
-        self.grid_size
+    fn rows(&self) -> usize {
+        self.rows
+    }
+
+    fn cols(&self) -> usize {
+        self.cols
+    }
+
+    fn is_island(&self, coord: Coord) -> bool {
+        self.cell(coord) == Cell::Island
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use battleship_config::{MinePenalty, ShipClass, Terrain};
+
+    #[test]
+    fn undo_and_redo_restore_the_exact_fleet_state() {
+        let config = GameConfig::new(4, 4, &[("Destroyer", 2)]);
+        let mut board = Board::with_config(&config);
+        board
+            .place_ship("Destroyer", Coord::new(0, 0), Orientation::Horizontal)
+            .unwrap();
+        board.confirm_fleet().unwrap();
+
+        let shots = [Coord::new(3, 3), Coord::new(0, 0), Coord::new(0, 1)];
+        let results: Vec<_> = shots.iter().map(|&c| board.guess(c).unwrap()).collect();
+        assert!(matches!(
+            &results[2],
+            GuessResult::Sunk { name: Some(name), .. } if name == "Destroyer"
+        ));
+        assert_eq!(board.player_state(), PlayerState::Dead);
+
+        assert_eq!(board.undo_last_guess().unwrap().target, shots[2]);
+        assert_eq!(board.player_state(), PlayerState::Alive);
+        assert_eq!(board.hits_remaining(), 1);
+        assert_eq!(board.undo_last_guess().unwrap().target, shots[1]);
+        assert_eq!(board.hits_remaining(), 2);
+        assert_eq!(board.guessed().len(), 1);
+
+        assert_eq!(board.redo().unwrap().result, GuessResult::Hit);
+        assert_eq!(board.redo().unwrap().result, results[2]);
+        assert!(board.redo().is_none());
+        assert_eq!(board.player_state(), PlayerState::Dead);
+        let log: Vec<_> = board.shot_log().iter().map(|s| s.result.clone()).collect();
+        assert_eq!(log, results);
+    }
+
+    #[test]
+    fn setup_edits_keep_ships_where_they_were_on_failure() {
+        let config = GameConfig::new(5, 5, &[("Cruiser", 3), ("Destroyer", 2)]);
+        let mut board = Board::with_config(&config);
+        board
+            .place_ship("Cruiser", Coord::new(0, 0), Orientation::Horizontal)
+            .unwrap();
+        board
+            .place_ship("Destroyer", Coord::new(2, 0), Orientation::Horizontal)
+            .unwrap();
+        let cruiser = |b: &Board| b.fleet.get_ship("Cruiser").unwrap().coords().clone();
+
+        // Rotating down from A1 would cross the destroyer on row C.
+        let before = cruiser(&board);
+        assert_eq!(
+            board.rotate_ship("Cruiser"),
+            Err(GameplayError::Overlaps("Destroyer".into()))
+        );
+        assert_eq!(cruiser(&board), before);
+
+        board.move_ship("Destroyer", Coord::new(4, 3)).unwrap();
+        board.rotate_ship("Cruiser").unwrap();
+        assert_eq!(
+            cruiser(&board),
+            Bitboard::from_coords(5, 5, [(0, 0), (1, 0), (2, 0)])
+        );
+
+        board.unplace_ship("Destroyer").unwrap();
+        assert_eq!(
+            board.unplace_ship("Destroyer"),
+            Err(GameplayError::ShipNotPlaced("Destroyer".into()))
+        );
+        assert!(matches!(
+            board.confirm_fleet(),
+            Err(GameplayError::FleetIncomplete)
+        ));
+        board
+            .place_ship("Destroyer", Coord::new(4, 0), Orientation::Horizontal)
+            .unwrap();
+        board.confirm_fleet().unwrap();
+        assert!(matches!(
+            board.move_ship("Destroyer", Coord::new(4, 2)),
+            Err(GameplayError::FleetLocked)
+        ));
+    }
+
+    #[test]
+    fn fleets_may_repeat_ship_classes() {
+        use battleship_config::ShipClass;
+        let config = GameConfig::with_classes(
+            10,
+            10,
+            vec![
+                ShipClass::new("Battleship", 4, 1),
+                ShipClass::new("Cruiser", 3, 2),
+                ShipClass::new("Destroyer", 2, 3),
+                ShipClass::new("Submarine", 1, 4),
+            ],
+        )
+        .with_adjacency(AdjacencyRule::NoTouch);
+        let mut board = Board::with_config(&config);
+        board
+            .randomly_place_fleet_with_rng(&mut crate::rng::seeded(8))
+            .unwrap();
+        board.confirm_fleet().unwrap();
+        assert_eq!(board.ship_lengths_remaining().len(), 10);
+
+        let id = board.ship_id("Submarine 3").unwrap();
+        let cell = board.ship(id).unwrap().coords().iter().next().unwrap();
+        assert_eq!(
+            board.guess(cell.into()).unwrap(),
+            GuessResult::Sunk {
+                name: Some("Submarine 3".into()),
+                cells: None
+            }
+        );
+        assert!(board.ship(id).unwrap().is_sunk());
+    }
+
+    #[test]
+    fn ships_sharing_a_name_still_keep_clear_of_each_other() {
+        // "Submarine" twice is numbered "Submarine 1" and "Submarine 2",
+        // colliding with the class already called "Submarine 1".
+        let config = GameConfig::with_classes(
+            1,
+            5,
+            vec![
+                ShipClass::new("Submarine", 1, 2),
+                ShipClass::new("Submarine 1", 1, 1),
+            ],
+        )
+        .with_adjacency(AdjacencyRule::NoTouch);
+        let mut board = Board::with_config(&config);
+        board
+            .randomly_place_fleet_with_rng(&mut crate::rng::seeded(2))
+            .unwrap();
+        board.confirm_fleet().unwrap();
+        assert_eq!(
+            board.ship_coords(true, false),
+            Bitboard::from_coords(1, 5, [(0, 0), (0, 2), (0, 4)])
+        );
+    }
+
+    #[test]
+    fn every_rule_set_can_be_laid_out() {
+        for rules in battleship_config::RuleSet::ALL {
+            let mut board = Board::with_config(&rules.config());
+            board
+                .randomly_place_fleet_with_rng(&mut crate::rng::seeded(1))
+                .unwrap();
+            board.confirm_fleet().unwrap();
+        }
+    }
+
+    #[test]
+    fn shaped_ships_move_and_rotate_as_a_whole() {
+        let tee = Shape::parse(&["###", ".#."]).unwrap();
+        let config = GameConfig::with_classes(5, 5, vec![ShipClass::shaped("Tee", tee, 1)]);
+        let mut board = Board::with_config(&config);
+        let cells = |board: &Board| {
+            let ship = board.ship(board.ship_id("Tee").unwrap()).unwrap();
+            ship.coords().iter().collect::<Vec<_>>()
+        };
+
+        board
+            .place_ship("Tee", Coord::new(0, 0), Orientation::Horizontal)
+            .unwrap();
+        assert_eq!(cells(&board), [(0, 0), (0, 1), (0, 2), (1, 1)]);
+        board
+            .place_ship("Tee", Coord::new(0, 0), Orientation::Vertical)
+            .unwrap();
+        assert_eq!(cells(&board), [(0, 1), (1, 0), (1, 1), (2, 1)]);
+        board.move_ship("Tee", Coord::new(2, 3)).unwrap();
+        assert_eq!(cells(&board), [(2, 4), (3, 3), (3, 4), (4, 4)]);
+        // Pointing up would put the right arm off the board.
+        assert_eq!(
+            board.rotate_ship("Tee"),
+            Err(GameplayError::OutOfBounds("Tee".into()))
+        );
+        assert_eq!(cells(&board), [(2, 4), (3, 3), (3, 4), (4, 4)]);
+        board.move_ship("Tee", Coord::new(2, 2)).unwrap();
+        board.rotate_ship("Tee").unwrap();
+        assert_eq!(cells(&board), [(2, 3), (3, 2), (3, 3), (3, 4)]);
+
+        let bent = Bitboard::from_coords(5, 5, [(0, 0), (0, 1), (1, 1), (1, 2)]);
+        assert_eq!(
+            board.place_ship_cells("Tee", bent),
+            Err(GameplayError::WrongShape("Tee".into()))
+        );
+    }
+
+    #[test]
+    fn shaped_fleets_are_placed_at_random() {
+        let mut board = Board::with_config(&battleship_config::RuleSet::Tetris.config());
+        board
+            .randomly_place_fleet_with_rng(&mut crate::rng::seeded(5))
+            .unwrap();
+        board.confirm_fleet().unwrap();
+        for ship in board.get_ships(true, true) {
+            let placed = Shape::from_cells(ship.coords().iter()).unwrap();
+            assert!(
+                ship.shape().matches(&placed),
+                "{} is misshapen",
+                ship.name()
+            );
+        }
+        assert_eq!(board.hits_remaining(), 20);
+    }
+
+    #[test]
+    fn straight_ships_lie_diagonally_only_when_allowed() {
+        let config = GameConfig::square(5, &[("Cruiser", 3), ("Dinghy", 1)]);
+        let diagonal = Bitboard::from_coords(5, 5, [(0, 0), (1, 1), (2, 2)]);
+        let mut board = Board::with_config(&config);
+        let denied = Err(GameplayError::DiagonalNotAllowed("Cruiser".into()));
+        assert_eq!(
+            board.place_ship("Cruiser", Coord::new(0, 0), Orientation::DiagonalDown),
+            denied
+        );
+        assert_eq!(board.place_ship_cells("Cruiser", diagonal.clone()), denied);
+        // The forbidden diagonal is reported before the wrong length.
+        let short = Bitboard::from_coords(5, 5, [(0, 0), (1, 1)]);
+        assert_eq!(board.place_ship_cells("Cruiser", short.clone()), denied);
+
+        let mut board = Board::with_config(&config.with_diagonal_ships(true));
+        let wrong_length = board.place_ship_cells("Cruiser", short);
+        assert!(matches!(
+            wrong_length,
+            Err(GameplayError::WrongLength { .. })
+        ));
+        let cells = |board: &Board| {
+            let ship = board.ship(board.ship_id("Cruiser").unwrap()).unwrap();
+            ship.coords().iter().collect::<Vec<_>>()
+        };
+        board
+            .place_ship("Cruiser", Coord::new(2, 0), Orientation::DiagonalUp)
+            .unwrap();
+        assert_eq!(cells(&board), [(0, 2), (1, 1), (2, 0)]);
+        assert_eq!(
+            board.place_ship("Cruiser", Coord::new(1, 0), Orientation::DiagonalUp),
+            Err(GameplayError::OutOfBounds("Cruiser".into()))
+        );
+        // Rotating swaps the diagonals within the same square.
+        board.rotate_ship("Cruiser").unwrap();
+        assert_eq!(cells(&board), [(0, 0), (1, 1), (2, 2)]);
+        board.move_ship("Cruiser", Coord::new(2, 2)).unwrap();
+        assert_eq!(cells(&board), [(2, 2), (3, 3), (4, 4)]);
+        assert_eq!(
+            board.place_ship("Dinghy", Coord::new(3, 3), Orientation::Horizontal),
+            Err(GameplayError::Overlaps("Cruiser".into()))
+        );
+    }
+
+    #[test]
+    fn diagonal_ships_are_placed_at_random() {
+        let config = GameConfig::square(3, &[("Cruiser", 3)]).with_diagonal_ships(true);
+        let board = Board::with_config(&config);
+        let blocked = Bitboard::new(3, 3);
+        // Three rows, three columns and two diagonals.
+        assert_eq!(
+            board.candidate_placements(&Shape::line(3), &blocked).len(),
+            8
+        );
+        let diagonal = (0..50).any(|seed| {
+            let mut board = Board::with_config(&config);
+            board
+                .randomly_place_fleet_with_rng(&mut crate::rng::seeded(seed))
+                .unwrap();
+            let ship = board.ship(board.ship_id("Cruiser").unwrap()).unwrap();
+            Orientation::of_line(ship.coords()).is_some_and(|o| o.is_diagonal())
+        });
+        assert!(diagonal);
+    }
+
+    #[test]
+    fn islands_hold_no_ships_and_take_no_shots() {
+        let map = Terrain::parse(&["....", ".##.", "...."]).unwrap();
+        let config = GameConfig::new(3, 4, &[("Cruiser", 3), ("Destroyer", 2)]).with_terrain(&map);
+        let mut board = Board::with_config(&config);
+        assert_eq!(
+            board.place_ship("Cruiser", Coord::new(1, 0), Orientation::Horizontal),
+            Err(GameplayError::Aground("Cruiser".into()))
+        );
+        board
+            .randomly_place_fleet_with_rng(&mut crate::rng::seeded(2))
+            .unwrap();
+        assert!(board.ship_coords(true, true).is_disjoint(board.islands()));
+        board.confirm_fleet().unwrap();
+        assert_eq!(
+            board.guess(Coord::new(1, 1)),
+            Err(GuessError::Island(Coord::new(1, 1)))
+        );
+
+        // Random shots use up every open cell and never an island.
+        let mut rng = crate::rng::seeded(3);
+        while board.random_guess_with_rng(&mut rng).is_ok() {}
+        assert_eq!(board.guessed().len(), 10);
+        assert!(board.guessed().is_disjoint(board.islands()));
+
+        // Both players see the islands from the start.
+        let fresh = Board::with_config(&config);
+        for reveal in [true, false] {
+            let state = BoardState::new(&fresh, reveal);
+            assert_eq!(state.cell(Coord::new(1, 2)), Cell::Island);
+        }
+        assert!(fresh.format_board(false).contains(Cell::Island.icon()));
+        assert_eq!(fresh.opponent_view().islands(), fresh.islands());
+    }
+
+    #[test]
+    fn mines_are_hidden_until_set_off() {
+        let config = GameConfig::square(5, &[("Cruiser", 3), ("Destroyer", 2)])
+            .with_mines(3, MinePenalty::LoseTurn);
+        let mut board = Board::with_config(&config);
+        board.place_mine(Coord::new(0, 0)).unwrap();
+        assert_eq!(
+            board.place_ship("Cruiser", Coord::new(0, 0), Orientation::Horizontal),
+            Err(GameplayError::OverMine("Cruiser".into()))
+        );
+        assert_eq!(
+            board.place_mine(Coord::new(0, 0)),
+            Err(GameplayError::BadMineCell(Coord::new(0, 0)))
+        );
+        board
+            .randomly_place_fleet_with_rng(&mut crate::rng::seeded(4))
+            .unwrap();
+        assert_eq!(board.mines().len(), 3);
+        assert!(board.mines().is_disjoint(&board.ship_coords(true, true)));
+        board.confirm_fleet().unwrap();
+
+        let hidden = board.mines().iter().nth(1).map(Coord::from).unwrap();
+        assert_eq!(BoardState::new(&board, false).cell(hidden), Cell::Empty);
+        assert_eq!(BoardState::new(&board, true).cell(hidden), Cell::Mine);
+        assert_eq!(board.guess(hidden), Ok(GuessResult::Mine));
+        assert_eq!(BoardState::new(&board, false).cell(hidden), Cell::Mine);
+
+        let view = board.opponent_view();
+        assert_eq!(view.mines_remaining(), 2);
+        assert!(view.guessed().contains(hidden) && view.misses().is_empty());
+
+        let hit = board
+            .counter_hit_with_rng(&mut crate::rng::seeded(5))
+            .unwrap();
+        assert_ne!(hit, GuessResult::Miss);
+        assert_eq!(board.hits_remaining(), 4);
+    }
+
+    #[test]
+    fn mine_blasts_stay_out_of_the_shot_log_and_the_attackers_view() {
+        let config = GameConfig::square(4, &[("Destroyer", 2)]);
+        let mut board = Board::with_config(&config);
+        board
+            .place_ship("Destroyer", Coord::new(0, 0), Orientation::Horizontal)
+            .unwrap();
+        board.confirm_fleet().unwrap();
+        let mut rng = crate::rng::seeded(1);
+        let struck = board.counter_hit_with_rng(&mut rng).unwrap();
+        assert_eq!(struck, GuessResult::Hit);
+        let blasted = board
+            .hit_coords(true, true)
+            .iter()
+            .next()
+            .map(Coord::from)
+            .unwrap();
+
+        assert!(board.shot_log().is_empty());
+        assert!(board.opponent_view().hits().is_empty());
+        assert_eq!(BoardState::new(&board, true).cell(blasted), Cell::Hit);
+        assert_eq!(BoardState::new(&board, false).cell(blasted), Cell::Empty);
+
+        // Sinking the destroyer with the second blast leaves the attacker
+        // thinking it is afloat until they fire at both cells.
+        board.counter_hit_with_rng(&mut rng).unwrap();
+        assert_eq!(board.player_state(), PlayerState::Dead);
+        assert!(board.opponent_view().sunk_ships().next().is_none());
+        assert_eq!(board.guess(Coord::new(0, 0)), Ok(GuessResult::Hit));
+        assert!(matches!(
+            board.guess(Coord::new(0, 1)),
+            Ok(GuessResult::Sunk { .. })
+        ));
+
+        // Taking a shot back leaves the blast damage in place.
+        board.undo_last_guess().unwrap();
+        assert_eq!(board.hits_remaining(), 0);
+        assert_eq!(board.opponent_view().hits().len(), 1);
+    }
+
+    #[test]
+    fn guesses_and_the_shot_log_announce_sinkings_by_the_boards_rule() {
+        let sunk = |name: Option<&str>, cells: Option<Vec<Coord>>| GuessResult::Sunk {
+            name: name.map(String::from),
+            cells,
+        };
+        let cells = vec![Coord::new(0, 0), Coord::new(0, 1)];
+        for (rule, told) in [
+            (SunkAnnouncement::HitOnly, GuessResult::Hit),
+            (SunkAnnouncement::SunkOnly, sunk(None, None)),
+            (SunkAnnouncement::ShipName, sunk(Some("Destroyer"), None)),
+            (
+                SunkAnnouncement::ShipPosition,
+                sunk(Some("Destroyer"), Some(cells.clone())),
+            ),
+        ] {
+            let config = GameConfig::square(4, &[("Destroyer", 2)]).with_sunk_announcement(rule);
+            let mut board = Board::with_config(&config);
+            board
+                .place_ship("Destroyer", Coord::new(0, 0), Orientation::Horizontal)
+                .unwrap();
+            board.confirm_fleet().unwrap();
+            board.guess(Coord::new(0, 0)).unwrap();
+            assert_eq!(
+                board.guess(Coord::new(0, 1)),
+                Ok(told.clone()),
+                "{:?}",
+                rule
+            );
+            assert_eq!(board.shot_log()[1].result, told, "{:?}", rule);
+        }
+    }
+
+    #[test]
+    fn a_new_guess_discards_the_redo_stack() {
+        let mut board = Board::new();
+        board
+            .randomly_place_fleet_with_rng(&mut crate::rng::seeded(3))
+            .unwrap();
+        board.guess(Coord::new(0, 0)).unwrap();
+        board.undo_last_guess();
+        board.guess(Coord::new(5, 5)).unwrap();
+        assert!(board.redo().is_none());
+        assert_eq!(board.shot_log().len(), 1);
+    }
+
+    #[test]
+    fn a_shot_that_cannot_be_redone_stays_on_the_redo_stack() {
+        let mut board = Board::new();
+        board
+            .randomly_place_fleet_with_rng(&mut crate::rng::seeded(3))
+            .unwrap();
+        board.undone.push(Coord::new(20, 20));
+        assert!(board.redo().is_none());
+        assert_eq!(board.undone, vec![Coord::new(20, 20)]);
+        assert!(board.shot_log().is_empty());
+    }
+
+    #[test]
+    fn snapshots_show_only_what_the_attacker_has_learned() {
+        let config = GameConfig::new(3, 3, &[("Destroyer", 2)])
+            .with_sunk_announcement(SunkAnnouncement::HitOnly);
+        let mut board = Board::with_config(&config);
+        board
+            .place_ship("Destroyer", Coord::new(0, 0), Orientation::Horizontal)
+            .unwrap();
+        board.confirm_fleet().unwrap();
+        board.guess(Coord::new(2, 2)).unwrap();
+        board.guess(Coord::new(0, 0)).unwrap();
+
+        let theirs = BoardState::new(&board, false);
+        assert_eq!(theirs.cells[0], [Cell::Hit, Cell::Empty, Cell::Empty]);
+        assert_eq!(theirs.cell(Coord::new(2, 2)), Cell::Miss);
+        let mine = BoardState::new(&board, true);
+        assert_eq!(mine.cells[0], [Cell::Hit, Cell::Ship, Cell::Empty]);
+
+        board.guess(Coord::new(0, 1)).unwrap();
+        let theirs = BoardState::new(&board, false);
+        assert_eq!(theirs.cells[0], [Cell::Hit, Cell::Hit, Cell::Empty]);
+        assert!(!theirs.ships[0].sunk);
+        assert_eq!(theirs.history[2].result, GuessResult::Hit);
+
+        let mine = BoardState::new(&board, true);
+        assert_eq!(mine.cells[0], [Cell::Sunk, Cell::Sunk, Cell::Empty]);
+        assert!(mine.ships[0].sunk);
+        assert_eq!(mine.ships[0].cells, [Coord::new(0, 0), Coord::new(0, 1)]);
+        // The shot log keeps what the attacker was told.
+        assert_eq!(mine.history[2].result, GuessResult::Hit);
+    }
+
+    #[test]
+    fn ship_position_rule_reveals_only_sunk_ships() {
+        let config = GameConfig::new(3, 3, &[("Destroyer", 2), ("Submarine", 1)])
+            .with_sunk_announcement(SunkAnnouncement::ShipPosition);
+        let mut board = Board::with_config(&config);
+        board
+            .place_ship("Destroyer", Coord::new(0, 0), Orientation::Horizontal)
+            .unwrap();
+        board
+            .place_ship("Submarine", Coord::new(2, 2), Orientation::Horizontal)
+            .unwrap();
+        board.confirm_fleet().unwrap();
+        board.guess(Coord::new(0, 1)).unwrap();
+        board.guess(Coord::new(0, 0)).unwrap();
+
+        let theirs = BoardState::new(&board, false);
+        assert_eq!(theirs.cells[0], [Cell::Sunk, Cell::Sunk, Cell::Empty]);
+        assert_eq!(theirs.cell(Coord::new(2, 2)), Cell::Empty);
+        assert_eq!(theirs.ships[0].cells, [Coord::new(0, 0), Coord::new(0, 1)]);
+        assert!(theirs.ships[1].cells.is_empty());
+        assert_eq!(
+            theirs.history[1].result,
+            GuessResult::Sunk {
+                name: Some("Destroyer".into()),
+                cells: Some(theirs.ships[0].cells.clone()),
+            }
+        );
+
+        let config = config.with_sunk_announcement(SunkAnnouncement::ShipName);
+        let mut board = Board::with_config(&config);
+        board
+            .place_ship("Destroyer", Coord::new(0, 0), Orientation::Horizontal)
+            .unwrap();
+        board
+            .place_ship("Submarine", Coord::new(2, 2), Orientation::Horizontal)
+            .unwrap();
+        board.confirm_fleet().unwrap();
+        board.guess(Coord::new(0, 1)).unwrap();
+        board.guess(Coord::new(0, 0)).unwrap();
+        let theirs = BoardState::new(&board, false);
+        assert_eq!(theirs.cells[0], [Cell::Hit, Cell::Hit, Cell::Empty]);
+        assert!(theirs.ships[0].sunk && theirs.ships[0].cells.is_empty());
+        assert_eq!(
+            theirs.history[1].result,
+            GuessResult::Sunk {
+                name: Some("Destroyer".into()),
+                cells: None
+            }
+        );
     }
 }
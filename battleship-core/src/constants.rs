@@ -1,14 +1,64 @@
 // Constants related to the game configuration
 
+use battleship_common::Coord;
+use battleship_config::{SunkAnnouncement, Weapon};
+use std::fmt;
+
 /// Result of a guess on the game board.
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum GuessResult {
     /// Shot missed all ships.
     Miss,
     /// Shot hit a ship but didn't sink it.
     Hit,
-    /// Shot hit and sunk a ship (includes ship name).
-    Sunk(&'static str),
+    /// Shot hit and sunk a ship.
+    Sunk {
+        /// Name of the sunk ship, or `None` when the rules keep it from the
+        /// attacker
+        name: Option<String>,
+        /// Every cell of the sunk ship, or `None` when the rules keep its
+        /// position from the attacker
+        cells: Option<Vec<Coord>>,
+    },
+    /// Shot set off one of the defender's hidden mines.
+    Mine,
+}
+
+impl GuessResult {
+    /// Returns what the attacker is told about this result under the
+    /// defender's [`SunkAnnouncement`] rule.
+    ///
+    /// # Example
+    /// ```
+    /// use battleship_core::{Coord, GuessResult, SunkAnnouncement};
+    /// let sunk = GuessResult::Sunk {
+    ///     name: Some("Destroyer".into()),
+    ///     cells: Some(vec![Coord::new(0, 0), Coord::new(0, 1)]),
+    /// };
+    /// assert_eq!(sunk.clone().announced(SunkAnnouncement::HitOnly), GuessResult::Hit);
+    /// assert_eq!(
+    ///     sunk.clone().announced(SunkAnnouncement::ShipName),
+    ///     GuessResult::Sunk { name: Some("Destroyer".into()), cells: None }
+    /// );
+    /// assert_eq!(
+    ///     sunk.clone().announced(SunkAnnouncement::SunkOnly),
+    ///     GuessResult::Sunk { name: None, cells: None }
+    /// );
+    /// assert_eq!(sunk.clone().announced(SunkAnnouncement::ShipPosition), sunk);
+    /// ```
+    pub fn announced(self, rule: SunkAnnouncement) -> Self {
+        match (rule, self) {
+            (SunkAnnouncement::HitOnly, GuessResult::Sunk { .. }) => GuessResult::Hit,
+            (SunkAnnouncement::ShipName, GuessResult::Sunk { name, .. }) => {
+                GuessResult::Sunk { name, cells: None }
+            }
+            (SunkAnnouncement::SunkOnly, GuessResult::Sunk { .. }) => GuessResult::Sunk {
+                name: None,
+                cells: None,
+            },
+            (_, result) => result,
+        }
+    }
 }
 
 impl std::fmt::Display for GuessResult {
@@ -16,37 +66,147 @@ impl std::fmt::Display for GuessResult {
         match self {
             GuessResult::Miss => write!(f, "Miss"),
             GuessResult::Hit => write!(f, "Hit"),
-            GuessResult::Sunk(name) => write!(f, "The {} was sunk!", name),
+            GuessResult::Sunk {
+                name: Some(name), ..
+            } => write!(f, "The {} was sunk!", name),
+            GuessResult::Sunk { name: None, .. } => write!(f, "A ship was sunk!"),
+            GuessResult::Mine => write!(f, "Boom! That was a mine"),
         }
     }
 }
 
+/// Outcome of a single shot: what it hit, or why it could not be fired.
+pub type ShotResult = Result<GuessResult, GuessError>;
+
 /// Represents possible errors during guess attempts
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum GuessError {
     /// Coordinate has already been guessed
-    AlreadyGuessed,
+    AlreadyGuessed(Coord),
     /// Coordinate is outside the valid grid
-    InvalidTarget,
+    InvalidTarget(Coord),
+    /// Coordinate is an island, which nobody fires at
+    Island(Coord),
+    /// The player has no uses left of the special weapon
+    OutOfAmmo(Weapon),
     /// No valid coordinates remain
     NoValidCoordinates,
     /// Random guess generation failed
     RandomGuessFailed,
 }
 
+impl fmt::Display for GuessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GuessError::AlreadyGuessed(c) => write!(f, "{} has already been guessed", c),
+            GuessError::InvalidTarget(c) => write!(f, "{} is not on the board", c),
+            GuessError::Island(c) => write!(f, "{} is an island", c),
+            GuessError::OutOfAmmo(w) => write!(f, "no {} left", w),
+            GuessError::NoValidCoordinates => write!(f, "every cell has already been guessed"),
+            GuessError::RandomGuessFailed => write!(f, "could not choose a random target"),
+        }
+    }
+}
+
+impl std::error::Error for GuessError {}
+
 /// Represents possible errors during gameplay
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum GameplayError {
-    /// Ship placement is invalid (overlaps or out of bounds)
-    InvalidPlacement,
+    /// The placement has a different number of cells than the ship's length
+    WrongLength {
+        /// Ship being placed
+        ship: String,
+        /// The ship's length
+        expected: usize,
+        /// Number of cells in the placement
+        found: usize,
+    },
+    /// The placement of the named ship is not a straight, unbroken line of
+    /// cells
+    NotContiguous(String),
+    /// The placement of the named ship does not form its shape in any
+    /// rotation or reflection
+    WrongShape(String),
+    /// The named ship was laid diagonally where the rules do not allow it
+    DiagonalNotAllowed(String),
+    /// The placement of the named ship covers an island
+    Aground(String),
+    /// The placement of the named ship covers a mine
+    OverMine(String),
+    /// The cell cannot hold a mine: it is off the board, an island, or
+    /// already holds a ship or mine
+    BadMineCell(Coord),
+    /// Every mine has already been laid
+    NoMinesLeft,
+    /// The placement of the named ship extends off the board
+    OutOfBounds(String),
+    /// The placement overlaps the named ship
+    Overlaps(String),
+    /// The placement touches the named ship where the adjacency rule
+    /// forbids it
+    TooClose(String),
     /// Cannot find a valid placement for the ship
     CantFindValidPlacement,
-    /// Referenced ship doesn't exist
-    ShipNotFound,
+    /// No ship has the given name
+    ShipNotFound(String),
     /// No valid coordinates available
     NoValidCoordinates,
+    /// The named ship has not been placed yet
+    ShipNotPlaced(String),
+    /// Not every ship and mine has been placed, so the fleet can't be
+    /// confirmed
+    FleetIncomplete,
+    /// The fleet has been confirmed and can no longer be changed
+    FleetLocked,
 }
 
+impl fmt::Display for GameplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameplayError::WrongLength {
+                ship,
+                expected,
+                found,
+            } => write!(f, "the {} needs {} cells, not {}", ship, expected, found),
+            GameplayError::NotContiguous(ship) => {
+                write!(f, "the {} must lie in one straight, unbroken line", ship)
+            }
+            GameplayError::WrongShape(ship) => {
+                write!(f, "those cells do not form the shape of the {}", ship)
+            }
+            GameplayError::DiagonalNotAllowed(ship) => {
+                write!(f, "the {} cannot lie diagonally", ship)
+            }
+            GameplayError::Aground(ship) => {
+                write!(f, "the {} would run aground on an island", ship)
+            }
+            GameplayError::OverMine(ship) => write!(f, "the {} would sit on a mine", ship),
+            GameplayError::BadMineCell(c) => write!(f, "{} cannot hold a mine", c),
+            GameplayError::NoMinesLeft => write!(f, "every mine has already been laid"),
+            GameplayError::OutOfBounds(ship) => {
+                write!(f, "the {} would not fit on the board", ship)
+            }
+            GameplayError::Overlaps(other) => write!(f, "that position overlaps the {}", other),
+            GameplayError::TooClose(other) => {
+                write!(f, "that position is too close to the {}", other)
+            }
+            GameplayError::CantFindValidPlacement => {
+                write!(f, "there is no room left for the fleet")
+            }
+            GameplayError::ShipNotFound(name) => write!(f, "there is no ship called {:?}", name),
+            GameplayError::NoValidCoordinates => write!(f, "no valid coordinates remain"),
+            GameplayError::ShipNotPlaced(ship) => write!(f, "the {} has not been placed", ship),
+            GameplayError::FleetIncomplete => {
+                write!(f, "every ship and mine must be placed first")
+            }
+            GameplayError::FleetLocked => write!(f, "the fleet has already been confirmed"),
+        }
+    }
+}
+
+impl std::error::Error for GameplayError {}
+
 /// Represents the state of a player
 #[derive(Debug, PartialEq, Clone)]
 pub enum PlayerState {
@@ -59,18 +219,24 @@ pub enum PlayerState {
 }
 
 /// Represents different cell states on the game board
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Cell {
-    /// Empty cell
+    /// Not fired at, and not known to hold a ship
     Empty,
-    /// Contains a ship
+    /// Contains a ship that has not been hit
     Ship,
     // Preview of ship placement
     //ShipPrev,
     /// Hit on a ship
     Hit,
+    /// Hit on a ship known to be sunk
+    Sunk,
     /// Missed shot
     Miss,
+    /// Island, where no ship lies and nobody fires
+    Island,
+    /// Mine, shown to its owner and to whoever set it off
+    Mine,
     // Cursor position
     //Cursor,
 }
@@ -86,7 +252,10 @@ impl Cell {
             Cell::Ship => '■',
             //Cell::ShipPrev => '☐',
             Cell::Hit => 'X',
+            Cell::Sunk => '#',
             Cell::Miss => 'O',
+            Cell::Island => '▲',
+            Cell::Mine => '*',
             //Cell::Cursor => '⌖',
         }
     }
@@ -1,24 +1,29 @@
-use std::collections::HashSet;
-
+use crate::bitboard::Bitboard;
 use crate::constants::GameplayError;
 use crate::constants::GuessError;
 use crate::GuessResult;
 
-use battleship_config::SHIPS;
-
-use crate::constants::GameplayError::ShipNotFound;
-use crate::GuessResult::{Hit, Miss, Sunk};
-use crate::ship::Ship;
+use battleship_config::GameConfig;
 
-use array_init::array_init;
+use crate::constants::GameplayError::{OutOfBounds, Overlaps, ShipNotFound};
+use crate::ship::{Ship, ShipId};
+use crate::GuessResult::{Mine, Miss};
 
 /// Manages a collection of ships for a player.
 ///
 /// The Fleet struct handles ship placement, tracking hits,
 /// and maintaining the overall state of all ships in play.
 pub struct Fleet {
-    /// Array of all ships in the fleet
-    ships: [Ship; SHIPS.len()],
+    /// Number of rows on the board the fleet is placed on
+    rows: usize,
+    /// Number of columns on the board the fleet is placed on
+    cols: usize,
+    /// All ships in the fleet
+    ships: Vec<Ship>,
+    /// Cells holding the fleet's mines
+    mines: Bitboard,
+    /// Number of mines the fleet hides once fully laid
+    mine_count: usize,
 }
 
 impl Fleet {
@@ -30,14 +35,55 @@ impl Fleet {
     /// # Returns
     /// * `Fleet` - New fleet with unplaced ships
     pub fn new() -> Self {
+        Self::from_config(&GameConfig::default())
+    }
+
+    /// Creates a new fleet for the board and ship classes in a configuration.
+    ///
+    /// Every ship gets a unique name; see [`GameConfig::ship_list`].
+    ///
+    /// # Arguments
+    /// * `config` - Board dimensions and the classes of ship in the fleet
+    ///
+    /// # Returns
+    /// * `Fleet` - New fleet with unplaced ships
+    pub fn from_config(config: &GameConfig) -> Self {
         Self {
-            ships: array_init(|i: usize| {
-                let (name, length) = SHIPS[i];
-                Ship::new(name, length)
-            }),
+            rows: config.rows,
+            cols: config.cols,
+            ships: config
+                .ship_shapes()
+                .into_iter()
+                .map(|(name, shape)| Ship::with_shape(name, shape))
+                .collect(),
+            mines: Bitboard::new(config.rows, config.cols),
+            mine_count: config.mines,
         }
     }
 
+    /// Returns the ids of every ship in the fleet, in fleet order.
+    pub fn ids(&self) -> impl Iterator<Item = ShipId> {
+        (0..self.ships.len()).map(ShipId)
+    }
+
+    /// Looks up the id of the ship with the given name.
+    ///
+    /// # Errors
+    /// Returns `ShipNotFound` if no ship matches the given name
+    pub fn id_of(&self, name: &str) -> Result<ShipId, GameplayError> {
+        self.ships
+            .iter()
+            .position(|ship| ship.name() == name)
+            .map(ShipId)
+            .ok_or_else(|| ShipNotFound(name.to_string()))
+    }
+
+    /// Gets a reference to a ship by id, or `None` if the id belongs to
+    /// another fleet.
+    pub fn ship(&self, id: ShipId) -> Option<&Ship> {
+        self.ships.get(id.0)
+    }
+
     /// Gets a reference to a ship by name.
     ///
     /// # Arguments
@@ -49,10 +95,8 @@ impl Fleet {
     /// # Errors
     /// Returns `ShipNotFound` if no ship matches the given name
     pub fn get_ship(&self, name: &str) -> Result<&Ship, GameplayError> {
-        self.ships
-            .iter()
-            .find(|ship| ship.name() == name)
-            .ok_or(ShipNotFound)
+        let id = self.id_of(name)?;
+        Ok(&self.ships[id.0])
     }
 
     /// Gets a mutable reference to a ship by name.
@@ -66,10 +110,8 @@ impl Fleet {
     /// # Errors
     /// Returns `ShipNotFound` if no ship matches the given name
     fn get_ship_mut(&mut self, name: &str) -> Result<&mut Ship, GameplayError> {
-        self.ships
-            .iter_mut()
-            .find(|ship| ship.name() == name)
-            .ok_or(ShipNotFound)
+        let id = self.id_of(name)?;
+        Ok(&mut self.ships[id.0])
     }
 
     /// Gets an iterator over ships based on their sunk status.
@@ -105,7 +147,8 @@ impl Fleet {
 
     /// Processes a guess against the fleet.
     ///
-    /// Checks each unsunk ship to see if the guess hits it.
+    /// A guess on a mine sets it off; otherwise each unsunk ship is checked
+    /// to see if the guess hits it.
     ///
     /// # Arguments
     /// * `target` - Coordinate being targeted
@@ -113,20 +156,31 @@ impl Fleet {
     /// # Returns
     /// * `Result<GuessResult, GuessError>` - Result of the guess
     pub fn guess(&mut self, target: (usize, usize)) -> Result<GuessResult, GuessError> {
+        if self.mines.contains(target) {
+            return Ok(Mine);
+        }
         for ship in &mut self.ships {
             if ship.is_sunk() {
                 continue;
             }
-            match ship.guess(target) {
-                Ok(Hit) => return Ok(Hit),
-                Ok(Sunk(name)) => return Ok(Sunk(name)),
-                Ok(Miss) => continue,
-                Err(err) => return Err(err),
+            match ship.guess(target)? {
+                Miss => continue,
+                struck => return Ok(struck),
             }
         }
         Ok(Miss)
     }
 
+    /// Reverts a guess made by [`Fleet::guess`], removing the hit from
+    /// whichever ship it struck. Misses leave the fleet unchanged.
+    pub(crate) fn undo_guess(&mut self, target: (usize, usize)) {
+        for ship in &mut self.ships {
+            if ship.undo_hit(target) {
+                return;
+            }
+        }
+    }
+
     /// Gets an iterator over unplaced ships.
     ///
     /// # Returns
@@ -143,13 +197,88 @@ impl Fleet {
     ///
     /// # Returns
     /// * `Result<(), GameplayError>` - Ok if successful, Error if placement invalid
-    pub fn place_ship(
+    ///
+    /// # Errors
+    /// Returns `ShipNotFound` for an unknown name, `OutOfBounds` if `coords`
+    /// belongs to a board of another size, `Overlaps` if another ship already
+    /// holds one of the cells, `OverMine` if a mine does, and any error from
+    /// [`Ship::place`]
+    pub fn place_ship(&mut self, name: &str, coords: Bitboard) -> Result<(), GameplayError> {
+        let id = self.id_of(name)?;
+        self.place_ship_by_id(id, coords)
+    }
+
+    /// Places the ship with the given id, checking the same rules as
+    /// [`Fleet::place_ship`].
+    pub(crate) fn place_ship_by_id(
         &mut self,
-        name: &str,
-        coords: HashSet<(usize, usize)>,
+        id: ShipId,
+        coords: Bitboard,
     ) -> Result<(), GameplayError> {
-        let ship = self.get_ship_mut(name)?;
-        ship.place(coords)
+        let ship_name = self.ships[id.0].name().to_string();
+        if coords.rows() != self.rows || coords.cols() != self.cols {
+            return Err(OutOfBounds(ship_name));
+        }
+        let overlapped = self
+            .ships
+            .iter()
+            .enumerate()
+            .filter(|&(i, s)| s.is_placed() && i != id.0)
+            .map(|(_, s)| s)
+            .find(|s| !s.coords().is_disjoint(&coords));
+        if let Some(other) = overlapped {
+            return Err(Overlaps(other.name().to_string()));
+        }
+        if !self.mines.is_disjoint(&coords) {
+            return Err(GameplayError::OverMine(ship_name));
+        }
+        self.ships[id.0].place(coords)
+    }
+
+    /// Lays a mine on an empty cell.
+    ///
+    /// # Errors
+    /// Returns `NoMinesLeft` once every mine is laid and `BadMineCell` if
+    /// the cell is off the board or already holds a ship or mine
+    pub fn place_mine(&mut self, cell: (usize, usize)) -> Result<(), GameplayError> {
+        if self.unlaid_mines() == 0 {
+            return Err(GameplayError::NoMinesLeft);
+        }
+        let taken = self.ship_coords(true, true);
+        if cell.0 >= self.rows || cell.1 >= self.cols || taken.contains(cell) {
+            return Err(GameplayError::BadMineCell(cell.into()));
+        }
+        if !self.mines.insert(cell) {
+            return Err(GameplayError::BadMineCell(cell.into()));
+        }
+        Ok(())
+    }
+
+    /// Cells holding the mines laid so far.
+    pub fn mines(&self) -> &Bitboard {
+        &self.mines
+    }
+
+    /// Number of mines still to be laid.
+    pub fn unlaid_mines(&self) -> usize {
+        self.mine_count - self.mines.len()
+    }
+
+    /// Number of mines the fleet hides once fully laid.
+    pub fn mine_count(&self) -> usize {
+        self.mine_count
+    }
+
+    /// Takes a ship off the board.
+    ///
+    /// # Arguments
+    /// * `name` - Name of the ship to remove
+    ///
+    /// # Errors
+    /// Returns `ShipNotFound` if no ship matches the given name
+    pub fn unplace_ship(&mut self, name: &str) -> Result<(), GameplayError> {
+        self.get_ship_mut(name)?.unplace();
+        Ok(())
     }
 
     /// Gets the coordinates of ships based on their sunk status.
@@ -159,11 +288,9 @@ impl Fleet {
     /// * `sunk` - Include coordinates of sunk ships
     ///
     /// # Returns
-    /// * `HashSet<(usize,usize)>` - Set of coordinates for matching ships
-    pub fn ship_coords(&self, unsunk: bool, sunk: bool) -> HashSet<(usize, usize)> {
-        self.get_ships(unsunk, sunk)
-            .flat_map(|s: &Ship| s.coords().iter().cloned())
-            .collect()
+    /// * `Bitboard` - Set of coordinates for matching ships
+    pub fn ship_coords(&self, unsunk: bool, sunk: bool) -> Bitboard {
+        self.collect_coords(unsunk, sunk, Ship::coords)
     }
 
     /// Gets the coordinates of hits on ships based on their sunk status.
@@ -173,11 +300,23 @@ impl Fleet {
     /// * `sunk` - Include hits on sunk ships
     ///
     /// # Returns
-    /// * `HashSet<(usize,usize)>` - Set of hit coordinates for matching ships
-    pub fn hit_coords(&self, unsunk: bool, sunk: bool) -> HashSet<(usize, usize)> {
-        self.get_ships(unsunk, sunk)
-            .flat_map(|s: &Ship| s.hits().iter().cloned())
-            .collect()
+    /// * `Bitboard` - Set of hit coordinates for matching ships
+    pub fn hit_coords(&self, unsunk: bool, sunk: bool) -> Bitboard {
+        self.collect_coords(unsunk, sunk, Ship::hits)
+    }
+
+    /// Unions one per-ship bitboard across the ships matching the sunk filter.
+    fn collect_coords(
+        &self,
+        unsunk: bool,
+        sunk: bool,
+        coords: impl Fn(&Ship) -> &Bitboard,
+    ) -> Bitboard {
+        let mut out = Bitboard::new(self.rows, self.cols);
+        for ship in self.get_ships(unsunk, sunk).filter(|s| s.is_placed()) {
+            out.union_with(coords(ship));
+        }
+        out
     }
     /// Counts the number of ships based on their sunk status.
     ///
@@ -238,10 +377,76 @@ impl Fleet {
 
     /// Returns the status for each ship as a tuple of name, length and whether
     /// it has been sunk.
-    pub fn ship_statuses(&self) -> Vec<(&'static str, usize, bool)> {
+    pub fn ship_statuses(&self) -> Vec<(&str, usize, bool)> {
         self.ships
             .iter()
             .map(|s| (s.name(), s.length(), s.is_sunk()))
             .collect()
     }
 }
+
+impl Default for Fleet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::GameplayError::{NotContiguous, WrongLength};
+    use crate::GuessResult::Hit;
+
+    fn fleet() -> Fleet {
+        Fleet::from_config(&GameConfig::square(6, &[("Cruiser", 3), ("Destroyer", 2)]))
+    }
+
+    #[test]
+    fn place_ship_rejects_malformed_and_overlapping_ships() {
+        let mut fleet = fleet();
+        let scattered = Bitboard::from_coords(6, 6, [(0, 0), (2, 3), (5, 5)]);
+        assert_eq!(
+            fleet.place_ship("Cruiser", scattered),
+            Err(NotContiguous("Cruiser".into()))
+        );
+        let short = Bitboard::from_coords(6, 6, [(0, 0), (0, 1)]);
+        assert!(matches!(
+            fleet.place_ship("Cruiser", short),
+            Err(WrongLength { .. })
+        ));
+        let wrong_board = Bitboard::from_coords(10, 10, [(0, 0), (0, 1), (0, 2)]);
+        assert_eq!(
+            fleet.place_ship("Cruiser", wrong_board),
+            Err(OutOfBounds("Cruiser".into()))
+        );
+
+        let cruiser = Bitboard::from_coords(6, 6, [(1, 0), (1, 1), (1, 2)]);
+        fleet.place_ship("Cruiser", cruiser).unwrap();
+        let crossing = Bitboard::from_coords(6, 6, [(0, 1), (1, 1)]);
+        assert_eq!(
+            fleet.place_ship("Destroyer", crossing),
+            Err(Overlaps("Cruiser".into()))
+        );
+        assert_eq!(fleet.unplaced_ships().count(), 1);
+    }
+
+    #[test]
+    fn mines_go_off_and_keep_ships_away() {
+        let config = GameConfig::square(6, &[("Destroyer", 2)]).with_mines(1, Default::default());
+        let mut fleet = Fleet::from_config(&config);
+        fleet.place_mine((2, 2)).unwrap();
+        assert_eq!(fleet.place_mine((3, 3)), Err(GameplayError::NoMinesLeft));
+        let over = Bitboard::from_coords(6, 6, [(2, 1), (2, 2)]);
+        assert_eq!(
+            fleet.place_ship("Destroyer", over),
+            Err(GameplayError::OverMine("Destroyer".into()))
+        );
+
+        fleet
+            .place_ship("Destroyer", Bitboard::from_coords(6, 6, [(0, 0), (0, 1)]))
+            .unwrap();
+        assert_eq!(fleet.guess((2, 2)), Ok(Mine));
+        assert_eq!(fleet.guess((0, 0)), Ok(Hit));
+        assert_eq!(fleet.hits_remaining(), 1);
+    }
+}
@@ -1,12 +1,29 @@
+pub mod analysis;
+pub mod bitboard;
 pub mod board;
 pub mod constants;
 pub mod fleet;
+pub mod orientation;
+pub mod placement;
+pub mod rng;
 pub mod ship;
+pub mod view;
+pub mod weapon;
 
-pub use battleship_common::BoardView;
-pub use battleship_config::{GRID_SIZE, SHIPS};
+pub use analysis::{FleetReport, LayoutCount};
+pub use battleship_common::{BoardView, Coord};
+pub use battleship_config::{
+    AdjacencyRule, Arsenal, ConfigError, Difficulty, GameConfig, MinePenalty, RuleSet, Settings,
+    Shape, ShipClass, ShotsPerTurn, SunkAnnouncement, Terrain, Weapon, GRID_SIZE, SHIPS,
+};
+pub use bitboard::Bitboard;
 pub use board::Board;
-pub use board::BoardState;
-pub use constants::{Cell, GameplayError, GuessError, GuessResult, PlayerState};
+pub use board::Shot;
+pub use board::{BoardState, ShipStatus};
+pub use constants::{Cell, GameplayError, GuessError, GuessResult, PlayerState, ShotResult};
 pub use fleet::Fleet;
-pub use ship::Ship;
+pub use orientation::{Orientation, UnknownOrientation};
+pub use placement::PlacementStrategy;
+pub use ship::{Ship, ShipId};
+pub use view::OpponentView;
+pub use weapon::{Action, ActionResult, Ping, WeaponResult};
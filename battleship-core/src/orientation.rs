@@ -0,0 +1,180 @@
+//! Directions a straight ship can lie in.
+
+use crate::bitboard::Bitboard;
+use std::fmt;
+use std::str::FromStr;
+
+/// Direction a straight ship extends from its starting cell.
+///
+/// Diagonal orientations are only legal on boards configured with
+/// [`GameConfig::diagonal_ships`](battleship_config::GameConfig::diagonal_ships).
+///
+/// # Example
+/// ```
+/// use battleship_core::Orientation;
+/// let up: Orientation = "du".parse().unwrap();
+/// assert_eq!(up, Orientation::DiagonalUp);
+/// assert_eq!(up.step(), (-1, 1));
+/// assert_eq!(Orientation::allowed(false), &[Orientation::Horizontal, Orientation::Vertical]);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Orientation {
+    /// Extends to the right along a row
+    Horizontal,
+    /// Extends downwards along a column
+    Vertical,
+    /// Extends down and to the right
+    DiagonalDown,
+    /// Extends up and to the right
+    DiagonalUp,
+}
+
+impl Orientation {
+    /// Every orientation, straight ones first.
+    pub const ALL: [Orientation; 4] = [
+        Orientation::Horizontal,
+        Orientation::Vertical,
+        Orientation::DiagonalDown,
+        Orientation::DiagonalUp,
+    ];
+
+    /// The orientations a straight ship may take.
+    ///
+    /// # Arguments
+    /// * `diagonals` - Whether the rules let ships lie diagonally
+    pub fn allowed(diagonals: bool) -> &'static [Orientation] {
+        if diagonals {
+            &Self::ALL
+        } else {
+            &Self::ALL[..2]
+        }
+    }
+
+    /// `(row, col)` change from one cell of the ship to the next.
+    pub fn step(&self) -> (isize, isize) {
+        match self {
+            Orientation::Horizontal => (0, 1),
+            Orientation::Vertical => (1, 0),
+            Orientation::DiagonalDown => (1, 1),
+            Orientation::DiagonalUp => (-1, 1),
+        }
+    }
+
+    /// Returns true for the two diagonal orientations.
+    pub fn is_diagonal(&self) -> bool {
+        matches!(self, Orientation::DiagonalDown | Orientation::DiagonalUp)
+    }
+
+    /// Works out which way a set of cells lies, if it forms one unbroken
+    /// straight line. A single cell counts as horizontal.
+    ///
+    /// # Example
+    /// ```
+    /// use battleship_core::{Bitboard, Orientation};
+    /// let up = Bitboard::from_coords(5, 5, [(2, 0), (1, 1), (0, 2)]);
+    /// assert_eq!(Orientation::of_line(&up), Some(Orientation::DiagonalUp));
+    /// let gap = Bitboard::from_coords(5, 5, [(0, 0), (2, 2)]);
+    /// assert_eq!(Orientation::of_line(&gap), None);
+    /// ```
+    pub fn of_line(cells: &Bitboard) -> Option<Orientation> {
+        // Cells come out row by row, so a rising diagonal is walked from its
+        // top-right end.
+        let cells: Vec<(usize, usize)> = cells.iter().collect();
+        let (&(row, col), rest) = cells.split_first()?;
+        let Some(&(row2, col2)) = rest.first() else {
+            return Some(Orientation::Horizontal);
+        };
+        let (orientation, step) = match (row2 as isize - row as isize, col2 as isize - col as isize)
+        {
+            (0, 1) => (Orientation::Horizontal, (0, 1)),
+            (1, 0) => (Orientation::Vertical, (1, 0)),
+            (1, 1) => (Orientation::DiagonalDown, (1, 1)),
+            (1, -1) => (Orientation::DiagonalUp, (1, -1)),
+            _ => return None,
+        };
+        let on_line = cells.iter().enumerate().all(|(i, &(r, c))| {
+            let i = i as isize;
+            r as isize == row as isize + i * step.0 && c as isize == col as isize + i * step.1
+        });
+        on_line.then_some(orientation)
+    }
+
+    /// Short name used when typing a placement, e.g. `h` or `du`.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Orientation::Horizontal => "h",
+            Orientation::Vertical => "v",
+            Orientation::DiagonalDown => "dd",
+            Orientation::DiagonalUp => "du",
+        }
+    }
+}
+
+impl fmt::Display for Orientation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Orientation::Horizontal => "horizontal",
+            Orientation::Vertical => "vertical",
+            Orientation::DiagonalDown => "diagonal down",
+            Orientation::DiagonalUp => "diagonal up",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Orientation {
+    type Err = UnknownOrientation;
+
+    /// Parses an orientation from its [`key`](Orientation::key) or its full
+    /// name, ignoring case; spaces, hyphens and underscores in full names
+    /// are interchangeable.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized: String = s
+            .trim()
+            .chars()
+            .filter(|c| !matches!(c, ' ' | '-' | '_'))
+            .collect::<String>()
+            .to_ascii_lowercase();
+        Orientation::ALL
+            .into_iter()
+            .find(|o| o.key() == normalized || o.to_string().replace(' ', "") == normalized)
+            .ok_or_else(|| UnknownOrientation(s.trim().to_string()))
+    }
+}
+
+/// Text that names no [`Orientation`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownOrientation(pub String);
+
+impl fmt::Display for UnknownOrientation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let keys: Vec<_> = Orientation::ALL.iter().map(Orientation::key).collect();
+        write!(
+            f,
+            "unknown orientation {:?}; expected one of {}",
+            self.0,
+            keys.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for UnknownOrientation {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orientations_parse_from_keys_and_names() {
+        for orientation in Orientation::ALL {
+            assert_eq!(orientation.key().parse(), Ok(orientation));
+            assert_eq!(orientation.to_string().parse(), Ok(orientation));
+        }
+        assert_eq!("Diagonal-Down".parse(), Ok(Orientation::DiagonalDown));
+        assert_eq!(" V ".parse(), Ok(Orientation::Vertical));
+        assert_eq!(
+            "sideways".parse::<Orientation>(),
+            Err(UnknownOrientation("sideways".into()))
+        );
+    }
+}
@@ -0,0 +1,421 @@
+//! Random fleet layout sampling.
+//!
+//! The sampler works on pre-enumerated candidate placements: for each ship it
+//! is handed every placement that ship could legally take on its own, and it
+//! picks one candidate per ship such that no chosen ship lies in another's
+//! exclusion zone. Because the candidates are enumerated up front, sampling
+//! never depends on lucky retries of random start coordinates.
+
+use crate::bitboard::Bitboard;
+use crate::rng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::{HashMap, HashSet};
+
+/// One position a ship could take.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Candidate {
+    /// Cells the ship occupies
+    pub cells: Bitboard,
+    /// Cells no other ship may occupy once this one is placed: the ship's
+    /// own cells plus any neighbours forbidden by the adjacency rule
+    pub zone: Bitboard,
+}
+
+/// How random fleet placement chooses among legal layouts.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PlacementStrategy {
+    /// Depth-first search over shuffled candidate placements.
+    ///
+    /// Always finds a layout when one exists and is fast even on crowded
+    /// boards, but layouts are not exactly uniformly distributed.
+    #[default]
+    Backtracking,
+    /// Every legal layout is equally likely.
+    ///
+    /// Uses rejection sampling over independently drawn placements after
+    /// first confirming that a layout exists. This matches the uniform prior
+    /// assumed by the posterior AI; expected running time grows as the board
+    /// gets more crowded.
+    Uniform,
+}
+
+/// Chooses one candidate per ship so that no chosen ship overlaps `blocked`
+/// or another chosen ship's zone.
+///
+/// # Arguments
+/// * `candidates` - For each ship, every placement it may take
+/// * `blocked` - Cells no ship may use (e.g. the zones of ships already on
+///   the board)
+/// * `strategy` - How to choose among legal layouts
+/// * `rng` - Source of randomness
+///
+/// # Returns
+/// * `Option<Vec<Bitboard>>` - The cells chosen for each ship, in the order
+///   of `candidates`, or `None` if no legal layout exists
+pub fn sample<R: Rng + ?Sized>(
+    candidates: &[Vec<Candidate>],
+    blocked: &Bitboard,
+    strategy: PlacementStrategy,
+    rng: &mut R,
+) -> Option<Vec<Bitboard>> {
+    let candidates = unblocked(candidates, blocked);
+    let layout = backtrack(&candidates, blocked, rng)?;
+    match strategy {
+        PlacementStrategy::Backtracking => Some(layout),
+        PlacementStrategy::Uniform => Some(rejection_sample(&candidates, blocked, rng)),
+    }
+}
+
+/// Returns true if at least one legal layout exists.
+pub fn exists(candidates: &[Vec<Candidate>], blocked: &Bitboard) -> bool {
+    let candidates = unblocked(candidates, blocked);
+    backtrack(&candidates, blocked, &mut rng::seeded(0)).is_some()
+}
+
+/// Counts the legal layouts exactly, treating every ship as distinct.
+///
+/// Partial layouts that leave the same cells free are counted once and
+/// reused, but the number of such states can still grow exponentially with
+/// the board, so the search gives up after visiting `budget` of them.
+///
+/// # Arguments
+/// * `candidates` - For each ship, every placement it may take
+/// * `blocked` - Cells no ship may use
+/// * `budget` - Maximum number of distinct partial layouts to examine
+///
+/// # Returns
+/// * `Option<u128>` - The number of layouts, or `None` if the budget ran out
+pub fn count(candidates: &[Vec<Candidate>], blocked: &Bitboard, budget: usize) -> Option<u128> {
+    let candidates = unblocked(candidates, blocked);
+    let order = most_constrained_first(&candidates);
+
+    fn search(
+        depth: usize,
+        order: &[usize],
+        candidates: &[Vec<Candidate>],
+        used: &Bitboard,
+        memo: &mut HashMap<(usize, Bitboard), u128>,
+        budget: usize,
+    ) -> Option<u128> {
+        if depth == order.len() {
+            return Some(1);
+        }
+        if let Some(&n) = memo.get(&(depth, used.clone())) {
+            return Some(n);
+        }
+        if memo.len() >= budget {
+            return None;
+        }
+        let mut total: u128 = 0;
+        for placement in candidates[order[depth]].iter() {
+            if placement.cells.is_disjoint(used) {
+                let next = used.union(&placement.zone);
+                let n = search(depth + 1, order, candidates, &next, memo, budget)?;
+                total = total.saturating_add(n);
+            }
+        }
+        memo.insert((depth, used.clone()), total);
+        Some(total)
+    }
+
+    search(0, &order, &candidates, blocked, &mut HashMap::new(), budget)
+}
+
+/// Estimates the number of legal layouts, treating every ship as distinct.
+///
+/// Each sample places the ships one at a time, choosing uniformly among the
+/// placements still open and multiplying together how many there were
+/// (Knuth's estimator for the size of a search tree). The mean over all
+/// samples is an unbiased estimate of the layout count.
+///
+/// # Arguments
+/// * `candidates` - For each ship, every placement it may take
+/// * `blocked` - Cells no ship may use
+/// * `samples` - Number of random descents to average over
+/// * `rng` - Source of randomness
+///
+/// # Returns
+/// * `f64` - Estimated number of layouts
+pub fn estimate_count<R: Rng + ?Sized>(
+    candidates: &[Vec<Candidate>],
+    blocked: &Bitboard,
+    samples: usize,
+    rng: &mut R,
+) -> f64 {
+    let candidates = unblocked(candidates, blocked);
+    let order = most_constrained_first(&candidates);
+    let mut total = 0.0;
+    for _ in 0..samples {
+        let mut used = blocked.clone();
+        let mut weight = 1.0;
+        for &ship in &order {
+            let options: Vec<&Candidate> = candidates[ship]
+                .iter()
+                .filter(|p| p.cells.is_disjoint(&used))
+                .collect();
+            let Some(placement) = options.choose(rng) else {
+                weight = 0.0;
+                break;
+            };
+            weight *= options.len() as f64;
+            used.union_with(&placement.zone);
+        }
+        total += weight;
+    }
+    total / samples.max(1) as f64
+}
+
+/// Orders ships so that those with the fewest placements come first.
+fn most_constrained_first(candidates: &[Vec<Candidate>]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..candidates.len()).collect();
+    order.sort_by_key(|&i| candidates[i].len());
+    order
+}
+
+/// Drops the candidates that overlap `blocked`.
+fn unblocked(candidates: &[Vec<Candidate>], blocked: &Bitboard) -> Vec<Vec<Candidate>> {
+    candidates
+        .iter()
+        .map(|c| {
+            c.iter()
+                .filter(|p| p.cells.is_disjoint(blocked))
+                .cloned()
+                .collect()
+        })
+        .collect()
+}
+
+/// Randomised depth-first search for one legal layout.
+fn backtrack<R: Rng + ?Sized>(
+    candidates: &[Vec<Candidate>],
+    blocked: &Bitboard,
+    rng: &mut R,
+) -> Option<Vec<Bitboard>> {
+    // Place the most constrained ships first to fail early.
+    let order = most_constrained_first(candidates);
+
+    fn search<R: Rng + ?Sized>(
+        depth: usize,
+        order: &[usize],
+        candidates: &[Vec<Candidate>],
+        used: &Bitboard,
+        chosen: &mut Vec<Option<Bitboard>>,
+        dead_ends: &mut HashSet<(usize, Bitboard)>,
+        rng: &mut R,
+    ) -> bool {
+        if depth == order.len() {
+            return true;
+        }
+        if dead_ends.contains(&(depth, used.clone())) {
+            return false;
+        }
+        let ship = order[depth];
+        let mut options: Vec<&Candidate> = candidates[ship]
+            .iter()
+            .filter(|p| p.cells.is_disjoint(used))
+            .collect();
+        options.shuffle(rng);
+        for placement in options {
+            chosen[ship] = Some(placement.cells.clone());
+            let next = used.union(&placement.zone);
+            if search(depth + 1, order, candidates, &next, chosen, dead_ends, rng) {
+                return true;
+            }
+        }
+        chosen[ship] = None;
+        dead_ends.insert((depth, used.clone()));
+        false
+    }
+
+    let mut chosen = vec![None; candidates.len()];
+    let mut dead_ends = HashSet::new();
+    if search(
+        0,
+        &order,
+        candidates,
+        blocked,
+        &mut chosen,
+        &mut dead_ends,
+        rng,
+    ) {
+        Some(
+            chosen
+                .into_iter()
+                .map(|p| p.expect("every ship is placed"))
+                .collect(),
+        )
+    } else {
+        None
+    }
+}
+
+/// Draws each ship's placement independently and uniformly, restarting the
+/// whole fleet on any conflict. Because zones are symmetric (ship A lies in
+/// B's zone exactly when B lies in A's), accepted layouts are exactly uniform.
+///
+/// Callers must ensure at least one layout exists.
+fn rejection_sample<R: Rng + ?Sized>(
+    candidates: &[Vec<Candidate>],
+    blocked: &Bitboard,
+    rng: &mut R,
+) -> Vec<Bitboard> {
+    'attempt: loop {
+        let mut used = blocked.clone();
+        let mut layout = Vec::with_capacity(candidates.len());
+        for options in candidates {
+            let placement = options.choose(rng).expect("layout exists");
+            if !placement.cells.is_disjoint(&used) {
+                continue 'attempt;
+            }
+            used.union_with(&placement.zone);
+            layout.push(placement.cells.clone());
+        }
+        return layout;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    /// All straight placements of a ship of `length` on a `rows`×`cols` board,
+    /// with zones grown by `offsets`.
+    fn lines_with(
+        rows: usize,
+        cols: usize,
+        length: usize,
+        offsets: &[(isize, isize)],
+    ) -> Vec<Candidate> {
+        let mut out = Vec::new();
+        for r in 0..rows {
+            for c in 0..cols {
+                let mut cells = Vec::new();
+                if c + length <= cols {
+                    cells.push(Bitboard::from_coords(
+                        rows,
+                        cols,
+                        (0..length).map(|k| (r, c + k)),
+                    ));
+                }
+                if length > 1 && r + length <= rows {
+                    cells.push(Bitboard::from_coords(
+                        rows,
+                        cols,
+                        (0..length).map(|k| (r + k, c)),
+                    ));
+                }
+                out.extend(cells.into_iter().map(|cells| Candidate {
+                    zone: cells.expand(offsets),
+                    cells,
+                }));
+            }
+        }
+        out
+    }
+
+    fn lines(rows: usize, cols: usize, length: usize) -> Vec<Candidate> {
+        lines_with(rows, cols, length, &[])
+    }
+
+    #[test]
+    fn backtracking_fills_a_fully_packed_board() {
+        let candidates = vec![lines(4, 4, 4); 4];
+        let blocked = Bitboard::new(4, 4);
+        let mut rng = rng::seeded(1);
+        for _ in 0..50 {
+            let layout = sample(
+                &candidates,
+                &blocked,
+                PlacementStrategy::Backtracking,
+                &mut rng,
+            )
+            .unwrap();
+            let mut used = Bitboard::new(4, 4);
+            for p in &layout {
+                assert!(p.is_disjoint(&used));
+                used.union_with(p);
+            }
+            assert_eq!(used.len(), 16);
+        }
+    }
+
+    #[test]
+    fn impossible_fleet_is_reported() {
+        let candidates = vec![lines(3, 3, 3); 4];
+        let blocked = Bitboard::new(3, 3);
+        assert!(!exists(&candidates, &blocked));
+        let mut rng = rng::seeded(1);
+        assert!(sample(&candidates, &blocked, PlacementStrategy::Uniform, &mut rng).is_none());
+    }
+
+    #[test]
+    fn uniform_strategy_is_uniform_over_layouts() {
+        // A 2x3 board with a length-2 and a length-1 ship.
+        let candidates = vec![lines(2, 3, 2), lines(2, 3, 1)];
+        let blocked = Bitboard::new(2, 3);
+        let n_layouts: usize = candidates[0]
+            .iter()
+            .map(|a| {
+                candidates[1]
+                    .iter()
+                    .filter(|b| a.cells.is_disjoint(&b.cells))
+                    .count()
+            })
+            .sum();
+
+        let mut rng = rng::seeded(99);
+        let trials = 40_000;
+        let mut counts: HashMap<Vec<Bitboard>, usize> = HashMap::new();
+        for _ in 0..trials {
+            let layout =
+                sample(&candidates, &blocked, PlacementStrategy::Uniform, &mut rng).unwrap();
+            *counts.entry(layout).or_default() += 1;
+        }
+
+        assert_eq!(counts.len(), n_layouts);
+        let expected = trials as f64 / n_layouts as f64;
+        for &count in counts.values() {
+            assert!((count as f64 - expected).abs() < expected * 0.15);
+        }
+    }
+
+    #[test]
+    fn counting_matches_enumeration() {
+        // A 2x3 board with a length-2 and a length-1 ship.
+        let candidates = vec![lines(2, 3, 2), lines(2, 3, 1)];
+        let blocked = Bitboard::new(2, 3);
+        assert_eq!(count(&candidates, &blocked, 1000), Some(28));
+        // Four length-4 ships fill a 4x4 board as four rows or four columns.
+        let packed = vec![lines(4, 4, 4); 4];
+        assert_eq!(count(&packed, &Bitboard::new(4, 4), 1000), Some(48));
+        assert_eq!(count(&packed, &Bitboard::new(4, 4), 1), None);
+
+        let estimate = estimate_count(&candidates, &blocked, 20_000, &mut rng::seeded(4));
+        assert!((estimate - 28.0).abs() < 28.0 * 0.05);
+    }
+
+    #[test]
+    fn no_touch_zones_keep_ships_apart() {
+        use battleship_config::AdjacencyRule;
+        let offsets = AdjacencyRule::NoTouch.offsets();
+        // Three length-3 ships fit on a 3x3 board only if they may touch.
+        let touching = vec![lines(3, 3, 3); 3];
+        let apart = vec![lines_with(3, 3, 3, offsets); 3];
+        let blocked = Bitboard::new(3, 3);
+        assert!(exists(&touching, &blocked));
+        assert!(!exists(&apart, &blocked));
+
+        let candidates = vec![lines_with(5, 5, 3, offsets); 3];
+        let mut rng = rng::seeded(5);
+        for strategy in [PlacementStrategy::Backtracking, PlacementStrategy::Uniform] {
+            let layout = sample(&candidates, &Bitboard::new(5, 5), strategy, &mut rng).unwrap();
+            for (i, a) in layout.iter().enumerate() {
+                for b in &layout[i + 1..] {
+                    assert!(a.expand(offsets).is_disjoint(b));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,51 @@
+//! Random number generation for reproducible games.
+//!
+//! Every random choice in the game (fleet placement, random guesses and AI
+//! shot selection) can be driven by an explicit generator. Seeding those
+//! generators from a single game seed via [`derive_seed`] makes a whole game
+//! reproducible from one `u64`.
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+/// Generator type used wherever the game owns its own source of randomness.
+pub type GameRng = StdRng;
+
+/// Stream used by the engine for fleet placement.
+pub const ENGINE_STREAM: u64 = 0;
+
+/// Stream used by the AI playing as player `n` (1 or 2).
+pub const fn player_stream(n: u64) -> u64 {
+    n
+}
+
+/// Creates a generator seeded from `seed`.
+pub fn seeded(seed: u64) -> GameRng {
+    GameRng::seed_from_u64(seed)
+}
+
+/// Creates a generator seeded from the operating system.
+pub fn from_entropy() -> GameRng {
+    GameRng::from_entropy()
+}
+
+/// Derives an independent seed for one component of a game.
+///
+/// Components seeded with different `stream` values from the same game seed
+/// receive unrelated sequences, so adding random calls to one component does
+/// not perturb the others.
+///
+/// # Example
+/// ```
+/// use battleship_core::rng::derive_seed;
+/// assert_eq!(derive_seed(42, 1), derive_seed(42, 1));
+/// assert_ne!(derive_seed(42, 1), derive_seed(42, 2));
+/// ```
+pub fn derive_seed(seed: u64, stream: u64) -> u64 {
+    // SplitMix64 finaliser over the combined seed and stream.
+    let mut z = seed ^ stream.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    z = z.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
@@ -1,21 +1,41 @@
+use crate::bitboard::Bitboard;
 use crate::constants::GameplayError;
-use crate::constants::GameplayError::InvalidPlacement;
+use crate::constants::GameplayError::{NotContiguous, WrongLength, WrongShape};
 use crate::constants::GuessError;
+use crate::orientation::Orientation;
 use crate::GuessResult;
-use std::collections::HashSet;
+use battleship_common::Coord;
+use battleship_config::Shape;
+
+/// Stable handle for a ship within its [`Fleet`](crate::Fleet).
+///
+/// Ids are assigned in fleet order when the fleet is created and never
+/// change, so they stay valid however the ships are placed, moved or hit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ShipId(pub(crate) usize);
+
+impl ShipId {
+    /// Position of the ship in its fleet.
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
 
 /// Represents a single ship in the Battleship game.
 ///
-/// Each ship has a name, length, and tracks its position and damage state.
+/// Each ship has a name, length and shape, and tracks its position and
+/// damage state.
 pub struct Ship {
     /// Name of the ship (e.g., "Carrier", "Battleship")
-    name: &'static str,
+    name: String,
     /// Length of the ship in grid units
     length: usize,
+    /// Outline the ship's cells must form, in any orientation
+    shape: Shape,
     /// Set of coordinates the ship occupies
-    coords: HashSet<(usize, usize)>,
+    coords: Bitboard,
     /// Set of coordinates where the ship has been hit
-    hits: HashSet<(usize, usize)>,
+    hits: Bitboard,
     /// Whether the ship has been placed on the board
     placed: bool,
     /// Whether the ship has been sunk
@@ -23,7 +43,7 @@ pub struct Ship {
 }
 
 impl Ship {
-    /// Creates a new ship instance.
+    /// Creates a new straight ship.
     ///
     /// # Arguments
     /// * `name` - Name of the ship
@@ -39,18 +59,42 @@ impl Ship {
     /// assert_eq!(carrier.length(), 5);
     /// assert!(!carrier.is_placed());
     /// ```
-    pub fn new(name: &'static str, length: usize) -> Self {
+    pub fn new(name: impl Into<String>, length: usize) -> Self {
+        Self::with_shape(name, Shape::line(length))
+    }
+
+    /// Creates a new ship with the given outline.
+    ///
+    /// # Arguments
+    /// * `name` - Name of the ship
+    /// * `shape` - Cells the ship covers, in any rotation or reflection
+    ///
+    /// # Returns
+    /// * `Ship` - New ship instance ready for placement
+    ///
+    /// # Example
+    /// ```
+    /// use battleship_core::{Bitboard, Shape, Ship};
+    /// let mut tee = Ship::with_shape("Tee", Shape::parse(&["###", ".#."]).unwrap());
+    /// assert_eq!(tee.length(), 4);
+    /// // Pointing up instead of down is still a tee.
+    /// let cells = Bitboard::from_coords(10, 10, [(4, 5), (5, 4), (5, 5), (5, 6)]);
+    /// assert!(tee.place(cells).is_ok());
+    /// ```
+    pub fn with_shape(name: impl Into<String>, shape: Shape) -> Self {
         Self {
-            name,
-            length,
-            coords: HashSet::with_capacity(length),
-            hits: HashSet::with_capacity(length),
+            name: name.into(),
+            length: shape.len(),
+            shape,
+            coords: Bitboard::new(0, 0),
+            hits: Bitboard::new(0, 0),
             placed: false,
             sunk: false,
         }
     }
 
-    /// Places the ship at the specified coordinates.
+    /// Places the ship at the specified coordinates, replacing any previous
+    /// position.
     ///
     /// # Arguments
     /// * `coords` - Set of coordinates where the ship will be placed
@@ -59,16 +103,58 @@ impl Ship {
     /// * `Result<(), GameplayError>` - Ok(()) if successful, Error if invalid placement
     ///
     /// # Errors
-    /// Returns `InvalidPlacement` if the number of coordinates doesn't match ship length
-    pub fn place(&mut self, coords: HashSet<(usize, usize)>) -> Result<(), GameplayError> {
+    /// Returns `WrongLength` if the number of coordinates doesn't match ship
+    /// length. A straight ship returns `NotContiguous` if the cells don't form
+    /// a straight, unbroken line, and any other ship
+    /// returns `WrongShape` if they don't form its shape
+    ///
+    /// # Example
+    /// ```
+    /// use battleship_core::{Bitboard, GameplayError, Ship};
+    /// let mut ship = Ship::new("Cruiser", 3);
+    /// let bent = Bitboard::from_coords(10, 10, [(0, 0), (0, 1), (1, 1)]);
+    /// assert_eq!(ship.place(bent), Err(GameplayError::NotContiguous("Cruiser".into())));
+    /// let gap = Bitboard::from_coords(10, 10, [(0, 0), (0, 1), (0, 3)]);
+    /// assert_eq!(ship.place(gap), Err(GameplayError::NotContiguous("Cruiser".into())));
+    /// // Whether a diagonal is allowed is up to the board's rules.
+    /// let diagonal = Bitboard::from_coords(10, 10, [(0, 0), (1, 1), (2, 2)]);
+    /// assert!(ship.place(diagonal).is_ok());
+    /// ```
+    pub fn place(&mut self, coords: Bitboard) -> Result<(), GameplayError> {
         if coords.len() != self.length {
-            return Err(InvalidPlacement);
+            return Err(WrongLength {
+                ship: self.name.clone(),
+                expected: self.length,
+                found: coords.len(),
+            });
+        }
+        let fits = if self.shape.is_line() {
+            Orientation::of_line(&coords).is_some()
+        } else {
+            Shape::from_cells(coords.iter()).is_ok_and(|s| self.shape.matches(&s))
+        };
+        if !fits {
+            return Err(if self.shape.is_line() {
+                NotContiguous(self.name.clone())
+            } else {
+                WrongShape(self.name.clone())
+            });
         }
-        self.coords.extend(coords.into_iter());
+        self.hits = Bitboard::new(coords.rows(), coords.cols());
+        self.coords = coords;
         self.placed = true;
+        self.sunk = false;
         Ok(())
     }
 
+    /// Takes the ship off the board, clearing its position and any hits.
+    pub fn unplace(&mut self) {
+        self.coords = Bitboard::new(0, 0);
+        self.hits = Bitboard::new(0, 0);
+        self.placed = false;
+        self.sunk = false;
+    }
+
     /// Processes a guess against this ship.
     ///
     /// # Arguments
@@ -79,22 +165,24 @@ impl Ship {
     ///
     /// # Examples
     /// ```
-    /// use battleship_core::{Ship, GuessResult};
-    /// use std::collections::HashSet;
+    /// use battleship_core::{Bitboard, Ship, GuessResult};
     /// let mut ship = Ship::new("Destroyer", 2);
-    /// ship.place(HashSet::from([(0,0), (0,1)])).unwrap();
+    /// ship.place(Bitboard::from_coords(10, 10, [(0,0), (0,1)])).unwrap();
     /// let result = ship.guess((0,0));
     /// assert!(matches!(result, Ok(GuessResult::Hit)));
     /// ```
     pub fn guess(&mut self, target: (usize, usize)) -> Result<GuessResult, GuessError> {
-        if self.hits.contains(&target) {
-            return Err(GuessError::AlreadyGuessed);
+        if self.hits.contains(target) {
+            return Err(GuessError::AlreadyGuessed(target.into()));
         }
-        if self.coords.contains(&target) {
+        if self.coords.contains(target) {
             self.hits.insert(target);
             if self.hits_remaining() == 0 {
                 self.sunk = true;
-                Ok(GuessResult::Sunk(self.name))
+                Ok(GuessResult::Sunk {
+                    name: Some(self.name.clone()),
+                    cells: Some(self.coords.iter().map(Coord::from).collect()),
+                })
             } else {
                 Ok(GuessResult::Hit)
             }
@@ -103,19 +191,34 @@ impl Ship {
         }
     }
 
-    pub fn name(&self) -> &'static str {
-        self.name
+    /// Reverts a hit made by [`Ship::guess`], refloating the ship if the hit
+    /// had sunk it. Returns false if `target` was not a hit on this ship.
+    pub(crate) fn undo_hit(&mut self, target: (usize, usize)) -> bool {
+        if !self.hits.remove(target) {
+            return false;
+        }
+        self.sunk = false;
+        true
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
     }
 
     pub fn length(&self) -> usize {
         self.length
     }
 
-    pub fn coords(&self) -> &HashSet<(usize, usize)> {
+    /// Outline of the ship as defined by its class.
+    pub fn shape(&self) -> &Shape {
+        &self.shape
+    }
+
+    pub fn coords(&self) -> &Bitboard {
         &self.coords
     }
 
-    pub fn hits(&self) -> &HashSet<(usize, usize)> {
+    pub fn hits(&self) -> &Bitboard {
         &self.hits
     }
 
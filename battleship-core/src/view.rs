@@ -0,0 +1,605 @@
+//! What an opponent can know about a board.
+
+use crate::bitboard::Bitboard;
+use crate::board::{Board, BoardState, Shot};
+use crate::constants::Cell;
+use crate::orientation::Orientation;
+use crate::weapon::Ping;
+use crate::GuessResult;
+use battleship_common::{BoardView, Coord};
+use battleship_config::{AdjacencyRule, Shape};
+use std::fmt;
+
+/// Read-only view of an opponent's board, limited to what the attacker has
+/// been told.
+///
+/// The view is built from the same hidden [`BoardState`] snapshot a remote
+/// client receives, so it holds the rules, the fleet definition and the
+/// result of every shot, but never where unhit ships lie. AI players take an
+/// `OpponentView` rather than a [`Board`] so they cannot see hidden ships.
+///
+/// Which hit cells belonged to a sunk ship is not announced; the view works
+/// it out from the shot history where only one position fits.
+///
+/// # Example
+/// ```
+/// use battleship_core::{Board, Coord, GameConfig, Orientation};
+/// let config = GameConfig::new(4, 4, &[("Destroyer", 2), ("Submarine", 3)]);
+/// let mut board = Board::with_config(&config);
+/// board.place_ship("Destroyer", Coord::new(0, 0), Orientation::Horizontal).unwrap();
+/// board.place_ship("Submarine", Coord::new(3, 0), Orientation::Horizontal).unwrap();
+/// board.confirm_fleet().unwrap();
+/// board.guess(Coord::new(0, 0)).unwrap();
+/// board.guess(Coord::new(0, 1)).unwrap();
+///
+/// let view = board.opponent_view();
+/// assert_eq!(view.ship_lengths_remaining(), vec![3]);
+/// assert_eq!(view.sunk_coords().len(), 2);
+/// assert!(view.unsunk_hits().is_empty());
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct OpponentView {
+    rows: usize,
+    cols: usize,
+    adjacency: AdjacencyRule,
+    diagonal_ships: bool,
+    /// Name, shape and known-sunk flag of every ship, in fleet order
+    fleet: Vec<(String, Shape, bool)>,
+    hits: Bitboard,
+    misses: Bitboard,
+    islands: Bitboard,
+    /// Mines that have gone off
+    mines: Bitboard,
+    /// Number of mines hidden on the board, set off or not
+    mine_count: usize,
+    /// Cells known to belong to sunk ships
+    sunk: Bitboard,
+    history: Vec<Shot>,
+    pings: Vec<Ping>,
+}
+
+impl OpponentView {
+    /// Number of rows on the board.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Number of columns on the board.
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Whether ships on the board may touch each other.
+    pub fn adjacency(&self) -> AdjacencyRule {
+        self.adjacency
+    }
+
+    /// Whether straight ships on the board may lie diagonally.
+    pub fn diagonal_ships(&self) -> bool {
+        self.diagonal_ships
+    }
+
+    /// Name, shape and known-sunk flag of every ship in the fleet.
+    pub fn fleet(&self) -> &[(String, Shape, bool)] {
+        &self.fleet
+    }
+
+    /// Names of the ships known to be sunk.
+    pub fn sunk_ships(&self) -> impl Iterator<Item = &str> {
+        self.fleet
+            .iter()
+            .filter(|(_, _, sunk)| *sunk)
+            .map(|(name, _, _)| name.as_str())
+    }
+
+    /// Lengths of the ships not known to be sunk.
+    pub fn ship_lengths_remaining(&self) -> Vec<usize> {
+        self.fleet
+            .iter()
+            .filter(|(_, _, sunk)| !sunk)
+            .map(|(_, shape, _)| shape.len())
+            .collect()
+    }
+
+    /// Shapes of the ships not known to be sunk.
+    pub fn ship_shapes_remaining(&self) -> Vec<Shape> {
+        self.fleet
+            .iter()
+            .filter(|(_, _, sunk)| !sunk)
+            .map(|(_, shape, _)| shape.clone())
+            .collect()
+    }
+
+    /// Cells fired at so far.
+    pub fn guessed(&self) -> Bitboard {
+        self.hits.union(&self.misses).union(&self.mines)
+    }
+
+    /// Open-water cells not yet fired at.
+    pub fn unguessed(&self) -> Bitboard {
+        (!&self.guessed()).difference(&self.islands)
+    }
+
+    /// Cells where a shot hit a ship.
+    pub fn hits(&self) -> &Bitboard {
+        &self.hits
+    }
+
+    /// Cells where a shot found open water.
+    pub fn misses(&self) -> &Bitboard {
+        &self.misses
+    }
+
+    /// Island cells, known from the start to hold no ship.
+    pub fn islands(&self) -> &Bitboard {
+        &self.islands
+    }
+
+    /// Cells where a shot set off a mine.
+    pub fn mines(&self) -> &Bitboard {
+        &self.mines
+    }
+
+    /// Number of hidden mines that have not gone off yet.
+    pub fn mines_remaining(&self) -> usize {
+        self.mine_count.saturating_sub(self.mines.len())
+    }
+
+    /// Hit cells known to belong to sunk ships.
+    pub fn sunk_coords(&self) -> &Bitboard {
+        &self.sunk
+    }
+
+    /// Hit cells not known to belong to a sunk ship.
+    pub fn unsunk_hits(&self) -> Bitboard {
+        self.hits.difference(&self.sunk)
+    }
+
+    /// Every shot taken at the board so far, oldest first.
+    pub fn shot_log(&self) -> &[Shot] {
+        &self.history
+    }
+
+    /// Every sonar ping taken at the board so far, oldest first.
+    pub fn pings(&self) -> &[Ping] {
+        &self.pings
+    }
+
+    /// Cells a sonar ping has shown to hold no ship.
+    pub fn sonar_clear(&self) -> Bitboard {
+        let mut clear = Bitboard::new(self.rows, self.cols);
+        for ping in self.pings.iter().filter(|ping| !ping.contact) {
+            clear.union_with(&ping.area(self.rows, self.cols));
+        }
+        clear
+    }
+
+    /// Areas in which a sonar ping found at least one ship.
+    pub fn sonar_contacts(&self) -> Vec<Bitboard> {
+        self.pings
+            .iter()
+            .filter(|ping| ping.contact)
+            .map(|ping| ping.area(self.rows, self.cols))
+            .collect()
+    }
+
+    /// Returns the given cells together with every neighbouring cell the
+    /// adjacency rule keeps other ships out of.
+    pub fn exclusion_zone(&self, cells: &Bitboard) -> Bitboard {
+        cells.expand(self.adjacency.offsets())
+    }
+
+    /// Calculates the cells a straight ship would occupy, or `None` if it
+    /// would run off the board.
+    ///
+    /// # Arguments
+    /// * `start` - End of the ship it extends from
+    /// * `length` - Length of the ship
+    /// * `orientation` - Direction the ship lies in
+    pub fn calc_placement(
+        &self,
+        start: (usize, usize),
+        length: usize,
+        orientation: Orientation,
+    ) -> Option<Bitboard> {
+        let (row_step, col_step) = orientation.step();
+        let mut cells = Vec::with_capacity(length);
+        for i in 0..length as isize {
+            let row = start.0.checked_add_signed(i * row_step)?;
+            let col = start.1.checked_add_signed(i * col_step)?;
+            if row >= self.rows || col >= self.cols {
+                return None;
+            }
+            cells.push((row, col));
+        }
+        Some(Bitboard::from_coords(self.rows, self.cols, cells))
+    }
+
+    /// Every position on the board a ship of the given shape could take, in
+    /// any rotation or reflection, clear of islands but ignoring what has
+    /// been fired at. Straight ships also lie diagonally when the rules allow
+    /// it.
+    ///
+    /// # Example
+    /// ```
+    /// use battleship_core::{Board, GameConfig, Shape};
+    /// let board = Board::with_config(&GameConfig::square(3, &[("Destroyer", 2)]));
+    /// let view = board.opponent_view();
+    /// assert_eq!(view.placements(&Shape::line(2)).len(), 12);
+    /// let ell = Shape::parse(&["#.", "##"]).unwrap();
+    /// assert_eq!(view.placements(&ell).len(), 16);
+    /// ```
+    pub fn placements(&self, shape: &Shape) -> Vec<Bitboard> {
+        let mut out = Vec::new();
+        for orientation in shape.orientations() {
+            let (height, width) = (orientation.height(), orientation.width());
+            if height > self.rows || width > self.cols {
+                continue;
+            }
+            for row in 0..=self.rows - height {
+                for col in 0..=self.cols - width {
+                    let cells = orientation.cells().iter().map(|&(r, c)| (row + r, col + c));
+                    out.push(Bitboard::from_coords(self.rows, self.cols, cells));
+                }
+            }
+        }
+        if self.diagonal_ships && shape.is_line() && shape.len() > 1 {
+            for start in Bitboard::full(self.rows, self.cols).iter() {
+                for &orientation in &Orientation::ALL[2..] {
+                    out.extend(self.calc_placement(start, shape.len(), orientation));
+                }
+            }
+        }
+        out.retain(|cells| cells.is_disjoint(&self.islands));
+        out
+    }
+
+    /// Marks as sunk the cells of each sunk ship whose position the shot
+    /// history pins down.
+    ///
+    /// A ship sunk by a shot covers that shot with cells hit no later than
+    /// the sinking shot and not part of another sunk ship. Whenever only one
+    /// position of its shape fits it must be the ship; repeating until
+    /// nothing changes lets one deduction unlock the next.
+    ///
+    /// A sinking announced without a name may be any ship not yet known to
+    /// be sunk. Once only one position of any such ship fits, the first
+    /// unsunk ship of that shape is marked sunk as well.
+    fn deduce_sunk_cells(&mut self) {
+        let mut sinkings: Vec<(Coord, Option<Shape>, Bitboard)> = Vec::new();
+        let mut hit_so_far = Bitboard::new(self.rows, self.cols);
+        for shot in &self.history {
+            match &shot.result {
+                GuessResult::Hit => {
+                    hit_so_far.insert(shot.target);
+                }
+                GuessResult::Sunk {
+                    cells: Some(cells), ..
+                } => {
+                    hit_so_far.insert(shot.target);
+                    for &cell in cells {
+                        self.sunk.insert(cell);
+                    }
+                }
+                GuessResult::Sunk {
+                    name: Some(name),
+                    cells: None,
+                } => {
+                    hit_so_far.insert(shot.target);
+                    if let Some((_, shape, _)) = self.fleet.iter().find(|(n, _, _)| n == name) {
+                        sinkings.push((shot.target, Some(shape.clone()), hit_so_far.clone()));
+                    }
+                }
+                GuessResult::Sunk {
+                    name: None,
+                    cells: None,
+                } => {
+                    hit_so_far.insert(shot.target);
+                    sinkings.push((shot.target, None, hit_so_far.clone()));
+                }
+                GuessResult::Miss | GuessResult::Mine => {}
+            }
+        }
+
+        let mut solved = vec![false; sinkings.len()];
+        loop {
+            let mut progress = false;
+            for (i, (target, shape, hit)) in sinkings.iter().enumerate() {
+                if solved[i] {
+                    continue;
+                }
+                let mut shapes = match shape {
+                    Some(shape) => vec![shape.clone()],
+                    None => self.ship_shapes_remaining(),
+                };
+                shapes.dedup();
+                let open = hit.difference(&self.sunk);
+                let mut fits: Vec<(Bitboard, Shape)> = Vec::new();
+                for shape in shapes {
+                    for p in self.placements(&shape) {
+                        if p.contains(*target)
+                            && p.is_subset(&open)
+                            && !fits.iter().any(|(fit, _)| *fit == p)
+                        {
+                            fits.push((p, shape.clone()));
+                        }
+                    }
+                }
+                if let [(position, fit_shape)] = fits.as_slice() {
+                    self.sunk.union_with(position);
+                    if shape.is_none() {
+                        if let Some(ship) = self
+                            .fleet
+                            .iter_mut()
+                            .find(|(_, s, sunk)| !sunk && s == fit_shape)
+                        {
+                            ship.2 = true;
+                        }
+                    }
+                    solved[i] = true;
+                    progress = true;
+                }
+            }
+            if !progress {
+                break;
+            }
+        }
+    }
+}
+
+impl From<&BoardState> for OpponentView {
+    /// Builds the view from a snapshot, reading only shot results and ship
+    /// statuses so that the positions of ships still afloat stay hidden even
+    /// in an owner's snapshot. Sunk ships are placed wherever the shot
+    /// history reveals or implies their cells.
+    fn from(state: &BoardState) -> Self {
+        let mut hits = Bitboard::new(state.rows, state.cols);
+        let mut misses = Bitboard::new(state.rows, state.cols);
+        let mut islands = Bitboard::new(state.rows, state.cols);
+        for (row, cells) in state.cells.iter().enumerate() {
+            for (col, cell) in cells.iter().enumerate() {
+                match cell {
+                    Cell::Hit | Cell::Sunk => {
+                        hits.insert((row, col));
+                    }
+                    Cell::Miss => {
+                        misses.insert((row, col));
+                    }
+                    Cell::Island => {
+                        islands.insert((row, col));
+                    }
+                    // Mines are read from the shot history, since an
+                    // owner's snapshot also shows those not yet set off.
+                    Cell::Empty | Cell::Ship | Cell::Mine => {}
+                }
+            }
+        }
+        let mut mines = Bitboard::new(state.rows, state.cols);
+        for shot in &state.history {
+            if shot.result == GuessResult::Mine {
+                mines.insert(shot.target);
+            }
+        }
+        let mut view = Self {
+            rows: state.rows,
+            cols: state.cols,
+            adjacency: state.adjacency,
+            diagonal_ships: state.diagonal_ships,
+            fleet: state
+                .ships
+                .iter()
+                .map(|ship| (ship.name.clone(), ship.shape.clone(), ship.sunk))
+                .collect(),
+            hits,
+            misses,
+            islands,
+            mines,
+            mine_count: state.mines,
+            sunk: Bitboard::new(state.rows, state.cols),
+            history: state.history.clone(),
+            pings: state.pings.clone(),
+        };
+        view.deduce_sunk_cells();
+        view
+    }
+}
+
+impl Board {
+    /// Returns what an opponent firing at this board knows about it.
+    pub fn opponent_view(&self) -> OpponentView {
+        OpponentView::from(&BoardState::new(self, false))
+    }
+}
+
+impl fmt::Display for OpponentView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                let cell = if self.islands.contains((row, col)) {
+                    Cell::Island
+                } else if self.mines.contains((row, col)) {
+                    Cell::Mine
+                } else if self.sunk.contains((row, col)) {
+                    Cell::Sunk
+                } else if self.hits.contains((row, col)) {
+                    Cell::Hit
+                } else if self.misses.contains((row, col)) {
+                    Cell::Miss
+                } else {
+                    Cell::Empty
+                };
+                write!(f, "{}", cell.icon())?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl BoardView for OpponentView {
+    fn rows(&self) -> usize {
+        self.rows
+    }
+
+    fn cols(&self) -> usize {
+        self.cols
+    }
+
+    fn is_island(&self, coord: Coord) -> bool {
+        self.islands.contains(coord)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameConfig, SunkAnnouncement};
+
+    #[test]
+    fn view_hides_unhit_ships() {
+        let mut board = Board::new();
+        board
+            .randomly_place_fleet_with_rng(&mut crate::rng::seeded(1))
+            .unwrap();
+        board.confirm_fleet().unwrap();
+        board.guess(Coord::new(4, 4)).unwrap();
+
+        let view = board.opponent_view();
+        assert_eq!(view.guessed().len(), 1);
+        assert_eq!(view.hits().len() + view.misses().len(), 1);
+        assert_eq!(view.ship_lengths_remaining(), vec![5, 4, 3, 3, 2]);
+        // An owner's snapshot carries the fleet, but the view drops it.
+        assert_eq!(OpponentView::from(&BoardState::new(&board, true)), view);
+    }
+
+    #[test]
+    fn sunk_cells_are_deduced_only_when_unambiguous() {
+        // A patrol boat on A1-A2 lies alongside a destroyer on B1-B2.
+        let config = GameConfig::new(3, 3, &[("Destroyer", 2), ("Patrol Boat", 2)]);
+        let fire = |targets: &[(usize, usize)]| {
+            let mut board = Board::with_config(&config);
+            board
+                .place_ship("Destroyer", Coord::new(1, 0), Orientation::Horizontal)
+                .unwrap();
+            board
+                .place_ship("Patrol Boat", Coord::new(0, 0), Orientation::Horizontal)
+                .unwrap();
+            board.confirm_fleet().unwrap();
+            for &target in targets {
+                board.guess(target.into()).unwrap();
+            }
+            board.opponent_view()
+        };
+
+        // A2 sinks the patrol boat while B2 is still untouched, so it can
+        // only be A1-A2, which in turn leaves B1-B2 for the destroyer.
+        let view = fire(&[(0, 0), (1, 0), (0, 1), (1, 1)]);
+        assert_eq!(
+            view.sunk_ships().collect::<Vec<_>>(),
+            ["Destroyer", "Patrol Boat"]
+        );
+        assert_eq!(view.sunk_coords().len(), 4);
+
+        // B2 sinks the destroyer with both A2 and B1 hit: either could be
+        // its other half.
+        let view = fire(&[(0, 1), (1, 0), (1, 1)]);
+        assert_eq!(view.sunk_ships().collect::<Vec<_>>(), ["Destroyer"]);
+        assert!(view.sunk_coords().is_empty());
+        assert_eq!(view.unsunk_hits().len(), 3);
+        assert_eq!(view.ship_lengths_remaining(), vec![2]);
+    }
+
+    #[test]
+    fn revealed_positions_settle_ambiguous_sinkings() {
+        // The ambiguous destroyer sinking above, with positions announced.
+        let config = GameConfig::new(3, 3, &[("Destroyer", 2), ("Patrol Boat", 2)])
+            .with_sunk_announcement(SunkAnnouncement::ShipPosition);
+        let mut board = Board::with_config(&config);
+        board
+            .place_ship("Destroyer", Coord::new(1, 0), Orientation::Horizontal)
+            .unwrap();
+        board
+            .place_ship("Patrol Boat", Coord::new(0, 0), Orientation::Horizontal)
+            .unwrap();
+        board.confirm_fleet().unwrap();
+        for target in [(0, 1), (1, 0), (1, 1)] {
+            board.guess(target.into()).unwrap();
+        }
+
+        let view = board.opponent_view();
+        let destroyer = Bitboard::from_coords(3, 3, [(1, 0), (1, 1)]);
+        assert_eq!(view.sunk_coords(), &destroyer);
+        assert_eq!(view.unsunk_hits().iter().collect::<Vec<_>>(), [(0, 1)]);
+    }
+
+    #[test]
+    fn shaped_sinkings_are_deduced() {
+        // An L on A1, B1, B2 is sunk by its corner; only one L through B1
+        // fits the three hits.
+        let ell = Shape::parse(&["#.", "##"]).unwrap();
+        let config = GameConfig::with_classes(3, 3, vec![crate::ShipClass::shaped("Ell", ell, 1)]);
+        let mut board = Board::with_config(&config);
+        let cells = Bitboard::from_coords(3, 3, [(0, 0), (1, 0), (1, 1)]);
+        board.place_ship_cells("Ell", cells.clone()).unwrap();
+        board.confirm_fleet().unwrap();
+        for target in [(0, 0), (1, 1), (1, 0)] {
+            board.guess(target.into()).unwrap();
+        }
+
+        let view = board.opponent_view();
+        assert_eq!(view.sunk_coords(), &cells);
+        assert!(view.ship_shapes_remaining().is_empty());
+    }
+
+    #[test]
+    fn unnamed_sinkings_are_matched_to_a_ship_by_position() {
+        let config = GameConfig::new(4, 4, &[("Cruiser", 3), ("Destroyer", 2)])
+            .with_sunk_announcement(SunkAnnouncement::SunkOnly);
+        let mut board = Board::with_config(&config);
+        board
+            .place_ship("Cruiser", Coord::new(2, 0), Orientation::Horizontal)
+            .unwrap();
+        board
+            .place_ship("Destroyer", Coord::new(0, 0), Orientation::Horizontal)
+            .unwrap();
+        board.confirm_fleet().unwrap();
+        board.guess(Coord::new(0, 0)).unwrap();
+        board.guess(Coord::new(0, 1)).unwrap();
+
+        let state = BoardState::new(&board, false);
+        let told = &state.history[1].result;
+        assert_eq!(
+            told,
+            &GuessResult::Sunk {
+                name: None,
+                cells: None
+            }
+        );
+        assert_eq!(told.to_string(), "A ship was sunk!");
+        assert!(state.ships.iter().all(|ship| !ship.sunk));
+
+        // Two hits can only be the destroyer.
+        let view = OpponentView::from(&state);
+        assert_eq!(view.sunk_ships().collect::<Vec<_>>(), ["Destroyer"]);
+        assert_eq!(view.sunk_coords().len(), 2);
+        assert_eq!(view.ship_lengths_remaining(), vec![3]);
+    }
+
+    #[test]
+    fn hit_only_rules_keep_sinkings_secret() {
+        let config = GameConfig::new(3, 3, &[("Destroyer", 2)])
+            .with_sunk_announcement(SunkAnnouncement::HitOnly);
+        let mut board = Board::with_config(&config);
+        board
+            .place_ship("Destroyer", Coord::new(0, 0), Orientation::Horizontal)
+            .unwrap();
+        board.confirm_fleet().unwrap();
+        board.guess(Coord::new(0, 0)).unwrap();
+        board.guess(Coord::new(0, 1)).unwrap();
+
+        let view = board.opponent_view();
+        assert_eq!(view.ship_lengths_remaining(), vec![2]);
+        assert!(view.sunk_coords().is_empty());
+        assert_eq!(view.unsunk_hits().len(), 2);
+    }
+}
@@ -0,0 +1,229 @@
+//! What a player can do with a turn in the advanced weapons mode.
+//!
+//! A turn is either an ordinary volley or one special [`Weapon`] aimed at a
+//! cell. Bombardments and torpedoes fire ordinary shots at the cells they
+//! reach, so they report a [`GuessResult`] for each; a sonar ping damages
+//! nothing and is remembered on the board as a [`Ping`].
+
+use crate::bitboard::Bitboard;
+use crate::board::Board;
+use crate::constants::{GuessError, GuessResult, ShotResult};
+use battleship_common::Coord;
+use battleship_config::{AdjacencyRule, SunkAnnouncement, Weapon};
+use std::fmt;
+
+/// How a player spends a turn.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Action {
+    /// Ordinary shots, one per cell
+    Volley(Vec<Coord>),
+    /// One special weapon aimed at a cell
+    Special(Weapon, Coord),
+}
+
+/// What a special weapon did.
+#[derive(Clone, Debug, PartialEq)]
+pub enum WeaponResult {
+    /// Cells a bombardment or torpedo fired at, in order, and what each shot
+    /// did
+    Strike(Vec<(Coord, GuessResult)>),
+    /// Whether a sonar ping found any ship in its area
+    Sonar {
+        /// True if at least one ship cell lies in the area
+        contact: bool,
+    },
+}
+
+impl WeaponResult {
+    /// Returns what the attacker is told about this result under the
+    /// defender's [`SunkAnnouncement`] rule.
+    pub fn announced(self, rule: SunkAnnouncement) -> Self {
+        match self {
+            WeaponResult::Strike(shots) => WeaponResult::Strike(
+                shots
+                    .into_iter()
+                    .map(|(coord, result)| (coord, result.announced(rule)))
+                    .collect(),
+            ),
+            sonar => sonar,
+        }
+    }
+
+    /// Each cell the weapon fired at with its result, in the form a volley
+    /// reports them; empty for a sonar ping.
+    pub fn shots(&self) -> Vec<(Coord, ShotResult)> {
+        match self {
+            WeaponResult::Strike(shots) => shots
+                .iter()
+                .map(|(coord, result)| (*coord, Ok(result.clone())))
+                .collect(),
+            WeaponResult::Sonar { .. } => Vec::new(),
+        }
+    }
+}
+
+impl fmt::Display for WeaponResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WeaponResult::Strike(shots) => {
+                let shots: Vec<_> = shots
+                    .iter()
+                    .map(|(coord, result)| format!("{}: {}", coord, result))
+                    .collect();
+                write!(f, "{}", shots.join(", "))
+            }
+            WeaponResult::Sonar { contact: true } => write!(f, "contact!"),
+            WeaponResult::Sonar { contact: false } => write!(f, "no contact"),
+        }
+    }
+}
+
+/// Outcome of a turn, sent back to the player who took it.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ActionResult {
+    /// Outcome of each shot of a volley, in the order fired
+    Volley(Vec<(Coord, ShotResult)>),
+    /// Outcome of a special weapon
+    Special {
+        /// Weapon used
+        weapon: Weapon,
+        /// Cell it was aimed at
+        target: Coord,
+        /// What it did, or why it could not be used
+        result: Result<WeaponResult, GuessError>,
+    },
+}
+
+/// A sonar ping taken at a board and whether it found a ship.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Ping {
+    /// Centre of the 3x3 area searched
+    pub center: Coord,
+    /// True if at least one ship cell lies in the area
+    pub contact: bool,
+}
+
+impl Ping {
+    /// Cells the ping searched on a `rows`×`cols` board.
+    pub fn area(&self, rows: usize, cols: usize) -> Bitboard {
+        area_around(self.center, rows, cols)
+    }
+}
+
+/// The on-board cells of the 3x3 square centred on `center`, as reached by
+/// a bombardment or a sonar ping.
+///
+/// # Example
+/// ```
+/// use battleship_core::{weapon, Coord};
+/// assert_eq!(weapon::area_around(Coord::new(0, 0), 10, 10).len(), 4);
+/// assert_eq!(weapon::area_around(Coord::new(4, 4), 10, 10).len(), 9);
+/// ```
+pub fn area_around(center: Coord, rows: usize, cols: usize) -> Bitboard {
+    Bitboard::from_coords(rows, cols, [(center.row, center.col)])
+        .expand(AdjacencyRule::NoTouch.offsets())
+}
+
+impl Board {
+    /// Uses a special weapon against this board.
+    ///
+    /// Keeping count of how many uses a player has left is up to the caller.
+    ///
+    /// # Arguments
+    /// * `weapon` - Weapon to use
+    /// * `target` - Cell it is aimed at; see [`Board::bombard`],
+    ///   [`Board::torpedo`] and [`Board::sonar_ping`]
+    ///
+    /// # Errors
+    /// Fails like the method for the weapon.
+    ///
+    /// # Example
+    /// ```
+    /// use battleship_core::{Board, Coord, GuessResult, Orientation, Weapon, WeaponResult};
+    /// let mut board = Board::new();
+    /// board.place_ship("Destroyer", Coord::new(2, 5), Orientation::Horizontal).unwrap();
+    /// let ping = board.use_weapon(Weapon::Sonar, Coord::new(1, 4)).unwrap();
+    /// assert_eq!(ping, WeaponResult::Sonar { contact: true });
+    /// let torpedo = board.use_weapon(Weapon::Torpedo, Coord::new(2, 0)).unwrap();
+    /// let WeaponResult::Strike(shots) = torpedo else { unreachable!() };
+    /// assert_eq!(shots.len(), 6);
+    /// assert_eq!(shots.last().unwrap(), &(Coord::new(2, 5), GuessResult::Hit));
+    /// ```
+    pub fn use_weapon(
+        &mut self,
+        weapon: Weapon,
+        target: Coord,
+    ) -> Result<WeaponResult, GuessError> {
+        match weapon {
+            Weapon::Bombardment => self.bombard(target).map(WeaponResult::Strike),
+            Weapon::Torpedo => self.torpedo(target).map(WeaponResult::Strike),
+            Weapon::Sonar => self
+                .sonar_ping(target)
+                .map(|contact| WeaponResult::Sonar { contact }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Orientation;
+    use battleship_config::{GameConfig, Terrain};
+
+    fn board() -> Board {
+        let map = Terrain::parse(&[".....", ".....", "...#.", "....."]).unwrap();
+        let config = GameConfig::new(4, 5, &[("Destroyer", 2)]).with_terrain(&map);
+        let mut board = Board::with_config(&config);
+        board
+            .place_ship("Destroyer", Coord::new(0, 3), Orientation::Horizontal)
+            .unwrap();
+        board.confirm_fleet().unwrap();
+        board
+    }
+
+    #[test]
+    fn bombardments_fire_at_the_open_cells_around_the_target() {
+        let mut board = board();
+        board.guess(Coord::new(1, 3)).unwrap();
+        let shots = board.bombard(Coord::new(1, 3)).unwrap();
+        // Nine cells less the island and the cell already fired at.
+        assert_eq!(shots.len(), 7);
+        assert_eq!(shots[1], (Coord::new(0, 3), GuessResult::Hit));
+        assert!(matches!(shots[2].1, GuessResult::Sunk { .. }));
+        let (a5, e1) = (Coord::new(0, 4), Coord::new(4, 0));
+        assert_eq!(board.bombard(a5), Err(GuessError::AlreadyGuessed(a5)));
+        assert_eq!(board.bombard(e1), Err(GuessError::InvalidTarget(e1)));
+    }
+
+    #[test]
+    fn torpedoes_run_until_they_strike_something() {
+        let mut board = board();
+        board.guess(Coord::new(0, 1)).unwrap();
+        let shots = board.torpedo(Coord::new(0, 0)).unwrap();
+        let fired: Vec<_> = shots.iter().map(|(coord, _)| coord.col).collect();
+        assert_eq!(fired, [0, 2, 3]);
+        assert_eq!(shots[2].1, GuessResult::Hit);
+
+        // An island stops the torpedo short of the far edge.
+        let shots = board.torpedo(Coord::new(2, 0)).unwrap();
+        assert_eq!(shots.len(), 3);
+        assert!(shots.iter().all(|(_, r)| *r == GuessResult::Miss));
+        let (c2, c4) = (Coord::new(2, 1), Coord::new(2, 3));
+        assert_eq!(board.torpedo(c4), Err(GuessError::Island(c4)));
+        assert_eq!(board.torpedo(c2), Err(GuessError::AlreadyGuessed(c2)));
+    }
+
+    #[test]
+    fn sonar_pings_reveal_ships_without_damage() {
+        let mut board = board();
+        let ping = board.use_weapon(Weapon::Sonar, Coord::new(1, 4));
+        assert_eq!(ping, Ok(WeaponResult::Sonar { contact: true }));
+        assert_eq!(board.sonar_ping(Coord::new(2, 1)), Ok(false));
+        assert!(board.guessed().is_empty());
+
+        let view = board.opponent_view();
+        assert_eq!(view.pings(), board.pings());
+        assert_eq!(view.sonar_clear().len(), 9);
+        assert_eq!(view.sonar_contacts().len(), 1);
+    }
+}
@@ -0,0 +1,139 @@
+//! Errors raised while running a game.
+
+use battleship_core::{ConfigError, GameplayError, GuessError};
+use battleship_save::SaveError;
+use battleship_transport::TransportError;
+use std::fmt;
+
+/// Errors that stop [`Engine::run`](crate::Engine::run).
+///
+/// Players are numbered 1 and 2, matching the engine's transports.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EngineError {
+    /// A player's fleet could not be set up
+    Placement {
+        /// Player whose fleet failed
+        player: usize,
+        /// What went wrong
+        source: GameplayError,
+    },
+    /// The connection to a player failed
+    Transport {
+        /// Player whose connection failed
+        player: usize,
+        /// What went wrong
+        source: TransportError,
+    },
+    /// A mine could not strike back at the player who set it off
+    CounterHit {
+        /// Player the mine should have struck
+        player: usize,
+        /// What went wrong
+        source: GuessError,
+    },
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EngineError::Placement { player, source } => {
+                write!(f, "could not set up player {}'s fleet: {}", player, source)
+            }
+            EngineError::Transport { player, source } => {
+                write!(f, "lost contact with player {}: {}", player, source)
+            }
+            EngineError::CounterHit { player, source } => {
+                write!(f, "a mine could not strike player {}: {}", player, source)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EngineError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EngineError::Placement { source, .. } => Some(source),
+            EngineError::Transport { source, .. } => Some(source),
+            EngineError::CounterHit { source, .. } => Some(source),
+        }
+    }
+}
+
+/// Any error a game can end with, whichever layer raised it.
+#[derive(Debug)]
+pub enum GameError {
+    /// Placing or editing ships failed
+    Gameplay(GameplayError),
+    /// A shot could not be fired
+    Guess(GuessError),
+    /// The engine stopped the game
+    Engine(EngineError),
+    /// A transport failed outside the engine
+    Transport(TransportError),
+    /// Saving or loading failed
+    Save(SaveError),
+    /// The game settings could not be loaded
+    Config(ConfigError),
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameError::Gameplay(e) => write!(f, "{}", e),
+            GameError::Guess(e) => write!(f, "{}", e),
+            GameError::Engine(e) => write!(f, "{}", e),
+            GameError::Transport(e) => write!(f, "{}", e),
+            GameError::Save(e) => write!(f, "{}", e),
+            GameError::Config(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for GameError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GameError::Gameplay(e) => Some(e),
+            GameError::Guess(e) => Some(e),
+            GameError::Engine(e) => Some(e),
+            GameError::Transport(e) => Some(e),
+            GameError::Save(e) => Some(e),
+            GameError::Config(e) => Some(e),
+        }
+    }
+}
+
+impl From<GameplayError> for GameError {
+    fn from(e: GameplayError) -> Self {
+        GameError::Gameplay(e)
+    }
+}
+
+impl From<GuessError> for GameError {
+    fn from(e: GuessError) -> Self {
+        GameError::Guess(e)
+    }
+}
+
+impl From<EngineError> for GameError {
+    fn from(e: EngineError) -> Self {
+        GameError::Engine(e)
+    }
+}
+
+impl From<TransportError> for GameError {
+    fn from(e: TransportError) -> Self {
+        GameError::Transport(e)
+    }
+}
+
+impl From<SaveError> for GameError {
+    fn from(e: SaveError) -> Self {
+        GameError::Save(e)
+    }
+}
+
+impl From<ConfigError> for GameError {
+    fn from(e: ConfigError) -> Self {
+        GameError::Config(e)
+    }
+}
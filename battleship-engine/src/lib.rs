@@ -1,59 +1,559 @@
-use battleship_core::{Board, BoardState, GuessResult, PlayerState};
-use battleship_transport::Transport;
+use battleship_core::rng::{self, GameRng};
+use battleship_core::{
+    Action, ActionResult, Arsenal, Board, BoardState, Coord, GameConfig, GuessError, GuessResult,
+    MinePenalty, PlayerState, RuleSet, ShotResult, ShotsPerTurn,
+};
+use battleship_transport::{Transport, TransportError};
+
+pub mod error;
+pub mod turn;
+
+pub use error::{EngineError, GameError};
+pub use turn::{ChainFire, Classic, TurnPolicy};
 
 pub struct Engine<T1: Transport, T2: Transport> {
     board1: Board,
     board2: Board,
     t1: T1,
     t2: T2,
+    rng: GameRng,
+    shots_per_turn: ShotsPerTurn,
+    mine_penalty: MinePenalty,
+    /// Special weapons each player has left
+    ammo: [Arsenal; 2],
+    turn_policy: Box<dyn TurnPolicy>,
 }
 
 impl<T1: Transport, T2: Transport> Engine<T1, T2> {
     pub fn new(t1: T1, t2: T2) -> Self {
+        Self::with_rules(RuleSet::default(), t1, t2)
+    }
+
+    /// Create an engine that plays by one of the preset rule sets.
+    pub fn with_rules(rules: RuleSet, t1: T1, t2: T2) -> Self {
+        Self::with_config(&rules.config(), t1, t2)
+    }
+
+    /// Create an engine whose boards follow the given configuration.
+    pub fn with_config(config: &GameConfig, t1: T1, t2: T2) -> Self {
+        Self::with_rng(config, rng::from_entropy(), t1, t2)
+    }
+
+    /// Create an engine whose fleet placement is reproducible from `seed`.
+    ///
+    /// To replay a whole game from one seed, also seed each AI player with
+    /// `rng::derive_seed(seed, rng::player_stream(n))`.
+    pub fn with_seed(config: &GameConfig, seed: u64, t1: T1, t2: T2) -> Self {
+        let rng = rng::seeded(rng::derive_seed(seed, rng::ENGINE_STREAM));
+        Self::with_rng(config, rng, t1, t2)
+    }
+
+    fn with_rng(config: &GameConfig, rng: GameRng, t1: T1, t2: T2) -> Self {
         Self {
-            board1: Board::new(),
-            board2: Board::new(),
+            board1: Board::with_config(config),
+            board2: Board::with_config(config),
             t1,
             t2,
+            rng,
+            shots_per_turn: config.shots_per_turn,
+            mine_penalty: config.mine_penalty,
+            ammo: [config.arsenal; 2],
+            turn_policy: Box::new(Classic),
         }
     }
 
-    async fn sync_states(&mut self) {
-        let p1_own = BoardState::new(&self.board1, true);
+    /// Returns this engine with `player`'s fleet laid out in advance, for
+    /// instance by hand. [`run`](Self::run) places at random only the
+    /// fleets not already confirmed.
+    ///
+    /// # Panics
+    /// Panics if `player` is neither 1 nor 2.
+    pub fn with_fleet(mut self, player: usize, board: Board) -> Self {
+        match player {
+            1 => self.board1 = board,
+            2 => self.board2 = board,
+            _ => panic!("no player {}", player),
+        }
+        self
+    }
+
+    /// Returns this engine using `policy` to decide who fires next instead
+    /// of strict alternation.
+    pub fn with_turn_policy(mut self, policy: impl TurnPolicy + 'static) -> Self {
+        self.turn_policy = Box::new(policy);
+        self
+    }
+
+    /// Sends each player a snapshot of both boards. The shot counts tell
+    /// each client how many shots it may fire now; zero means wait. Each
+    /// player's own snapshot also carries the special weapons they have
+    /// left.
+    async fn sync_states(&mut self, p1_shots: usize, p2_shots: usize) -> Result<(), EngineError> {
+        let p1_own = BoardState::new(&self.board1, true)
+            .with_shots(p1_shots)
+            .with_arsenal(self.ammo[0]);
         let p1_op = BoardState::new(&self.board2, false);
-        let p2_own = BoardState::new(&self.board2, true);
+        let p2_own = BoardState::new(&self.board2, true)
+            .with_shots(p2_shots)
+            .with_arsenal(self.ammo[1]);
         let p2_op = BoardState::new(&self.board1, false);
-        self.t1.send_board_state(p1_own).await;
-        self.t1.send_board_state(p1_op).await;
-        self.t2.send_board_state(p2_own).await;
-        self.t2.send_board_state(p2_op).await;
+        self.t1.send_board_state(p1_own).await.map_err(lost(1))?;
+        self.t1.send_board_state(p1_op).await.map_err(lost(1))?;
+        self.t2.send_board_state(p2_own).await.map_err(lost(2))?;
+        self.t2.send_board_state(p2_op).await.map_err(lost(2))?;
+        Ok(())
     }
 
-    pub async fn run(&mut self) {
-        self.board1.randomly_place_fleet().unwrap();
-        self.board2.randomly_place_fleet().unwrap();
-        self.sync_states().await;
+    /// Places both fleets, unless already confirmed, and plays until one is
+    /// sunk.
+    ///
+    /// Shots a player cannot legally fire, such as repeats, are reported
+    /// back to that player as errors and use up the shot. A volley that sets
+    /// off a mine costs its shooter what the [`MinePenalty`] says: their next
+    /// turn, or a counter-hit on a random cell of their own fleet.
+    ///
+    /// While a player has special weapons left, their turn is received as
+    /// an [`Action`] rather than a plain volley; see [`play_turn`].
+    ///
+    /// # Errors
+    /// Returns an [`EngineError`] if a fleet cannot be placed, a player's
+    /// transport fails, or a mine cannot strike back at its shooter.
+    pub async fn run(&mut self) -> Result<(), EngineError> {
+        for (player, board) in [(1, &mut self.board1), (2, &mut self.board2)] {
+            if board.is_confirmed() {
+                continue;
+            }
+            board
+                .randomly_place_fleet_with_rng(&mut self.rng)
+                .and_then(|()| board.confirm_fleet())
+                .map_err(|source| EngineError::Placement { player, source })?;
+        }
+        let mut p1_to_move = true;
+        // Whether each player sits out their next turn after a mine.
+        let mut skip_next = [false, false];
         loop {
-            let mv1 = self.t1.recv_move().await;
-            let res1 = match self.board2.guess(mv1) {
-                Ok(r) => r,
-                Err(_) => GuessResult::Miss,
+            let (results, defender) = if p1_to_move {
+                let shots = volley_size(self.shots_per_turn, &self.board1, &self.board2);
+                self.sync_states(shots, 0).await?;
+                let results = play_turn(&mut self.t1, &mut self.board2, shots, &mut self.ammo[0])
+                    .await
+                    .map_err(lost(1))?;
+                (results, &self.board2)
+            } else {
+                let shots = volley_size(self.shots_per_turn, &self.board2, &self.board1);
+                self.sync_states(0, shots).await?;
+                let results = play_turn(&mut self.t2, &mut self.board1, shots, &mut self.ammo[1])
+                    .await
+                    .map_err(lost(2))?;
+                (results, &self.board1)
             };
-            self.t1.send_result(res1).await;
-            self.sync_states().await;
-            if self.board2.player_state() == PlayerState::Dead {
-                break;
-            }
-            let mv2 = self.t2.recv_move().await;
-            let res2 = match self.board1.guess(mv2) {
-                Ok(r) => r,
-                Err(_) => GuessResult::Miss,
+            if defender.player_state() == PlayerState::Dead {
+                return self.sync_states(0, 0).await;
+            }
+            let mined = results.iter().any(|(_, r)| r == &Ok(GuessResult::Mine));
+            if mined {
+                let attacker = if p1_to_move {
+                    &mut self.board1
+                } else {
+                    &mut self.board2
+                };
+                match self.mine_penalty {
+                    MinePenalty::LoseTurn => skip_next[usize::from(!p1_to_move)] = true,
+                    MinePenalty::CounterHit => {
+                        let player = if p1_to_move { 1 } else { 2 };
+                        attacker
+                            .counter_hit_with_rng(&mut self.rng)
+                            .map_err(|source| EngineError::CounterHit { player, source })?;
+                        if attacker.player_state() == PlayerState::Dead {
+                            return self.sync_states(0, 0).await;
+                        }
+                    }
+                }
+            }
+            if !self.turn_policy.fires_again(&results) {
+                p1_to_move = !p1_to_move;
+            }
+            let next = usize::from(!p1_to_move);
+            if skip_next[next] {
+                skip_next[next] = false;
+                p1_to_move = !p1_to_move;
+            }
+        }
+    }
+}
+
+/// Wraps a transport failure with the player it happened to.
+fn lost(player: usize) -> impl Fn(TransportError) -> EngineError {
+    move |source| EngineError::Transport { player, source }
+}
+
+/// Number of shots `attacker` may fire at `defender` this turn: what the
+/// rule allows, at least one, and never more than the cells left to target.
+fn volley_size(rule: ShotsPerTurn, attacker: &Board, defender: &Board) -> usize {
+    let allowed = match rule {
+        ShotsPerTurn::Fixed(n) => n,
+        ShotsPerTurn::SurvivingShips => attacker.ship_lengths_remaining().len(),
+    };
+    allowed.max(1).min(defender.unguessed().len())
+}
+
+/// Receives one volley over `t`, fires at most `shots` of it at `defender`
+/// and reports the results back.
+async fn play_volley<T: Transport>(
+    t: &mut T,
+    defender: &mut Board,
+    shots: usize,
+) -> Result<Vec<(Coord, ShotResult)>, TransportError> {
+    let volley = t.recv_volley().await?;
+    let results = fire_volley(defender, volley, shots);
+    t.send_volley_results(results.clone()).await?;
+    Ok(results)
+}
+
+/// Plays one turn over `t`: a plain volley once `ammo` is empty, otherwise
+/// an [`Action`] that may use one of the special weapons left.
+///
+/// A special weapon is only used up if it goes off; one that cannot be
+/// used, like an illegal shot, still costs the turn.
+///
+/// # Returns
+/// * Every shot fired at `defender` this turn with its announced result;
+///   empty after a sonar ping
+async fn play_turn<T: Transport>(
+    t: &mut T,
+    defender: &mut Board,
+    shots: usize,
+    ammo: &mut Arsenal,
+) -> Result<Vec<(Coord, ShotResult)>, TransportError> {
+    if ammo.is_empty() {
+        return play_volley(t, defender, shots).await;
+    }
+    let (reply, fired) = match t.recv_action().await? {
+        Action::Volley(volley) => {
+            let results = fire_volley(defender, volley, shots);
+            (ActionResult::Volley(results.clone()), results)
+        }
+        Action::Special(weapon, target) => {
+            let result = if ammo.count(weapon) == 0 {
+                Err(GuessError::OutOfAmmo(weapon))
+            } else {
+                defender.use_weapon(weapon, target)
             };
-            self.t2.send_result(res2).await;
-            self.sync_states().await;
-            if self.board1.player_state() == PlayerState::Dead {
-                break;
+            if result.is_ok() {
+                ammo.take(weapon);
             }
+            let fired = result.as_ref().map(|r| r.shots()).unwrap_or_default();
+            (
+                ActionResult::Special {
+                    weapon,
+                    target,
+                    result,
+                },
+                fired,
+            )
         }
+    };
+    t.send_action_result(reply).await?;
+    Ok(fired)
+}
+
+/// Fires at most `shots` of `volley` at `defender`.
+fn fire_volley(defender: &mut Board, volley: Vec<Coord>, shots: usize) -> Vec<(Coord, ShotResult)> {
+    volley
+        .into_iter()
+        .take(shots)
+        .map(|coord| (coord, defender.guess(coord)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use battleship_core::{Cell, Weapon, WeaponResult};
+    use battleship_transport::LocalTransport;
+    use futures::executor::block_on;
+
+    /// Every cell of a 10x10 board in row-major order.
+    fn sweep() -> impl Iterator<Item = Coord> {
+        (0..10).flat_map(|r| (0..10).map(move |c| Coord::new(r, c)))
+    }
+
+    /// A strategy for [`play_client`] that fires at every cell in turn.
+    fn sweeper() -> impl FnMut(&BoardState) -> Action {
+        let mut cells = sweep();
+        move |own| Action::Volley(cells.by_ref().take(own.shots).collect())
+    }
+
+    /// Plays one side of a game over `t` until either fleet is sunk.
+    ///
+    /// On each of the player's turns `choose` is given their own snapshot and
+    /// returns the action to take, which is sent as a plain volley while the
+    /// player has no special weapons left.
+    ///
+    /// # Returns
+    /// * Each turn's own snapshot with the engine's reply to it
+    fn play_client(
+        mut t: LocalTransport,
+        mut choose: impl FnMut(&BoardState) -> Action,
+    ) -> Vec<(BoardState, ActionResult)> {
+        let mut turns = Vec::new();
+        loop {
+            let own = block_on(t.recv_board_state()).unwrap();
+            let opp = block_on(t.recv_board_state()).unwrap();
+            if own.state == PlayerState::Dead || opp.state == PlayerState::Dead {
+                return turns;
+            }
+            if own.shots == 0 {
+                continue;
+            }
+            let reply = match choose(&own) {
+                Action::Volley(volley) if own.arsenal.is_empty() => {
+                    block_on(t.send_volley(volley)).unwrap();
+                    ActionResult::Volley(block_on(t.recv_volley_results()).unwrap())
+                }
+                Action::Special(..) if own.arsenal.is_empty() => {
+                    panic!("chose a special weapon with none left")
+                }
+                action => {
+                    block_on(t.send_action(action)).unwrap();
+                    block_on(t.recv_action_result()).unwrap()
+                }
+            };
+            turns.push((own, reply));
+        }
+    }
+
+    /// Plays a game with both players sweeping the board row by row.
+    ///
+    /// # Returns
+    /// * What each player saw, as returned by [`play_client`]
+    fn play_sweeping(
+        engine: &mut Engine<LocalTransport, LocalTransport>,
+        c1: LocalTransport,
+        c2: LocalTransport,
+    ) -> [Vec<(BoardState, ActionResult)>; 2] {
+        let h1 = std::thread::spawn(move || play_client(c1, sweeper()));
+        let h2 = std::thread::spawn(move || play_client(c2, sweeper()));
+        assert_eq!(block_on(engine.run()), Ok(()));
+        [h1.join().unwrap(), h2.join().unwrap()]
+    }
+
+    /// Every shot result in a client's replies.
+    fn volley_results(turns: &[(BoardState, ActionResult)]) -> Vec<(Coord, ShotResult)> {
+        turns
+            .iter()
+            .flat_map(|(_, reply)| match reply {
+                ActionResult::Volley(results) => results.clone(),
+                ActionResult::Special { .. } => Vec::new(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn engine_plays_to_the_end_and_reports_illegal_shots() {
+        let (c1, e1) = LocalTransport::pair();
+        let (c2, e2) = LocalTransport::pair();
+        let a1 = Coord::new(0, 0);
+        // Player 1 repeats their first shot on their second turn.
+        let (mut sweep, mut turn) = (sweeper(), 0);
+        let repeater = move |own: &BoardState| {
+            turn += 1;
+            if turn == 2 {
+                Action::Volley(vec![a1])
+            } else {
+                sweep(own)
+            }
+        };
+        let h1 = std::thread::spawn(move || play_client(c1, repeater));
+        let h2 = std::thread::spawn(move || play_client(c2, sweeper()));
+
+        let mut engine = Engine::with_seed(&GameConfig::default(), 11, e1, e2);
+        assert_eq!(block_on(engine.run()), Ok(()));
+
+        let seen = volley_results(&h1.join().unwrap());
+        h2.join().unwrap();
+        assert!(seen.contains(&(a1, Err(GuessError::AlreadyGuessed(a1)))));
+    }
+
+    #[test]
+    fn fleets_laid_out_in_advance_are_kept() {
+        use battleship_core::Orientation;
+        let config = GameConfig::square(10, &[("Destroyer", 2)]);
+        let mut board = Board::with_config(&config);
+        board
+            .place_ship("Destroyer", Coord::new(9, 8), Orientation::Horizontal)
+            .unwrap();
+        board.confirm_fleet().unwrap();
+
+        let (c1, e1) = LocalTransport::pair();
+        let (c2, e2) = LocalTransport::pair();
+        let mut engine = Engine::with_seed(&config, 5, e1, e2).with_fleet(1, board);
+        play_sweeping(&mut engine, c1, c2);
+
+        let ship = engine
+            .board1
+            .ship(engine.board1.ship_id("Destroyer").unwrap())
+            .unwrap();
+        assert_eq!(ship.coords().iter().collect::<Vec<_>>(), [(9, 8), (9, 9)]);
+    }
+
+    #[test]
+    fn mines_cost_the_shooter_a_turn_or_a_counter_hit() {
+        // Shots and mine blasts a player's own board had taken.
+        let damage = |own: &BoardState| {
+            let fired_at: Vec<Coord> = own.history.iter().map(|shot| shot.target).collect();
+            let blasts = sweep()
+                .filter(|&c| matches!(own.cell(c), Cell::Hit | Cell::Sunk))
+                .filter(|c| !fired_at.contains(c))
+                .count();
+            fired_at.len() + blasts
+        };
+        for penalty in [MinePenalty::LoseTurn, MinePenalty::CounterHit] {
+            let (c1, e1) = LocalTransport::pair();
+            let (c2, e2) = LocalTransport::pair();
+            let config = GameConfig::default().with_mines(10, penalty);
+            let mut engine = Engine::with_seed(&config, 5, e1, e2);
+
+            let mut mines = 0;
+            for turns in play_sweeping(&mut engine, c1, c2) {
+                for pair in turns.windows(2) {
+                    let ((before, reply), (after, _)) = (&pair[0], &pair[1]);
+                    let ActionResult::Volley(results) = reply else {
+                        unreachable!()
+                    };
+                    if results.iter().any(|(_, r)| r == &Ok(GuessResult::Mine)) {
+                        mines += 1;
+                        // Either the opponent fired twice in a row or the
+                        // blast struck the shooter's own fleet.
+                        assert!(
+                            damage(after) - damage(before) >= 2,
+                            "{:?}: no penalty",
+                            penalty
+                        );
+                    }
+                }
+            }
+            assert!(mines > 0);
+        }
+    }
+
+    #[test]
+    fn special_weapons_are_limited_by_each_players_ammo() {
+        // Ping twice, bombard once and then sweep the board.
+        let weapons_client = |t: LocalTransport| {
+            let mut orders = vec![
+                Action::Special(Weapon::Sonar, Coord::new(4, 4)),
+                Action::Special(Weapon::Sonar, Coord::new(5, 5)),
+                Action::Special(Weapon::Bombardment, Coord::new(8, 8)),
+            ]
+            .into_iter();
+            let mut sweep = sweeper();
+            play_client(t, move |own| {
+                let volley = sweep(own);
+                if own.arsenal.is_empty() {
+                    volley
+                } else {
+                    orders.next().unwrap_or(volley)
+                }
+            })
+        };
+
+        let (c1, e1) = LocalTransport::pair();
+        let (c2, e2) = LocalTransport::pair();
+        let h1 = std::thread::spawn(move || weapons_client(c1));
+        let h2 = std::thread::spawn(move || weapons_client(c2));
+        let arsenal = Arsenal {
+            bombardments: 1,
+            torpedoes: 0,
+            sonar_pings: 1,
+        };
+        let config = GameConfig::default().with_arsenal(arsenal);
+        let mut engine = Engine::with_seed(&config, 3, e1, e2);
+        assert_eq!(block_on(engine.run()), Ok(()));
+
+        let turns = h1.join().unwrap();
+        h2.join().unwrap();
+        let arsenals: Vec<Arsenal> = turns.iter().map(|(own, _)| own.arsenal).collect();
+        let specials: Vec<&ActionResult> = turns
+            .iter()
+            .map(|(_, reply)| reply)
+            .filter(|reply| matches!(reply, ActionResult::Special { .. }))
+            .collect();
+        assert_eq!(arsenals[0], arsenal);
+        assert_eq!(
+            arsenals[1],
+            Arsenal {
+                sonar_pings: 0,
+                ..arsenal
+            }
+        );
+        assert_eq!(
+            arsenals[2],
+            Arsenal {
+                sonar_pings: 0,
+                ..arsenal
+            }
+        );
+        assert!(arsenals[3..].iter().all(Arsenal::is_empty));
+        let ActionResult::Special {
+            result: Ok(ping), ..
+        } = specials[0]
+        else {
+            panic!("the sonar ping failed: {:?}", specials[0]);
+        };
+        assert!(matches!(ping, WeaponResult::Sonar { .. }));
+        assert_eq!(
+            specials[1],
+            &ActionResult::Special {
+                weapon: Weapon::Sonar,
+                target: Coord::new(5, 5),
+                result: Err(GuessError::OutOfAmmo(Weapon::Sonar)),
+            }
+        );
+        let ActionResult::Special {
+            result: Ok(WeaponResult::Strike(shots)),
+            ..
+        } = specials[2]
+        else {
+            panic!("the bombardment did not go off: {:?}", specials[2]);
+        };
+        assert_eq!(shots.len(), 9);
+    }
+
+    #[test]
+    fn engine_reports_a_vanished_player() {
+        let (c1, e1) = LocalTransport::pair();
+        let (_c2, e2) = LocalTransport::pair();
+        drop(c1);
+        let mut engine = Engine::new(e1, e2);
+        assert_eq!(
+            block_on(engine.run()),
+            Err(EngineError::Transport {
+                player: 1,
+                source: TransportError::Disconnected
+            })
+        );
+    }
+
+    #[test]
+    fn computer_players_play_to_the_end_over_transports() {
+        use battleship_core::Difficulty;
+        use battleship_player::{AIPlayer, PlayerClient};
+        let computer = |seed, t: LocalTransport| {
+            let player = AIPlayer::with_seed(seed).with_difficulty(Difficulty::Hard);
+            std::thread::spawn(move || block_on(PlayerClient::new(player, t).run()))
+        };
+        let (c1, e1) = LocalTransport::pair();
+        let (c2, e2) = LocalTransport::pair();
+        let h1 = computer(1, c1);
+        let h2 = computer(2, c2);
+
+        let config = GameConfig::default().with_arsenal(Arsenal::advanced());
+        let mut engine = Engine::with_seed(&config, 9, e1, e2);
+        assert_eq!(block_on(engine.run()), Ok(()));
+        assert_eq!(h1.join().unwrap(), Ok(()));
+        assert_eq!(h2.join().unwrap(), Ok(()));
+        let states = [engine.board1.player_state(), engine.board2.player_state()];
+        assert!(states.contains(&PlayerState::Dead));
     }
 }
@@ -0,0 +1,66 @@
+//! Turn order rules.
+//!
+//! After every volley the engine asks its [`TurnPolicy`] whether the
+//! attacker fires again or play passes to the opponent.
+
+use battleship_core::{Coord, GuessResult, ShotResult};
+
+/// Decides who fires next.
+pub trait TurnPolicy: Send {
+    /// Returns true if the player who fired `results` takes another turn.
+    ///
+    /// # Arguments
+    /// * `results` - Outcome of each shot of the volley just fired
+    fn fires_again(&mut self, results: &[(Coord, ShotResult)]) -> bool;
+}
+
+/// Players strictly alternate, one volley each.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Classic;
+
+impl TurnPolicy for Classic {
+    fn fires_again(&mut self, _results: &[(Coord, ShotResult)]) -> bool {
+        false
+    }
+}
+
+/// A player keeps firing as long as every turn scores at least one hit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ChainFire;
+
+impl TurnPolicy for ChainFire {
+    fn fires_again(&mut self, results: &[(Coord, ShotResult)]) -> bool {
+        results
+            .iter()
+            .any(|(_, r)| matches!(r, Ok(GuessResult::Hit | GuessResult::Sunk { .. })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use battleship_core::GuessError;
+
+    #[test]
+    fn chain_fire_continues_only_after_a_hit() {
+        let a1 = Coord::new(0, 0);
+        let miss = [
+            (a1, Ok(GuessResult::Miss)),
+            (a1, Err(GuessError::AlreadyGuessed(a1))),
+        ];
+        let hit = [
+            (a1, Ok(GuessResult::Miss)),
+            (Coord::new(0, 1), Ok(GuessResult::Hit)),
+        ];
+        let sunk = GuessResult::Sunk {
+            name: Some("Destroyer".into()),
+            cells: None,
+        };
+        let sunk = [(Coord::new(0, 2), Ok(sunk))];
+
+        assert!(!ChainFire.fires_again(&miss));
+        assert!(ChainFire.fires_again(&hit));
+        assert!(ChainFire.fires_again(&sunk));
+        assert!(!Classic.fires_again(&hit));
+    }
+}
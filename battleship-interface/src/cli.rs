@@ -1,5 +1,6 @@
-use battleship_common::BoardView;
-use crate::GameInterface;
+use crate::{render, GameInterface};
+use battleship_common::{BoardView, Coord};
+use battleship_core::{Action, Arsenal, Board, BoardState, Orientation, Weapon};
 use std::io::{self, Write};
 
 /// Simple command line user interface implementation.
@@ -9,28 +10,49 @@ use std::io::{self, Write};
 pub struct CLIInterface;
 
 impl GameInterface for CLIInterface {
-    fn get_move(&self, board: &dyn BoardView) -> (usize, usize) {
-        print!("Enter your move (e.g., A5): ");
-        io::stdout().flush().unwrap();
-        let mut input = String::new();
-        io::stdin().read_line(&mut input).unwrap();
-        let input = input.trim().to_uppercase();
-        if input.len() < 2 {
-            return (0, 0);
+    fn get_move(&self, board: &dyn BoardView) -> Coord {
+        loop {
+            let input = self.prompt("Enter your move (e.g., A5): ");
+            match Coord::parse_on(&input, board) {
+                Ok(coord) => return coord,
+                Err(e) => self.display_message(&format!("Invalid move: {}", e)),
+            }
+        }
+    }
+
+    fn get_volley(&self, board: &dyn BoardView, shots: usize) -> Vec<Coord> {
+        if shots == 1 {
+            return vec![self.get_move(board)];
+        }
+        loop {
+            let input = self.prompt(&format!("Enter {} moves (e.g., A5 B6 C7): ", shots));
+            match parse_volley(&input, board, shots) {
+                Ok(volley) => return volley,
+                Err(e) => self.display_message(&format!("Invalid volley: {}", e)),
+            }
+        }
+    }
+
+    fn get_action(&self, board: &dyn BoardView, shots: usize, arsenal: &Arsenal) -> Action {
+        if arsenal.is_empty() {
+            return Action::Volley(self.get_volley(board, shots));
         }
-        let row_char = input.chars().next().unwrap();
-        let row = row_char as usize - b'A' as usize;
-        let col = input[1..].parse::<usize>().unwrap_or(1);
-        let size = board.grid_size();
-        if row >= size || col == 0 || col > size {
-            (0, 0)
-        } else {
-            (row, col - 1)
+        let example = arsenal.available().next().map_or("", |w| w.key());
+        loop {
+            let input = self.prompt(&format!(
+                "Enter {} move(s), or a weapon and a cell (e.g., {} C3; left: {}): ",
+                shots, example, arsenal
+            ));
+            match parse_action(&input, board, shots, arsenal) {
+                Ok(action) => return action,
+                Err(e) => self.display_message(&format!("Invalid move: {}", e)),
+            }
         }
     }
 
-    fn display_board(&self, board: &dyn BoardView) {
-        println!("{}", board);
+    fn display_board(&self, state: &BoardState) {
+        println!("{}", render::board(state));
+        println!("{}", render::ships(state));
     }
 
     fn display_message(&self, message: &str) {
@@ -39,28 +61,174 @@ impl GameInterface for CLIInterface {
 }
 
 impl CLIInterface {
-    pub fn get_move_with_default(&self, board: &dyn BoardView, default: (usize, usize)) -> (usize, usize) {
-        let row_char = (b'A' + default.0 as u8) as char;
-        let col = default.1 + 1;
-        print!("Enter your move (e.g., {}{}): ", row_char, col);
+    pub fn get_move_with_default(&self, board: &dyn BoardView, default: Coord) -> Coord {
+        loop {
+            let input = self.prompt(&format!("Enter your move (e.g., {}): ", default));
+            if input.trim().is_empty() {
+                return default;
+            }
+            match Coord::parse_on(&input, board) {
+                Ok(coord) => return coord,
+                Err(e) => self.display_message(&format!("Invalid move: {}", e)),
+            }
+        }
+    }
+
+    /// Ask where to place `ship`, as a starting cell and an orientation
+    /// such as `A5 h`.
+    ///
+    /// # Arguments
+    /// * `board` - Board the ship goes on
+    /// * `ship` - Name of the ship, used in the prompt
+    /// * `orientations` - Orientations the rules allow, e.g.
+    ///   [`Orientation::allowed`]
+    ///
+    /// # Returns
+    /// * `Option<(Coord, Orientation)>` - Where to place the ship, or `None`
+    ///   if the player entered nothing or `orientations` is empty
+    pub fn get_placement(
+        &self,
+        board: &dyn BoardView,
+        ship: &str,
+        orientations: &[Orientation],
+    ) -> Option<(Coord, Orientation)> {
+        let example = orientations.first()?.key();
+        let keys: Vec<_> = orientations.iter().map(Orientation::key).collect();
+        loop {
+            let input = self.prompt(&format!(
+                "Place your {} (e.g., A5 {}; orientations: {}; blank for random): ",
+                ship,
+                example,
+                keys.join(", ")
+            ));
+            if input.trim().is_empty() {
+                return None;
+            }
+            match parse_placement(&input, board, orientations) {
+                Ok(placement) => return Some(placement),
+                Err(e) => self.display_message(&format!("Invalid placement: {}", e)),
+            }
+        }
+    }
+
+    /// Lets the player lay out their fleet one ship at a time, then
+    /// confirms it.
+    ///
+    /// A blank answer places that ship and all the rest at random, as are
+    /// any mines. If the ships placed by hand leave no room for the rest,
+    /// the player starts over. A board already confirmed is left alone.
+    ///
+    /// # Arguments
+    /// * `board` - Board whose fleet is laid out
+    pub fn place_fleet(&self, board: &mut Board) {
+        if board.is_confirmed() {
+            return;
+        }
+        let orientations = Orientation::allowed(board.diagonal_ships());
+        loop {
+            let next = board
+                .get_ships(true, true)
+                .find(|ship| !ship.is_placed())
+                .map(|ship| ship.name().to_string());
+            if let Some(name) = next {
+                self.display_board(&BoardState::new(board, true));
+                if let Some((start, orientation)) = self.get_placement(board, &name, orientations) {
+                    if let Err(e) = board.place_ship(&name, start, orientation) {
+                        self.display_message(&format!("Invalid placement: {}", e));
+                    }
+                    continue;
+                }
+            }
+            match board
+                .randomly_place_fleet()
+                .and_then(|()| board.confirm_fleet())
+            {
+                Ok(()) => return,
+                Err(e) => {
+                    self.display_message(&format!("{}; starting over", e));
+                    let placed: Vec<String> = board
+                        .get_ships(true, true)
+                        .filter(|ship| ship.is_placed())
+                        .map(|ship| ship.name().to_string())
+                        .collect();
+                    for name in placed {
+                        board
+                            .unplace_ship(&name)
+                            .expect("the fleet is still in setup");
+                    }
+                }
+            }
+        }
+    }
+
+    /// Print `message` and read one line from standard input.
+    fn prompt(&self, message: &str) -> String {
+        print!("{}", message);
         io::stdout().flush().unwrap();
         let mut input = String::new();
         io::stdin().read_line(&mut input).unwrap();
-        let input = input.trim().to_uppercase();
-        if input.is_empty() {
-            return default;
-        }
-        if input.len() < 2 {
-            return (0, 0);
+        input
+    }
+}
+
+/// Parses exactly `shots` distinct on-board coordinates separated by
+/// whitespace or commas.
+fn parse_volley(input: &str, board: &dyn BoardView, shots: usize) -> Result<Vec<Coord>, String> {
+    let mut volley: Vec<Coord> = Vec::with_capacity(shots);
+    for word in input.split(|c: char| c.is_whitespace() || c == ',') {
+        if word.is_empty() {
+            continue;
         }
-        let row_char = input.chars().next().unwrap();
-        let row = row_char as usize - b'A' as usize;
-        let col = input[1..].parse::<usize>().unwrap_or(1);
-        let size = board.grid_size();
-        if row >= size || col == 0 || col > size {
-            (0, 0)
-        } else {
-            (row, col - 1)
+        let coord = Coord::parse_on(word, board).map_err(|e| e.to_string())?;
+        if volley.contains(&coord) {
+            return Err(format!("{} is listed twice", coord));
         }
+        volley.push(coord);
+    }
+    if volley.len() != shots {
+        return Err(format!("expected {} moves, got {}", shots, volley.len()));
+    }
+    Ok(volley)
+}
+
+/// Parses either a special weapon followed by its target cell or, if the
+/// first word names no weapon, a volley of exactly `shots` cells.
+fn parse_action(
+    input: &str,
+    board: &dyn BoardView,
+    shots: usize,
+    arsenal: &Arsenal,
+) -> Result<Action, String> {
+    let mut words = input.split_whitespace();
+    let Some(Ok(weapon)) = words.next().map(str::parse::<Weapon>) else {
+        return parse_volley(input, board, shots).map(Action::Volley);
+    };
+    let (Some(cell), None) = (words.next(), words.next()) else {
+        return Err(format!("aim the {} at one cell", weapon));
+    };
+    if arsenal.count(weapon) == 0 {
+        return Err(format!("no {} left", weapon));
+    }
+    let coord = Coord::parse_on(cell, board).map_err(|e| e.to_string())?;
+    Ok(Action::Special(weapon, coord))
+}
+
+/// Parses a starting cell followed by one of the allowed orientations.
+fn parse_placement(
+    input: &str,
+    board: &dyn BoardView,
+    orientations: &[Orientation],
+) -> Result<(Coord, Orientation), String> {
+    let mut words = input.split_whitespace();
+    let (Some(cell), Some(direction), None) = (words.next(), words.next(), words.next()) else {
+        return Err("expected a cell and an orientation".to_string());
+    };
+    let coord = Coord::parse_on(cell, board).map_err(|e| e.to_string())?;
+    let orientation = direction
+        .parse::<Orientation>()
+        .map_err(|e| e.to_string())?;
+    if !orientations.contains(&orientation) {
+        return Err(format!("ships cannot lie {} in this game", orientation));
     }
+    Ok((coord, orientation))
 }
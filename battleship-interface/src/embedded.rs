@@ -1,4 +1,5 @@
-use battleship_common::BoardView;
+use battleship_common::{BoardView, Coord};
+use battleship_core::BoardState;
 use crate::GameInterface;
 
 /// Stub implementation of a user interface for embedded targets.
@@ -8,11 +9,11 @@ use crate::GameInterface;
 pub struct EmbeddedInterface;
 
 impl GameInterface for EmbeddedInterface {
-    fn get_move(&self, _board: &dyn BoardView) -> (usize, usize) {
-        (0, 0) // Replace with embedded-specific logic.
+    fn get_move(&self, _board: &dyn BoardView) -> Coord {
+        Coord::new(0, 0) // Replace with embedded-specific logic.
     }
 
-    fn display_board(&self, _board: &dyn BoardView) {
+    fn display_board(&self, _state: &BoardState) {
         // Embedded display logic.
     }
 
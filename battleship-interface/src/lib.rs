@@ -1,4 +1,5 @@
-use battleship_common::BoardView;
+use battleship_common::{BoardView, Coord};
+use battleship_core::{Action, Arsenal, BoardState};
 
 /// Abstraction over user interaction for the Battleship game.
 ///
@@ -9,11 +10,37 @@ pub trait GameInterface {
     /// Request the next move from the player.
     ///
     /// The implementation is responsible for validating and parsing
-    /// any user input into board coordinates.
-    fn get_move(&self, board: &dyn BoardView) -> (usize, usize);
+    /// any user input into a coordinate on `board`.
+    fn get_move(&self, board: &dyn BoardView) -> Coord;
 
-    /// Render the current state of the provided board to the user.
-    fn display_board(&self, board: &dyn BoardView);
+    /// Request `shots` distinct moves to be fired together as one volley.
+    ///
+    /// The default implementation asks for one move at a time with
+    /// [`get_move`](Self::get_move), skipping any cell already chosen.
+    fn get_volley(&self, board: &dyn BoardView, shots: usize) -> Vec<Coord> {
+        let mut volley: Vec<Coord> = Vec::with_capacity(shots);
+        while volley.len() < shots {
+            let coord = self.get_move(board);
+            if volley.contains(&coord) {
+                self.display_message(&format!("{} is already in this volley", coord));
+            } else {
+                volley.push(coord);
+            }
+        }
+        volley
+    }
+
+    /// Request a turn that may use one of the special weapons in
+    /// `arsenal` instead of firing `shots` ordinary shots.
+    ///
+    /// The default implementation never uses a special weapon and asks for
+    /// a volley with [`get_volley`](Self::get_volley).
+    fn get_action(&self, board: &dyn BoardView, shots: usize, _arsenal: &Arsenal) -> Action {
+        Action::Volley(self.get_volley(board, shots))
+    }
+
+    /// Render a snapshot of a board to the user.
+    fn display_board(&self, state: &BoardState);
 
     /// Show an informational message to the player.
     fn display_message(&self, message: &str);
@@ -21,4 +48,5 @@ pub trait GameInterface {
 
 pub mod cli;
 pub mod embedded;
+pub mod render;
 
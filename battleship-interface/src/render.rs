@@ -0,0 +1,73 @@
+//! Text rendering of board snapshots.
+//!
+//! Interfaces that draw to a character display share these helpers so that
+//! every text front end shows a board the same way.
+
+use battleship_core::{BoardState, Coord};
+use std::fmt::Write as _;
+
+/// Draws the grid of a snapshot with row letters and column numbers.
+///
+/// # Arguments
+/// * `state` - Snapshot to draw
+///
+/// # Returns
+/// * `String` - One line per row, preceded by a header of column numbers
+///
+/// # Example
+/// ```
+/// use battleship_core::{Board, BoardState};
+/// use battleship_interface::render;
+/// let state = BoardState::new(&Board::new(), false);
+/// let text = render::board(&state);
+/// assert_eq!(text.lines().count(), 11);
+/// assert!(text.lines().nth(1).unwrap().starts_with("A "));
+/// ```
+pub fn board(state: &BoardState) -> String {
+    let label_width = Coord::row_label(state.rows.saturating_sub(1)).len();
+
+    let mut out = String::new();
+    // header
+    out.push_str(&" ".repeat(label_width + 2));
+    for col in 1..=state.cols {
+        let _ = write!(out, " {} ", col);
+    }
+    out.push('\n');
+
+    // rows
+    for (row, cells) in state.cells.iter().enumerate() {
+        let _ = write!(
+            out,
+            "{:<width$} ",
+            Coord::row_label(row),
+            width = label_width
+        );
+        for cell in cells {
+            let _ = write!(out, " {} ", cell.icon());
+        }
+        out.push('\n');
+    }
+
+    out
+}
+
+/// Lists each ship with its length and whether it is known to be sunk.
+///
+/// # Example
+/// ```
+/// use battleship_core::{Board, BoardState};
+/// use battleship_interface::render;
+/// let state = BoardState::new(&Board::new(), false);
+/// assert!(render::ships(&state).starts_with("Carrier(5):☐ Battleship(4):☐"));
+/// ```
+pub fn ships(state: &BoardState) -> String {
+    state
+        .ships
+        .iter()
+        .map(|ship| {
+            let icon = if ship.sunk { "☒" } else { "☐" };
+            format!("{}({}):{}", ship.name, ship.length, icon)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -1,17 +1,28 @@
 use async_trait::async_trait;
-use battleship_common::BoardView;
-use battleship_core::{Board, GuessResult};
+use battleship_core::rng::{self, GameRng};
+use battleship_core::{
+    Action, ActionResult, Arsenal, Coord, Difficulty, GuessResult, OpponentView, ShotResult,
+};
 use battleship_interface::GameInterface;
-use battleship_transport::Transport;
+use battleship_transport::{Transport, TransportError};
 
 pub mod posterior;
 pub mod probability;
 
 /// Core player trait used by the game engine.
+///
+/// Players only ever see the opponent's board through an [`OpponentView`],
+/// so no implementation can peek at hidden ships.
 #[async_trait]
-pub trait Player<B: BoardView + Sync> {
-    async fn next_move(&mut self, board: &B) -> (usize, usize);
+pub trait Player {
+    async fn next_move(&mut self, board: &OpponentView) -> Coord;
     async fn on_move_result(&mut self, result: GuessResult);
+    /// Choose `shots` distinct targets to fire together in one turn.
+    async fn next_volley(&mut self, board: &OpponentView, shots: usize) -> Vec<Coord>;
+    /// Choose a turn that may use one of the special weapons in `arsenal`
+    /// instead of a volley of `shots`.
+    async fn next_action(&mut self, board: &OpponentView, shots: usize, arsenal: Arsenal)
+        -> Action;
 }
 
 /// Human player backed by an Interface implementation.
@@ -26,30 +37,100 @@ impl<I: GameInterface> HumanPlayer<I> {
 }
 
 #[async_trait]
-impl<I, B> Player<B> for HumanPlayer<I>
+impl<I> Player for HumanPlayer<I>
 where
     I: GameInterface + Send,
-    B: BoardView + Sync,
 {
-    async fn next_move(&mut self, board: &B) -> (usize, usize) {
+    async fn next_move(&mut self, board: &OpponentView) -> Coord {
         self.interface.get_move(board)
     }
 
+    async fn next_volley(&mut self, board: &OpponentView, shots: usize) -> Vec<Coord> {
+        self.interface.get_volley(board, shots)
+    }
+
     async fn on_move_result(&mut self, result: GuessResult) {
         self.interface.display_message(&format!("{}", result));
     }
+
+    async fn next_action(
+        &mut self,
+        board: &OpponentView,
+        shots: usize,
+        arsenal: Arsenal,
+    ) -> Action {
+        self.interface.get_action(board, shots, &arsenal)
+    }
 }
 
 /// Placeholder AI player using probability module.
-pub struct AIPlayer;
+pub struct AIPlayer {
+    rng: GameRng,
+    temperature: f64,
+}
+
+impl AIPlayer {
+    /// Create an AI whose shot choices are seeded from the operating system.
+    pub fn new() -> Self {
+        Self {
+            rng: rng::from_entropy(),
+            temperature: 1.0,
+        }
+    }
+
+    /// Create an AI whose shot choices are fully determined by `seed`.
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            rng: rng::seeded(seed),
+            temperature: 1.0,
+        }
+    }
+
+    /// Returns this AI playing at the given strength.
+    ///
+    /// Easier AIs flatten the shot distribution so they often pass over the
+    /// most likely cells; harder ones sharpen it towards them.
+    pub fn with_difficulty(mut self, difficulty: Difficulty) -> Self {
+        self.temperature = match difficulty {
+            Difficulty::Easy => 4.0,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 0.25,
+        };
+        self
+    }
+}
+
+impl Default for AIPlayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 #[async_trait]
-impl Player<Board> for AIPlayer {
-    async fn next_move(&mut self, board: &Board) -> (usize, usize) {
-        probability::calc_pdf_and_guess(board)
+impl Player for AIPlayer {
+    async fn next_move(&mut self, board: &OpponentView) -> Coord {
+        probability::calc_pdf_and_guess_tempered(board, self.temperature, &mut self.rng)
     }
 
     async fn on_move_result(&mut self, _result: GuessResult) {}
+
+    async fn next_volley(&mut self, board: &OpponentView, shots: usize) -> Vec<Coord> {
+        probability::calc_pdf_and_guess_volley_tempered(
+            board,
+            shots,
+            self.temperature,
+            &mut self.rng,
+        )
+    }
+
+    async fn next_action(
+        &mut self,
+        board: &OpponentView,
+        shots: usize,
+        arsenal: Arsenal,
+    ) -> Action {
+        probability::choose_action_tempered(board, shots, &arsenal, self.temperature, &mut self.rng)
+    }
 }
 
 /// Remote player communicating over a Transport.
@@ -65,19 +146,88 @@ impl<I: GameInterface, T: Transport> RemotePlayer<I, T> {
 }
 
 #[async_trait]
-impl<I, T, B> Player<B> for RemotePlayer<I, T>
+impl<I, T> Player for RemotePlayer<I, T>
 where
     I: GameInterface + Send,
     T: Transport + Send,
-    B: BoardView + Sync,
 {
-    async fn next_move(&mut self, board: &B) -> (usize, usize) {
+    async fn next_move(&mut self, board: &OpponentView) -> Coord {
         self.iface.get_move(board)
     }
 
     async fn on_move_result(&mut self, result: GuessResult) {
         let _ = self.transport.send_result(result).await;
     }
+
+    async fn next_volley(&mut self, board: &OpponentView, shots: usize) -> Vec<Coord> {
+        self.iface.get_volley(board, shots)
+    }
+
+    async fn next_action(
+        &mut self,
+        board: &OpponentView,
+        shots: usize,
+        arsenal: Arsenal,
+    ) -> Action {
+        self.iface.get_action(board, shots, &arsenal)
+    }
+}
+
+/// Client that plays a [`Player`] against the engine over a [`Transport`],
+/// e.g. an [`AIPlayer`] standing in for one side of the game.
+pub struct PlayerClient<P: Player, T: Transport> {
+    player: P,
+    transport: T,
+}
+
+impl<P: Player, T: Transport> PlayerClient<P, T> {
+    pub fn new(player: P, transport: T) -> Self {
+        Self { player, transport }
+    }
+
+    /// Plays until either fleet is sunk, passing the result of each shot
+    /// back to the player.
+    ///
+    /// # Errors
+    /// Returns a [`TransportError`] if the connection to the engine fails.
+    pub async fn run(&mut self) -> Result<(), TransportError> {
+        loop {
+            let my_state = self.transport.recv_board_state().await?;
+            let opp_state = self.transport.recv_board_state().await?;
+            if my_state.state == battleship_core::PlayerState::Dead
+                || opp_state.state == battleship_core::PlayerState::Dead
+            {
+                return Ok(());
+            }
+            if my_state.shots == 0 {
+                continue;
+            }
+
+            let view = OpponentView::from(&opp_state);
+            let results = if my_state.arsenal.is_empty() {
+                let volley = self.player.next_volley(&view, my_state.shots).await;
+                self.transport.send_volley(volley).await?;
+                self.transport.recv_volley_results().await?
+            } else {
+                let action = self
+                    .player
+                    .next_action(&view, my_state.shots, my_state.arsenal)
+                    .await;
+                self.transport.send_action(action).await?;
+                match self.transport.recv_action_result().await? {
+                    ActionResult::Volley(results) => results,
+                    ActionResult::Special { result, .. } => {
+                        result.map(|result| result.shots()).unwrap_or_default()
+                    }
+                }
+            };
+            for (_, result) in results {
+                if let Ok(result) = result {
+                    self.player.on_move_result(result).await;
+                }
+            }
+        }
+    }
 }
 
 /// Client that communicates exclusively via a [`Transport`] and
@@ -92,28 +242,73 @@ impl<I: GameInterface, T: Transport> InterfaceClient<I, T> {
         Self { iface, transport }
     }
 
-    pub async fn run(&mut self) {
+    /// Plays until either fleet is sunk.
+    ///
+    /// While the player has special weapons left each turn is sent as an
+    /// [`Action`], which is what the engine then expects.
+    ///
+    /// # Errors
+    /// Returns a [`TransportError`] if the connection to the engine fails.
+    pub async fn run(&mut self) -> Result<(), TransportError> {
         loop {
-            let my_state = self.transport.recv_board_state().await;
-            let opp_state = self.transport.recv_board_state().await;
+            let my_state = self.transport.recv_board_state().await?;
+            let opp_state = self.transport.recv_board_state().await?;
 
             self.iface.display_message("Opponent board:");
             self.iface.display_board(&opp_state);
-            self.iface.display_message(&opp_state.ships);
             self.iface.display_message("Your board:");
             self.iface.display_board(&my_state);
-            self.iface.display_message(&my_state.ships);
 
             if my_state.state == battleship_core::PlayerState::Dead
                 || opp_state.state == battleship_core::PlayerState::Dead
             {
-                break;
+                return Ok(());
+            }
+            if my_state.shots == 0 {
+                self.iface.display_message("Waiting for opponent...");
+                continue;
+            }
+
+            let view = OpponentView::from(&opp_state);
+            if my_state.arsenal.is_empty() {
+                let volley = self.iface.get_volley(&view, my_state.shots);
+                self.transport.send_volley(volley).await?;
+                let results = self.transport.recv_volley_results().await?;
+                self.show_volley(results);
+                continue;
+            }
+
+            let action = self
+                .iface
+                .get_action(&view, my_state.shots, &my_state.arsenal);
+            self.transport.send_action(action).await?;
+            match self.transport.recv_action_result().await? {
+                ActionResult::Volley(results) => self.show_volley(results),
+                ActionResult::Special {
+                    weapon,
+                    target,
+                    result,
+                } => {
+                    let outcome = match result {
+                        Ok(result) => result.to_string(),
+                        Err(e) => e.to_string(),
+                    };
+                    self.iface
+                        .display_message(&format!("{} at {}: {}", weapon, target, outcome));
+                }
             }
+        }
+    }
 
-            let mv = self.iface.get_move(&opp_state);
-            self.transport.send_move(mv).await;
-            let res = self.transport.recv_result().await;
-            self.iface.display_message(&format!("{}", res));
+    /// Shows the outcome of each shot of a volley.
+    fn show_volley(&self, results: Vec<(Coord, ShotResult)>) {
+        for (coord, res) in results {
+            match res {
+                Ok(result) => self
+                    .iface
+                    .display_message(&format!("{}: {}", coord, result)),
+                Err(e) => self.iface.display_message(&format!("{}: {}", coord, e)),
+            }
         }
     }
 }
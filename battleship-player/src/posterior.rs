@@ -2,59 +2,140 @@
 //!
 //! This module computes the true posterior probability that each cell contains part of any
 //! unsunk ship, given a board of observed hits and misses.  It uses:
-//! 1. **Bitmask representations** (`u128`) for fast overlap/coverage checks on a 10×10 grid.
+//! 1. **Bitmask representations** (two `u128` words) for fast overlap/coverage checks on
+//!    boards of up to 256 cells.
 //! 2. **Pruning** via a precomputed "future union" mask to eliminate branches that can't explain all hits.
 //! 3. **Memoization** of subtrees to avoid re-exploring identical partial fleets.
 //! 4. **Parallelism** (Rayon) over the first ship's placements for multicore speed.
 //!
+//! Boards whose ships may not touch are supported through [`Posterior::with_adjacency`],
+//! which also rules out cells next to sunk ships, and fleets of non-straight or diagonal
+//! ships through [`Posterior::with_shapes`].
+//!
 //! # Usage
 //! ```rust
 //! use battleship_player::posterior::Posterior;
 //!
 //! // Example: one ship length 2, a single hit at (0,0), no misses.
-//! let post = Posterior::new(&[], &[(0,0)], &[2]);
-//! let heatmap = post.compute();  // [[f64;10];10] sums to 1.0
+//! let post = Posterior::new(10, 10, &[], &[(0,0)], &[2]);
+//! let heatmap = post.compute();  // 10×10 `Vec<Vec<f64>>` summing to 1.0
 //! ```
 
+use battleship_config::{AdjacencyRule, Shape};
+use battleship_core::{Bitboard, OpponentView};
 use rayon::prelude::*;
 use std::collections::HashMap;
-use battleship_config::GRID_SIZE;
-type Mask = u128;  // 100 bits → 10×10 grid
+use std::ops::{BitAnd, BitOr};
+
+/// Number of `u128` words backing a [`Mask`].
+const MASK_WORDS: usize = 2;
+
+/// Largest number of board cells a [`Mask`] can represent.
+pub const MAX_CELLS: usize = MASK_WORDS * 128;
+
+/// Bitmask over the board cells, with bit `r * cols + c` set for each
+/// occupied cell.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+struct Mask([u128; MASK_WORDS]);
+
+impl Mask {
+    /// Mask with only the given bit set.
+    fn bit(index: usize) -> Self {
+        let mut m = Mask::default();
+        m.0[index / 128] = 1 << (index % 128);
+        m
+    }
+
+    fn is_set(&self, index: usize) -> bool {
+        (self.0[index / 128] >> (index % 128)) & 1 == 1
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.iter().all(|&w| w == 0)
+    }
+}
+
+impl BitOr for Mask {
+    type Output = Mask;
+
+    fn bitor(self, rhs: Mask) -> Mask {
+        Mask([self.0[0] | rhs.0[0], self.0[1] | rhs.0[1]])
+    }
+}
+
+impl BitAnd for Mask {
+    type Output = Mask;
+
+    fn bitand(self, rhs: Mask) -> Mask {
+        Mask([self.0[0] & rhs.0[0], self.0[1] & rhs.0[1]])
+    }
+}
 
 /// Packs a list of (row, col) coordinates into a single `Mask`,
-/// with bit (r*10 + c) set to 1 for each occupied cell.
-fn coords_to_mask(coords: &[(usize, usize)]) -> Mask {
-    coords.iter()
-          .fold(0, |mask, &(r, c)| mask | (1 << (r * GRID_SIZE + c)))
+/// with bit (r*cols + c) set to 1 for each occupied cell.
+fn coords_to_mask(cols: usize, coords: &[(usize, usize)]) -> Mask {
+    coords.iter().fold(Mask::default(), |mask, &(r, c)| {
+        mask | Mask::bit(r * cols + c)
+    })
 }
 
-/// Generate every possible placement of a ship of length `length`, excluding any which
-/// overlap the `exclude_mask` (misses or sunk cells).
-fn gen_placements(exclude_mask: Mask, length: usize) -> Vec<Mask> {
+/// One candidate position for a ship.
+#[derive(Clone, Copy)]
+struct Placement {
+    /// Cells the ship occupies
+    cells: Mask,
+    /// Cells no other ship may occupy: the ship plus its forbidden neighbours
+    zone: Mask,
+}
+
+/// Generate every possible placement of a ship of the given shape, in every rotation and
+/// reflection (and along both diagonals for a straight ship when `diagonal_ships` is set),
+/// excluding any which overlap the `exclude_mask` (misses, sunk cells and their exclusion
+/// zones) or whose forbidden neighbours include a hit the ship does not cover.
+fn gen_placements(
+    rows: usize,
+    cols: usize,
+    adjacency: AdjacencyRule,
+    exclude_mask: Mask,
+    hit_mask: Mask,
+    shape: &Shape,
+    diagonal_ships: bool,
+) -> Vec<Placement> {
     let mut out = Vec::new();
+    let mut push = |coords: Vec<(usize, usize)>| {
+        let cells = coords_to_mask(cols, &coords);
+        let zone = coords_to_mask(cols, &expand(rows, cols, adjacency, &coords));
+        let foreign_neighbours = Mask([zone.0[0] & !cells.0[0], zone.0[1] & !cells.0[1]]);
+        if (cells & exclude_mask).is_empty() && (foreign_neighbours & hit_mask).is_empty() {
+            out.push(Placement { cells, zone });
+        }
+    };
 
-    // Horizontal placements
-    for r in 0..GRID_SIZE {
-        for c in 0..=(GRID_SIZE - length) {
-            let mut m = 0;
-            for k in 0..length {
-                m |= 1 << (r * GRID_SIZE + c + k);
-            }
-            if m & exclude_mask == 0 {
-                out.push(m);
+    for orientation in shape.orientations() {
+        let (height, width) = (orientation.height(), orientation.width());
+        if height > rows || width > cols {
+            continue;
+        }
+        for r in 0..=(rows - height) {
+            for c in 0..=(cols - width) {
+                push(
+                    orientation
+                        .cells()
+                        .iter()
+                        .map(|&(dr, dc)| (r + dr, c + dc))
+                        .collect(),
+                );
             }
         }
     }
 
-    // Vertical placements
-    for c in 0..GRID_SIZE {
-        for r in 0..=(GRID_SIZE - length) {
-            let mut m = 0;
-            for k in 0..length {
-                m |= 1 << ((r + k) * GRID_SIZE + c);
-            }
-            if m & exclude_mask == 0 {
-                out.push(m);
+    let len = shape.len();
+    if diagonal_ships && shape.is_line() && len > 1 && len <= rows && len <= cols {
+        for r in 0..=(rows - len) {
+            for c in 0..=(cols - len) {
+                // Both diagonals of the len×len square starting at (r, c).
+                push((0..len).map(|i| (r + i, c + i)).collect());
+                push((0..len).map(|i| (r + len - 1 - i, c + i)).collect());
             }
         }
     }
@@ -62,65 +143,204 @@ fn gen_placements(exclude_mask: Mask, length: usize) -> Vec<Mask> {
     out
 }
 
+/// Returns `coords` plus every on-board neighbour the adjacency rule forbids to other ships.
+fn expand(
+    rows: usize,
+    cols: usize,
+    adjacency: AdjacencyRule,
+    coords: &[(usize, usize)],
+) -> Vec<(usize, usize)> {
+    let mut out = coords.to_vec();
+    for &(r, c) in coords {
+        for &(dr, dc) in adjacency.offsets() {
+            if let (Some(nr), Some(nc)) = (r.checked_add_signed(dr), c.checked_add_signed(dc)) {
+                if nr < rows && nc < cols {
+                    out.push((nr, nc));
+                }
+            }
+        }
+    }
+    out
+}
+
 /// Main struct for computing the exact posterior.
 pub struct Posterior {
-    miss_mask: Mask,
+    rows: usize,
+    cols: usize,
     hit_mask: Mask,
-    placements: Vec<Vec<Mask>>,  // All valid placements for each remaining ship
-    future_union: Vec<Mask>,     // Pruning masks: union of placements[depth..]
+    placements: Vec<Vec<Placement>>, // All valid placements for each remaining ship
+    future_union: Vec<Mask>,         // Pruning masks: union of placements[depth..] cells
 }
 
 impl Posterior {
     /// Construct a new Posterior calculator.
     ///
     /// # Arguments
+    /// - `rows`, `cols`: board dimensions
     /// - `misses`: list of observed miss or sunk-cell coordinates
     /// - `hits`:   list of observed unsunk-hit coordinates
     /// - `unsunk_ship_lengths`: lengths of all ships not yet sunk (e.g., `[5,4,3,3,2]`)
+    ///
+    /// # Panics
+    /// Panics if the board has more than [`MAX_CELLS`] cells.
     pub fn new(
+        rows: usize,
+        cols: usize,
         misses: &[(usize, usize)],
         hits: &[(usize, usize)],
         unsunk_ship_lengths: &[usize],
     ) -> Self {
-        let miss_mask = coords_to_mask(misses);
-        let hit_mask  = coords_to_mask(hits);
+        Self::with_adjacency(
+            rows,
+            cols,
+            AdjacencyRule::Allowed,
+            misses,
+            &[],
+            hits,
+            unsunk_ship_lengths,
+        )
+    }
 
-        let mut placements: Vec<Vec<Mask>> = unsunk_ship_lengths
+    /// Construct a Posterior calculator for a board whose ships obey `adjacency`.
+    ///
+    /// # Arguments
+    /// - `rows`, `cols`: board dimensions
+    /// - `adjacency`: whether ships may touch each other
+    /// - `misses`: list of observed miss coordinates
+    /// - `sunk`: list of cells belonging to sunk ships; their neighbours are ruled out
+    ///   too when the adjacency rule forbids touching
+    /// - `hits`:   list of observed unsunk-hit coordinates
+    /// - `unsunk_ship_lengths`: lengths of all ships not yet sunk
+    ///
+    /// # Panics
+    /// Panics if the board has more than [`MAX_CELLS`] cells.
+    pub fn with_adjacency(
+        rows: usize,
+        cols: usize,
+        adjacency: AdjacencyRule,
+        misses: &[(usize, usize)],
+        sunk: &[(usize, usize)],
+        hits: &[(usize, usize)],
+        unsunk_ship_lengths: &[usize],
+    ) -> Self {
+        let shapes: Vec<Shape> = unsunk_ship_lengths
             .iter()
-            .map(|&len| gen_placements(miss_mask, len))
+            .map(|&len| Shape::line(len))
             .collect();
+        Self::with_shapes(rows, cols, adjacency, misses, sunk, hits, &shapes, false)
+    }
+
+    /// Construct a Posterior calculator for ships of any shape.
+    ///
+    /// Takes the same arguments as [`Posterior::with_adjacency`], with the
+    /// remaining ships given by `unsunk_ship_shapes`; each may lie in any
+    /// rotation or reflection, and straight ones also diagonally when
+    /// `diagonal_ships` is set.
+    ///
+    /// # Panics
+    /// Panics if the board has more than [`MAX_CELLS`] cells.
+    #[allow(clippy::too_many_arguments)]
+    pub fn with_shapes(
+        rows: usize,
+        cols: usize,
+        adjacency: AdjacencyRule,
+        misses: &[(usize, usize)],
+        sunk: &[(usize, usize)],
+        hits: &[(usize, usize)],
+        unsunk_ship_shapes: &[Shape],
+        diagonal_ships: bool,
+    ) -> Self {
+        assert!(
+            rows * cols <= MAX_CELLS,
+            "posterior supports at most {} cells",
+            MAX_CELLS
+        );
+        let exclude_mask = coords_to_mask(cols, misses)
+            | coords_to_mask(cols, &expand(rows, cols, adjacency, sunk));
+        let hit_mask = coords_to_mask(cols, hits);
 
-        let mut zipped: Vec<_> = unsunk_ship_lengths
+        let mut placements: Vec<Vec<Placement>> = unsunk_ship_shapes
             .iter()
-            .cloned()
-            .zip(placements.into_iter())
+            .map(|shape| {
+                gen_placements(
+                    rows,
+                    cols,
+                    adjacency,
+                    exclude_mask,
+                    hit_mask,
+                    shape,
+                    diagonal_ships,
+                )
+            })
             .collect();
-        zipped.sort_by_key(|(_, p)| p.len());
-        placements = zipped.into_iter().map(|(_, p)| p).collect();
+        placements.sort_by_key(|p| p.len());
 
         let n = placements.len();
-        let mut future_union = vec![0; n + 1];
+        let mut future_union = vec![Mask::default(); n + 1];
         for d in (0..n).rev() {
-            let u = placements[d].iter().fold(future_union[d + 1], |acc, &m| acc | m);
+            let u = placements[d]
+                .iter()
+                .fold(future_union[d + 1], |acc, p| acc | p.cells);
             future_union[d] = u;
         }
 
-        Posterior { miss_mask, hit_mask, placements, future_union }
+        Posterior {
+            rows,
+            cols,
+            hit_mask,
+            placements,
+            future_union,
+        }
     }
 
-    /// Compute the 10×10 posterior heatmap `[[f64;10];10]` summing to 1.0.
-    pub fn compute(&self) -> [[f64; GRID_SIZE]; GRID_SIZE] {
+    /// Construct a Posterior calculator from everything an attacker knows
+    /// about the opponent's board.
+    ///
+    /// Hits not known to belong to a sunk ship are treated as unsunk hits,
+    /// and islands, mines that went off and cells a sonar ping found clear
+    /// like misses.
+    ///
+    /// # Panics
+    /// Panics if the board has more than [`MAX_CELLS`] cells.
+    pub fn from_view(view: &OpponentView) -> Self {
+        let coords = |cells: &Bitboard| cells.iter().collect::<Vec<_>>();
+        Self::with_shapes(
+            view.rows(),
+            view.cols(),
+            view.adjacency(),
+            &coords(
+                &view
+                    .misses()
+                    .union(view.islands())
+                    .union(view.mines())
+                    .union(&view.sonar_clear()),
+            ),
+            &coords(view.sunk_coords()),
+            &coords(&view.unsunk_hits()),
+            &view.ship_shapes_remaining(),
+            view.diagonal_ships(),
+        )
+    }
+
+    /// Compute the `rows`×`cols` posterior heatmap, indexed `[row][col]`
+    /// and summing to 1.0.
+    pub fn compute(&self) -> Vec<Vec<f64>> {
+        let (rows, cols) = (self.rows, self.cols);
+        let n_cells = rows * cols;
         let ship_count = self.placements.len();
         let mut total_weight = 0f64;
-        let mut cell_counts = vec![0f64; GRID_SIZE * GRID_SIZE];
+        let mut cell_counts = vec![0f64; n_cells];
 
+        /// `used` holds the cells of the ships placed so far and `blocked` the union
+        /// of their exclusion zones.
         fn backtrack(
             depth: usize,
             used: Mask,
+            blocked: Mask,
             cfg: &Posterior,
             counts: &mut [f64],
             weight: &mut f64,
-            memo: &mut HashMap<(usize, Mask), (f64, Vec<f64>)>,
+            memo: &mut HashMap<(usize, Mask, Mask), (f64, Vec<f64>)>,
         ) {
             if (used | cfg.future_union[depth]) & cfg.hit_mask != cfg.hit_mask {
                 return;
@@ -129,16 +349,16 @@ impl Posterior {
             if depth == cfg.placements.len() {
                 if used & cfg.hit_mask == cfg.hit_mask {
                     *weight += 1.0;
-                    for bit in 0..(GRID_SIZE * GRID_SIZE) {
-                        if (used >> bit) & 1 == 1 {
-                            counts[bit] += 1.0;
+                    for (bit, count) in counts.iter_mut().enumerate() {
+                        if used.is_set(bit) {
+                            *count += 1.0;
                         }
                     }
                 }
                 return;
             }
 
-            let key = (depth, used);
+            let key = (depth, used, blocked);
             if let Some(&(w, ref subtotal)) = memo.get(&key) {
                 *weight += w;
                 for i in 0..subtotal.len() {
@@ -148,13 +368,16 @@ impl Posterior {
             }
 
             let mut local_weight = 0f64;
-            let mut local_counts = vec![0f64; GRID_SIZE * GRID_SIZE];
+            let mut local_counts = vec![0f64; counts.len()];
 
-            for &placement_mask in &cfg.placements[depth] {
-                if used & placement_mask != 0 { continue; }
+            for placement in &cfg.placements[depth] {
+                if !(blocked & placement.cells).is_empty() {
+                    continue;
+                }
                 backtrack(
                     depth + 1,
-                    used | placement_mask,
+                    used | placement.cells,
+                    blocked | placement.zone,
                     cfg,
                     &mut local_counts,
                     &mut local_weight,
@@ -170,19 +393,27 @@ impl Posterior {
         }
 
         if ship_count == 0 {
-            return [[0.0; GRID_SIZE]; GRID_SIZE];
+            return vec![vec![0.0; cols]; rows];
         }
 
         let first_ship_placements = &self.placements[0];
         let partials: Vec<(Vec<f64>, f64)> = first_ship_placements
             .par_iter()
-            .map(|&first_mask| {
-                let mut counts = vec![0f64; GRID_SIZE * GRID_SIZE];
+            .map(|first| {
+                let mut counts = vec![0f64; n_cells];
                 let mut weight = 0f64;
                 let mut memo = HashMap::new();
 
-                if self.hit_mask == 0 || (first_mask & self.hit_mask != 0) {
-                    backtrack(1, first_mask, self, &mut counts, &mut weight, &mut memo);
+                if self.hit_mask.is_empty() || !(first.cells & self.hit_mask).is_empty() {
+                    backtrack(
+                        1,
+                        first.cells,
+                        first.zone,
+                        self,
+                        &mut counts,
+                        &mut weight,
+                        &mut memo,
+                    );
                 }
 
                 (counts, weight)
@@ -196,13 +427,10 @@ impl Posterior {
             }
         }
 
-        let mut heatmap = [[0.0; GRID_SIZE]; GRID_SIZE];
+        let mut heatmap = vec![vec![0.0; cols]; rows];
         if total_weight > 0.0 {
-            for bit in 0..(GRID_SIZE * GRID_SIZE) {
-                let p = cell_counts[bit] / total_weight;
-                let r = bit / GRID_SIZE;
-                let c = bit % GRID_SIZE;
-                heatmap[r][c] = p;
+            for (bit, &count) in cell_counts.iter().enumerate() {
+                heatmap[bit / cols][bit % cols] = count / total_weight;
             }
         }
         heatmap
@@ -213,31 +441,31 @@ impl Posterior {
 mod tests {
     use super::*;
 
-    fn approx_eq(a: f64, b: f64) -> bool { (a - b).abs() < 1e-8 }
+    const GRID_SIZE: usize = 10;
+
+    fn approx_eq(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-8
+    }
 
     #[test]
     fn test_single_length1_uniform() {
-        let post = Posterior::new(&[], &[], &[1]);
+        let post = Posterior::new(GRID_SIZE, GRID_SIZE, &[], &[], &[1]);
         let pm = post.compute();
-        for r in 0..GRID_SIZE {
-            for c in 0..GRID_SIZE {
-                assert!(approx_eq(pm[r][c], 1.0 / 100.0));
-            }
-        }
+        assert!(pm.iter().flatten().all(|&p| approx_eq(p, 1.0 / 100.0)));
     }
 
     #[test]
     fn test_length2_with_one_hit() {
-        let post = Posterior::new(&[], &[(0, 0)], &[2]);
+        let post = Posterior::new(GRID_SIZE, GRID_SIZE, &[], &[(0, 0)], &[2]);
         let pm = post.compute();
         assert!(approx_eq(pm[0][0], 1.0));
         assert!(approx_eq(pm[0][1], 0.5));
         assert!(approx_eq(pm[1][0], 0.5));
-        for r in 0..GRID_SIZE {
-            for c in 0..GRID_SIZE {
-                let is_known = (r == 0 && c == 0) || (r == 0 && c == 1) || (r == 1 && c == 0);
+        for (r, row) in pm.iter().enumerate() {
+            for (c, &p) in row.iter().enumerate() {
+                let is_known = (r == 0 && c <= 1) || (r == 1 && c == 0);
                 if !is_known {
-                    assert!(approx_eq(pm[r][c], 0.0));
+                    assert!(approx_eq(p, 0.0));
                 }
             }
         }
@@ -245,14 +473,121 @@ mod tests {
 
     #[test]
     fn test_two_length1_ships_uniform() {
-        let post = Posterior::new(&[], &[], &[1, 1]);
+        let post = Posterior::new(GRID_SIZE, GRID_SIZE, &[], &[], &[1, 1]);
         let pm = post.compute();
         let expected = 99.0 / 4950.0;
-        for r in 0..GRID_SIZE {
-            for c in 0..GRID_SIZE {
-                assert!(approx_eq(pm[r][c], expected));
+        assert!(pm.iter().flatten().all(|&p| approx_eq(p, expected)));
+    }
+
+    #[test]
+    fn test_larger_board_uniform() {
+        let post = Posterior::new(15, 15, &[], &[], &[1]);
+        let pm = post.compute();
+        assert_eq!(pm.len(), 15);
+        assert!(pm.iter().all(|row| row.len() == 15));
+        assert!(pm.iter().flatten().all(|&p| approx_eq(p, 1.0 / 225.0)));
+    }
+
+    #[test]
+    fn test_rectangular_board_edges() {
+        // A length-3 ship on a 2×4 board fits horizontally only.
+        let post = Posterior::new(2, 4, &[], &[], &[3]);
+        let pm = post.compute();
+        assert_eq!(pm.len(), 2);
+        assert!(pm.iter().all(|row| row.len() == 4));
+        assert!(approx_eq(pm[0][0], 0.25));
+        assert!(approx_eq(pm[0][1], 0.5));
+        assert!(approx_eq(pm[1][2], 0.5));
+        assert!(approx_eq(pm[1][3], 0.25));
+    }
+
+    #[test]
+    fn test_no_touch_rules_out_cells_around_sunk_ship() {
+        // A sunk length-1 ship in the corner; one length-1 ship remains on a 3x3 board.
+        let post =
+            Posterior::with_adjacency(3, 3, AdjacencyRule::NoTouch, &[], &[(0, 0)], &[], &[1]);
+        let pm = post.compute();
+        for (r, row) in pm.iter().enumerate() {
+            for (c, &p) in row.iter().enumerate() {
+                let expected = if r <= 1 && c <= 1 { 0.0 } else { 0.2 };
+                assert!(approx_eq(p, expected));
             }
         }
     }
-}
 
+    #[test]
+    fn test_no_touch_ships_never_adjacent() {
+        // Two length-2 ships on a 3x3 board: without touching they must take
+        // opposite edges.
+        let post = Posterior::with_adjacency(3, 3, AdjacencyRule::NoTouch, &[], &[], &[], &[2, 2]);
+        let pm = post.compute();
+        assert!(approx_eq(pm[1][1], 0.0));
+        let total: f64 = pm.iter().flatten().sum();
+        assert!(approx_eq(total, 4.0));
+    }
+
+    #[test]
+    fn test_shaped_ship_in_every_orientation() {
+        // An L of three cells fills any 2x2 square but one corner: four
+        // orientations in one square, so each cell is covered 3 times in 4.
+        let ell = [Shape::parse(&["#.", "##"]).unwrap()];
+        let post = Posterior::with_shapes(2, 2, AdjacencyRule::Allowed, &[], &[], &[], &ell, false);
+        let pm = post.compute();
+        assert!(pm.iter().flatten().all(|&p| approx_eq(p, 0.75)));
+
+        // A miss in the corner leaves only the L that avoids it.
+        let post = Posterior::with_shapes(
+            2,
+            2,
+            AdjacencyRule::Allowed,
+            &[(0, 0)],
+            &[],
+            &[],
+            &ell,
+            false,
+        );
+        let pm = post.compute();
+        assert!(approx_eq(pm[0][0], 0.0));
+        assert!(approx_eq(pm[1][1], 1.0));
+    }
+
+    #[test]
+    fn test_diagonal_ships() {
+        // A length-2 ship on a 2x2 board: four straight placements plus two
+        // diagonals, each cell covered by three of the six.
+        let line = [Shape::line(2)];
+        let post = Posterior::with_shapes(2, 2, AdjacencyRule::Allowed, &[], &[], &[], &line, true);
+        let pm = post.compute();
+        assert!(pm.iter().flatten().all(|&p| approx_eq(p, 0.5)));
+
+        // A hit at (0,0) with misses beside and below it leaves only the diagonal.
+        let post = Posterior::with_shapes(
+            2,
+            2,
+            AdjacencyRule::Allowed,
+            &[(0, 1), (1, 0)],
+            &[],
+            &[(0, 0)],
+            &line,
+            true,
+        );
+        let pm = post.compute();
+        assert!(approx_eq(pm[1][1], 1.0));
+    }
+
+    #[test]
+    fn test_from_view_uses_only_shot_results() {
+        use battleship_core::{Board, Coord, GameConfig, Orientation};
+        let mut board = Board::with_config(&GameConfig::new(2, 2, &[("Destroyer", 2)]));
+        board
+            .place_ship("Destroyer", Coord::new(0, 0), Orientation::Horizontal)
+            .unwrap();
+        board.confirm_fleet().unwrap();
+        board.guess(Coord::new(0, 0)).unwrap();
+
+        let pm = Posterior::from_view(&board.opponent_view()).compute();
+        assert!(approx_eq(pm[0][1], 0.5));
+        assert!(approx_eq(pm[1][0], 0.5));
+        assert!(approx_eq(pm[1][1], 0.0));
+    }
+}
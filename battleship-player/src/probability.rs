@@ -1,151 +1,153 @@
-use battleship_config::GRID_SIZE;
-use battleship_core::Board;
+use battleship_core::{weapon, Action, Arsenal, Bitboard, Coord, OpponentView, Weapon};
 use rand::Rng;
-use std::collections::HashSet;
-use std::fmt::Display;
-
-/// Calculates and displays a 2D array in a formatted way.
-/// Useful for debugging and visualizing probability distributions.
-///
-/// # Arguments
-/// * `matrix` - 2D array to display
-/// * `N` - Number of rows
-/// * `M` - Number of columns
-///
-/// # Type Parameters
-/// * `T` - Type that implements Display and Copy
-fn pretty_print_2d_array<T: Display + Copy, const N: usize, const M: usize>(matrix: &[[T; M]; N]) {
-    for row in matrix.iter() {
-        println!(
-            "{}",
-            row.iter()
-                .map(|&elem| format_element(&elem))
-                .collect::<Vec<String>>()
-                .join(" ")
-        );
-    }
-}
-
-/// Formats a single element for display in the probability matrix.
-///
-/// # Arguments
-/// * `elem` - Element to format
-///
-/// # Returns
-/// * `String` - Formatted string representation
-fn format_element<T: Display>(elem: &T) -> String {
-    if let Some(f) = elem_as_f64(elem) {
-        format!("{:>8.3}", f)
-    } else {
-        format!("{:>8}", elem)
-    }
-}
-
-/// Attempts to convert a displayable element to f64.
-///
-/// # Arguments
-/// * `elem` - Element to convert
-///
-/// # Returns
-/// * `Option<f64>` - Converted value if possible, None if conversion fails
-fn elem_as_f64<T: Display>(elem: &T) -> Option<f64> {
-    elem.to_string().parse::<f64>().ok()
-}
 
 /// Calculates the probability density function for ship locations using a Bayesian approach.
 ///
-/// For each remaining ship length, we enumerate all candidate placements. Each placement's
+/// For each remaining ship shape, we enumerate all candidate placements in every rotation
+/// and reflection. Each placement's
 /// posterior weight is computed as:
 ///    P(placement | observations) ∝ P(observations | placement) * P(placement)
 ///
 /// Here we assume:
 /// - A uniform prior over placements.
 /// - A likelihood function defined as:
-///     - If the placement conflicts with any miss/sunk cell, its likelihood is 0.
+///     - If the placement conflicts with any miss/sunk cell, or with the exclusion zone
+///       of a sunk ship under the board's adjacency rule, its likelihood is 0.
+///     - If the adjacency rule forbids the placement from touching an unsunk hit it
+///       does not cover, its likelihood is 0.
 ///     - If there are unsunk hit cells on board:
 ///         - If the placement explains at least one unsunk hit then
-///             likelihood = (L_HIT)^(number of unsunk hits covered),
+///           likelihood = (L_HIT)^(number of unsunk hits covered),
 ///         - Otherwise the placement is penalized with a low likelihood: L_NO_HIT.
 ///     - If there are no unsunk hits, the likelihood is 1 (all placements are equally likely).
 ///
 /// For each candidate placement, its likelihood is added to each unguessed coordinate that is
 /// part of that placement, and in the end the matrix is normalized.
 ///
+/// Only what the attacker has been told is used: hits not known to belong to
+/// a sunk ship count as unsunk hits.
+///
+/// While hidden mines remain, each open cell is also scaled by the chance it
+/// is not a mine. Mines fill water the ships leave free, so the risk is the
+/// density of the remaining mines over the open cells, weighted by how
+/// unlikely the cell is to hold a ship: cells with the best weight carry no
+/// risk and cells no ship can reach carry the full density.
+///
+/// Sonar pings count too: cells a ping found clear are excluded like misses,
+/// and a placement reaching into a contact area no hit accounts for yet is
+/// weighted up by L_CONTACT for each such area.
+///
 /// # Arguments
-/// * `board` - Reference to the game board
+/// * `board` - What the attacker knows about the opponent's board
 ///
 /// # Returns
-/// * `[[f64; GRID_SIZE]; GRID_SIZE]` - 2D array of probabilities
-fn calc_pdf(board: &Board) -> [[f64; GRID_SIZE]; GRID_SIZE] {
+/// * `Vec<Vec<f64>>` - 2D array of probabilities, sized to the board
+fn calc_pdf(board: &OpponentView) -> Vec<Vec<f64>> {
+    let (rows, cols) = (board.rows(), board.cols());
     let unguessed_coords = board.unguessed();
-    let unsunk_hit_coords: HashSet<(usize, usize)> = board.hit_coords(true, false);
-    let misses_and_sunk_coords: HashSet<(usize, usize)> = board
-        .guessed()
-        .difference(&unsunk_hit_coords)
-        .cloned()
-        .collect();
-    let unsunk_ship_lengths: Vec<usize> = board.ship_lengths_remaining();
+    let unsunk_hit_coords = board.unsunk_hits();
+    let excluded_coords = board
+        .misses()
+        .union(board.mines())
+        .union(&board.sonar_clear())
+        .union(&board.exclusion_zone(board.sunk_coords()));
+    let contacts = unresolved_contacts(board);
 
-    let mut prob_matrix = [[0.0; GRID_SIZE]; GRID_SIZE];
+    let mut prob_matrix = vec![vec![0.0; cols]; rows];
 
     const L_HIT: f64 = 5.0;
     const L_NO_HIT: f64 = 0.2;
+    const L_CONTACT: f64 = 3.0;
+
+    for ship_shape in board.ship_shapes_remaining() {
+        for placement in board.placements(&ship_shape) {
+            if !placement.is_disjoint(&excluded_coords) {
+                continue;
+            }
+            // A hit next to the ship but not on it belongs to another ship,
+            // which the adjacency rule may forbid.
+            let neighbours = board.exclusion_zone(&placement).difference(&placement);
+            if !neighbours.is_disjoint(&unsunk_hit_coords) {
+                continue;
+            }
+
+            let n_hits = unsunk_hit_coords.intersection(&placement).len();
+            let likelihood = if !unsunk_hit_coords.is_empty() {
+                if n_hits > 0 {
+                    L_HIT.powi(n_hits as i32)
+                } else {
+                    L_NO_HIT
+                }
+            } else {
+                1.0
+            };
+            let n_contacts = contacts
+                .iter()
+                .filter(|area| !area.is_disjoint(&placement))
+                .count();
+            let likelihood = likelihood * L_CONTACT.powi(n_contacts as i32);
 
-    for ship_length in unsunk_ship_lengths {
-        for i in 0..GRID_SIZE {
-            for j in 0..GRID_SIZE {
-                let start_coord = (i, j);
-                for &horizontal in &[true, false] {
-                    let placement = board.calc_placement(start_coord, ship_length, horizontal);
-                    if !board.valid_placement(&placement, &misses_and_sunk_coords) {
-                        continue;
-                    }
-
-                    let n_hits = unsunk_hit_coords.intersection(&placement).count();
-                    let likelihood = if !unsunk_hit_coords.is_empty() {
-                        if n_hits > 0 {
-                            L_HIT.powi(n_hits as i32)
-                        } else {
-                            L_NO_HIT
-                        }
-                    } else {
-                        1.0
-                    };
-
-                    for coord in &placement {
-                        if unguessed_coords.contains(coord) {
-                            prob_matrix[coord.0][coord.1] += likelihood;
-                        }
-                    }
+            for coord in &placement {
+                if unguessed_coords.contains(coord) {
+                    prob_matrix[coord.0][coord.1] += likelihood;
                 }
             }
         }
     }
 
+    // If no placement fits, any open cell is as good as another; islands
+    // and cells already fired at stay at zero.
+    if prob_matrix.iter().flatten().all(|&p| p == 0.0) {
+        for (row, col) in &unguessed_coords {
+            prob_matrix[row][col] = 1.0;
+        }
+    }
+
+    let mines_remaining = board.mines_remaining();
+    if mines_remaining > 0 {
+        let density = (mines_remaining as f64 / unguessed_coords.len() as f64).min(1.0);
+        let best = prob_matrix
+            .iter()
+            .flatten()
+            .fold(0.0, |a: f64, &b| a.max(b));
+        for value in prob_matrix.iter_mut().flatten() {
+            let risk = density * (1.0 - *value / best);
+            *value *= 1.0 - risk;
+        }
+    }
+
     normalize_pdf(&prob_matrix)
 }
 
+/// Sonar contact areas that no hit lies in yet, so the ship found there
+/// is still to be located.
+fn unresolved_contacts(board: &OpponentView) -> Vec<Bitboard> {
+    board
+        .sonar_contacts()
+        .into_iter()
+        .filter(|area| area.is_disjoint(board.hits()))
+        .collect()
+}
+
 /// Normalizes a probability density function matrix so all values sum to 1.
 ///
 /// # Arguments
 /// * `matrix` - Matrix to normalize
 ///
 /// # Returns
-/// * `[[f64; GRID_SIZE]; GRID_SIZE]` - Normalized probability matrix
-fn normalize_pdf(matrix: &[[f64; GRID_SIZE]; GRID_SIZE]) -> [[f64; GRID_SIZE]; GRID_SIZE] {
+/// * `Vec<Vec<f64>>` - Normalized probability matrix
+fn normalize_pdf(matrix: &[Vec<f64>]) -> Vec<Vec<f64>> {
     let sum: f64 = matrix.iter().flatten().sum();
+    let n_cells: usize = matrix.iter().map(Vec::len).sum();
     if sum == 0.0 {
         // Instead of returning a zeroed matrix, distribute probability uniformly.
-        let uniform = 1.0 / (GRID_SIZE * GRID_SIZE) as f64;
-        return [[uniform; GRID_SIZE]; GRID_SIZE];
-    }
-    let mut normalized_matrix = [[0.0; GRID_SIZE]; GRID_SIZE];
-    for (i, row) in matrix.iter().enumerate() {
-        for (j, &value) in row.iter().enumerate() {
-            normalized_matrix[i][j] = value / sum;
-        }
+        let uniform = 1.0 / n_cells as f64;
+        return matrix.iter().map(|row| vec![uniform; row.len()]).collect();
     }
-    normalized_matrix
+    matrix
+        .iter()
+        .map(|row| row.iter().map(|&value| value / sum).collect())
+        .collect()
 }
 
 /// Samples a coordinate from the probability distribution, adjusting
@@ -158,40 +160,40 @@ fn normalize_pdf(matrix: &[[f64; GRID_SIZE]; GRID_SIZE]) -> [[f64; GRID_SIZE]; G
 /// * `pdf` - Probability density function matrix
 /// * `temperature` - Temperature factor to adjust the confidence in the moves
 ///
+/// * `rng` - Source of randomness for the draw
+///
 /// # Returns
 /// * `(usize, usize)` - Selected coordinate
-fn sample_pdf(pdf: &[[f64; GRID_SIZE]; GRID_SIZE], temperature: f64) -> (usize, usize) {
+fn sample_pdf<R: Rng + ?Sized>(pdf: &[Vec<f64>], temperature: f64, rng: &mut R) -> (usize, usize) {
+    let n_rows = pdf.len();
+    let n_cols = pdf.first().map_or(0, Vec::len);
+
     // Create an adjusted matrix by applying a Boltzmann factor: p'(x) = p(x)^(1/temperature)
-    let mut adjusted_matrix = [[0.0; GRID_SIZE]; GRID_SIZE];
-    let mut total = 0.0;
-    for i in 0..GRID_SIZE {
-        for j in 0..GRID_SIZE {
-            // When temperature == 1.0, probabilities remain unchanged.
-            adjusted_matrix[i][j] = pdf[i][j].powf(1.0 / temperature);
-            total += adjusted_matrix[i][j];
-        }
-    }
+    // When temperature == 1.0, probabilities remain unchanged.
+    let adjusted_matrix: Vec<Vec<f64>> = pdf
+        .iter()
+        .map(|row| row.iter().map(|p| p.powf(1.0 / temperature)).collect())
+        .collect();
+    let total: f64 = adjusted_matrix.iter().flatten().sum();
 
     // If total is zero, fall back to a uniform random selection from all board coordinates.
     if total == 0.0 {
-        let mut rng = rand::thread_rng();
-        return (rng.gen_range(0..GRID_SIZE), rng.gen_range(0..GRID_SIZE));
+        return (rng.gen_range(0..n_rows), rng.gen_range(0..n_cols));
     }
 
     // Perform cumulative sampling from the adjusted probability distribution.
-    let mut rng = rand::thread_rng();
     let random_value: f64 = rng.gen_range(0.0..total);
     let mut cumulative_sum = 0.0;
-    for i in 0..GRID_SIZE {
-        for j in 0..GRID_SIZE {
-            cumulative_sum += adjusted_matrix[i][j];
+    for (i, row) in adjusted_matrix.iter().enumerate() {
+        for (j, &value) in row.iter().enumerate() {
+            cumulative_sum += value;
             if random_value < cumulative_sum {
                 return (i, j);
             }
         }
     }
     // Fallback due to floating point imprecision.
-    (GRID_SIZE - 1, GRID_SIZE - 1)
+    (n_rows - 1, n_cols - 1)
 }
 
 /// Calculates probabilities and makes an intelligent guess.
@@ -200,11 +202,337 @@ fn sample_pdf(pdf: &[[f64; GRID_SIZE]; GRID_SIZE], temperature: f64) -> (usize,
 /// with coordinate selection for making educated guesses.
 ///
 /// # Arguments
-/// * `board` - Reference to the game board
+/// * `board` - What the attacker knows about the opponent's board
+///
+/// # Returns
+/// * `Coord` - Chosen coordinate for the next guess
+pub fn calc_pdf_and_guess(board: &OpponentView) -> Coord {
+    calc_pdf_and_guess_with_rng(board, &mut rand::thread_rng())
+}
+
+/// Same as [`calc_pdf_and_guess`], drawing the choice from `rng` so that a
+/// seeded generator reproduces the same sequence of shots.
+pub fn calc_pdf_and_guess_with_rng<R: Rng + ?Sized>(board: &OpponentView, rng: &mut R) -> Coord {
+    calc_pdf_and_guess_tempered(board, 1.0, rng)
+}
+
+/// Same as [`calc_pdf_and_guess_with_rng`], sampling at the given
+/// `temperature`: values above 1.0 make the AI weaker, values below 1.0
+/// make it greedier.
+pub fn calc_pdf_and_guess_tempered<R: Rng + ?Sized>(
+    board: &OpponentView,
+    temperature: f64,
+    rng: &mut R,
+) -> Coord {
+    let pdf = calc_pdf(board);
+    sample_pdf(&pdf, temperature, rng).into()
+}
+
+/// Chooses `shots` distinct cells for a Salvo volley.
+///
+/// The distribution is computed once for the whole volley, and each chosen
+/// cell is removed from it before the next draw, so the volley never targets
+/// the same cell twice.
+///
+/// # Arguments
+/// * `board` - What the attacker knows about the opponent's board
+/// * `shots` - Number of cells to choose; capped at the number of unguessed cells
+/// * `rng` - Source of randomness for the draws
+///
+/// # Returns
+/// * `Vec<Coord>` - Chosen coordinates, in the order drawn
+pub fn calc_pdf_and_guess_volley_with_rng<R: Rng + ?Sized>(
+    board: &OpponentView,
+    shots: usize,
+    rng: &mut R,
+) -> Vec<Coord> {
+    calc_pdf_and_guess_volley_tempered(board, shots, 1.0, rng)
+}
+
+/// Same as [`calc_pdf_and_guess_volley_with_rng`], sampling at the given
+/// `temperature`.
+pub fn calc_pdf_and_guess_volley_tempered<R: Rng + ?Sized>(
+    board: &OpponentView,
+    shots: usize,
+    temperature: f64,
+    rng: &mut R,
+) -> Vec<Coord> {
+    let mut pdf = calc_pdf(board);
+    let mut remaining = board.unguessed();
+    let mut volley = Vec::with_capacity(shots);
+    while volley.len() < shots && !remaining.is_empty() {
+        let (row, col) = sample_pdf(&pdf, temperature, rng);
+        if !remaining.remove((row, col)) {
+            // Only reachable through the uniform fallback; pick any open cell.
+            let cell = remaining.iter().next().expect("remaining is non-empty");
+            remaining.remove(cell);
+            pdf[cell.0][cell.1] = 0.0;
+            volley.push(cell.into());
+            continue;
+        }
+        pdf[row][col] = 0.0;
+        volley.push(Coord::new(row, col));
+    }
+    volley
+}
+
+/// Chooses how to spend a turn when special weapons are left.
+///
+/// Cell probabilities are the normalized pdf scaled by the number of ship
+/// cells still to be found, so they estimate the chance each cell holds a ship.
+/// In order of preference the AI:
+/// - bombards the 3x3 area holding the most probability while a hit is
+///   still unsunk;
+/// - pings with sonar the most promising area not pinged before while it
+///   has no lead at all, neither an unsunk hit nor an unexplained contact;
+/// - launches a torpedo from the west edge, or just east of an island,
+///   along the row most likely to strike a ship, if that beats the volley;
+/// - otherwise fires an ordinary volley.
+///
+/// # Arguments
+/// * `board` - What the attacker knows about the opponent's board
+/// * `shots` - Number of cells a volley would fire at
+/// * `arsenal` - Special weapons the attacker has left
+/// * `rng` - Source of randomness for a volley
 ///
 /// # Returns
-/// * `(usize, usize)` - Chosen coordinate for the next guess
-pub fn calc_pdf_and_guess(board: &Board) -> (usize, usize) {
+/// * `Action` - Chosen action
+pub fn choose_action_with_rng<R: Rng + ?Sized>(
+    board: &OpponentView,
+    shots: usize,
+    arsenal: &Arsenal,
+    rng: &mut R,
+) -> Action {
+    choose_action_tempered(board, shots, arsenal, 1.0, rng)
+}
+
+/// Same as [`choose_action_with_rng`], sampling a volley at the given
+/// `temperature`.
+pub fn choose_action_tempered<R: Rng + ?Sized>(
+    board: &OpponentView,
+    shots: usize,
+    arsenal: &Arsenal,
+    temperature: f64,
+    rng: &mut R,
+) -> Action {
+    let (rows, cols) = (board.rows(), board.cols());
+    let afloat: usize = board.ship_lengths_remaining().iter().sum();
+    let ship_cells = afloat.saturating_sub(board.unsunk_hits().len());
     let pdf = calc_pdf(board);
-    sample_pdf(&pdf, 1.0)
+    let unguessed = board.unguessed();
+    let cell_prob = |(row, col): (usize, usize)| {
+        if unguessed.contains((row, col)) {
+            pdf[row][col] * ship_cells as f64
+        } else {
+            0.0
+        }
+    };
+    let area_mass = |center: Coord| -> f64 {
+        weapon::area_around(center, rows, cols)
+            .iter()
+            .map(cell_prob)
+            .sum()
+    };
+    let best_center = |centers: Vec<Coord>| {
+        centers
+            .into_iter()
+            .map(|center| (center, area_mass(center)))
+            .filter(|&(_, mass)| mass > 0.0)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(center, _)| center)
+    };
+    let all_cells: Vec<Coord> = (0..rows)
+        .flat_map(|row| (0..cols).map(move |col| Coord::new(row, col)))
+        .collect();
+
+    let has_unsunk_hit = !board.unsunk_hits().is_empty();
+    if has_unsunk_hit && arsenal.count(Weapon::Bombardment) > 0 {
+        if let Some(center) = best_center(all_cells.clone()) {
+            return Action::Special(Weapon::Bombardment, center);
+        }
+    }
+
+    if !has_unsunk_hit && unresolved_contacts(board).is_empty() {
+        if arsenal.count(Weapon::Sonar) > 0 {
+            let pinged: Vec<Coord> = board.pings().iter().map(|ping| ping.center).collect();
+            let fresh = all_cells
+                .iter()
+                .copied()
+                .filter(|c| !pinged.contains(c))
+                .collect();
+            if let Some(center) = best_center(fresh) {
+                return Action::Special(Weapon::Sonar, center);
+            }
+        }
+
+        if arsenal.count(Weapon::Torpedo) > 0 {
+            let islands = board.islands();
+            let run_prob = |start: Coord| -> f64 {
+                let miss_all: f64 = (start.col..cols)
+                    .map(|col| (start.row, col))
+                    .take_while(|&cell| !islands.contains(cell))
+                    .map(|cell| 1.0 - cell_prob(cell).min(1.0))
+                    .product();
+                1.0 - miss_all
+            };
+            let launch = all_cells
+                .iter()
+                .copied()
+                .filter(|c| !islands.contains(*c))
+                .filter(|c| c.col == 0 || islands.contains((c.row, c.col - 1)))
+                .map(|start| (start, run_prob(start)))
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+            let mut volley_probs: Vec<f64> = unguessed.iter().map(cell_prob).collect();
+            volley_probs.sort_by(|a, b| b.total_cmp(a));
+            let volley_prob: f64 = volley_probs.iter().take(shots).sum();
+            if let Some((start, p)) = launch {
+                if p > volley_prob.min(1.0) {
+                    return Action::Special(Weapon::Torpedo, start);
+                }
+            }
+        }
+    }
+
+    Action::Volley(calc_pdf_and_guess_volley_tempered(
+        board,
+        shots,
+        temperature,
+        rng,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use battleship_core::{rng, Board, PlayerState};
+
+    /// Plays a full game with every random choice drawn from `seed` and
+    /// returns the shots taken.
+    fn play_seeded_game(seed: u64) -> Vec<Coord> {
+        let mut board = Board::new();
+        let mut placement_rng = rng::seeded(rng::derive_seed(seed, rng::ENGINE_STREAM));
+        board
+            .randomly_place_fleet_with_rng(&mut placement_rng)
+            .unwrap();
+        board.confirm_fleet().unwrap();
+
+        let mut ai_rng = rng::seeded(rng::derive_seed(seed, rng::player_stream(1)));
+        let mut shots = Vec::new();
+        while board.player_state() != PlayerState::Dead {
+            let shot = calc_pdf_and_guess_with_rng(&board.opponent_view(), &mut ai_rng);
+            board.guess(shot).unwrap();
+            shots.push(shot);
+        }
+        shots
+    }
+
+    #[test]
+    fn same_seed_replays_same_game() {
+        assert_eq!(play_seeded_game(2024), play_seeded_game(2024));
+    }
+
+    #[test]
+    fn volley_targets_distinct_unguessed_cells() {
+        let mut board = Board::new();
+        let mut rng = rng::seeded(7);
+        board.randomly_place_fleet_with_rng(&mut rng).unwrap();
+        for shot in calc_pdf_and_guess_volley_with_rng(&board.opponent_view(), 20, &mut rng) {
+            board.guess(shot).unwrap();
+        }
+        assert_eq!(board.guessed().len(), 20);
+
+        let volley = calc_pdf_and_guess_volley_with_rng(&board.opponent_view(), 200, &mut rng);
+        assert_eq!(volley.len(), 80);
+        assert!(volley.iter().all(|&c| !board.guessed().contains(c)));
+    }
+
+    #[test]
+    fn islands_are_never_targeted() {
+        use battleship_core::{GameConfig, Terrain};
+        let map = Terrain::parse(&["#...#", ".....", "..#..", ".....", "#...#"]).unwrap();
+        let config = GameConfig::square(5, &[("Cruiser", 3), ("Destroyer", 2)]).with_terrain(&map);
+        let mut board = Board::with_config(&config);
+        let mut rng = rng::seeded(11);
+        board.randomly_place_fleet_with_rng(&mut rng).unwrap();
+        board.confirm_fleet().unwrap();
+        while board.player_state() != PlayerState::Dead {
+            let view = board.opponent_view();
+            let shot = calc_pdf_and_guess_with_rng(&view, &mut rng);
+            assert!(
+                !view.islands().contains(shot),
+                "fired at the island {}",
+                shot
+            );
+            board.guess(shot).unwrap();
+        }
+        let volley = calc_pdf_and_guess_volley_with_rng(&board.opponent_view(), 25, &mut rng);
+        assert!(volley.iter().all(|&c| !board.islands().contains(c)));
+    }
+
+    #[test]
+    fn likely_ship_cells_are_preferred_while_mines_remain() {
+        use battleship_core::{GameConfig, MinePenalty, Orientation};
+        let plain = GameConfig::square(5, &[("Destroyer", 2)]);
+        let mined = plain.clone().with_mines(3, MinePenalty::CounterHit);
+        let pdfs: Vec<_> = [plain, mined]
+            .iter()
+            .map(|config| {
+                let mut board = Board::with_config(config);
+                board
+                    .place_ship("Destroyer", Coord::new(2, 2), Orientation::Horizontal)
+                    .unwrap();
+                for &mine in [(0, 0), (4, 4), (0, 4)].iter().take(config.mines) {
+                    board.place_mine(mine.into()).unwrap();
+                }
+                board.confirm_fleet().unwrap();
+                board.guess(Coord::new(2, 2)).unwrap();
+                if config.mines > 0 {
+                    board.guess(Coord::new(0, 0)).unwrap();
+                }
+                calc_pdf(&board.opponent_view())
+            })
+            .collect();
+        let (plain, mined) = (&pdfs[0], &pdfs[1]);
+        assert_eq!(mined[0][0], 0.0, "a mine that went off holds no ship");
+        // Far from the hit, the risk of a mine outweighs the slim chance of
+        // a ship.
+        assert!(mined[4][0] / mined[2][3] < plain[4][0] / plain[2][3]);
+    }
+
+    #[test]
+    fn special_weapons_follow_the_leads_on_the_board() {
+        use battleship_core::{Arsenal, Orientation};
+        let mut board = Board::new();
+        board
+            .place_ship("Destroyer", Coord::new(4, 4), Orientation::Horizontal)
+            .unwrap();
+        let mut rng = rng::seeded(3);
+        let arsenal = Arsenal::advanced();
+
+        // With no lead yet, sonar searches the open water.
+        let Action::Special(Weapon::Sonar, center) =
+            choose_action_with_rng(&board.opponent_view(), 1, &arsenal, &mut rng)
+        else {
+            panic!("expected a sonar ping");
+        };
+        board.sonar_ping(center).unwrap();
+
+        // Water a ping found clear is never fired at.
+        board.sonar_ping(Coord::new(8, 8)).unwrap();
+        let pdf = calc_pdf(&board.opponent_view());
+        assert_eq!(pdf[8][8], 0.0);
+        assert_eq!(pdf[9][9], 0.0);
+
+        // An unsunk hit calls for a bombardment next to it.
+        board.guess(Coord::new(4, 4)).unwrap();
+        let action = choose_action_with_rng(&board.opponent_view(), 1, &arsenal, &mut rng);
+        let Action::Special(Weapon::Bombardment, center) = action else {
+            panic!("expected a bombardment, got {:?}", action);
+        };
+        assert!(weapon::area_around(center, 10, 10).contains(Coord::new(4, 5)));
+
+        // Without special weapons every turn is a volley.
+        let action = choose_action_with_rng(&board.opponent_view(), 2, &Arsenal::none(), &mut rng);
+        assert!(matches!(action, Action::Volley(shots) if shots.len() == 2));
+    }
 }
@@ -1,10 +1,44 @@
+
 use async_trait::async_trait;
 use battleship_core::Board;
-use battleship_common::Result;
+use std::fmt;
+
+/// Errors raised while saving or loading a game.
+#[derive(Debug)]
+pub enum SaveError {
+    /// Reading or writing the save failed
+    Io(std::io::Error),
+    /// The saved data could not be understood
+    Corrupt(String),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Io(e) => write!(f, "could not access the save: {}", e),
+            SaveError::Corrupt(reason) => write!(f, "the save is corrupt: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SaveError::Io(e) => Some(e),
+            SaveError::Corrupt(_) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for SaveError {
+    fn from(e: std::io::Error) -> Self {
+        SaveError::Io(e)
+    }
+}
 
 #[async_trait]
 pub trait SaveLoad {
-    async fn save(&self, board: &Board) -> Result<()>;
-    async fn load(&self) -> Result<Board>;
+    async fn save(&self, board: &Board) -> Result<(), SaveError>;
+    async fn load(&self) -> Result<Board, SaveError>;
 }
 
@@ -1,25 +1,68 @@
 use async_trait::async_trait;
-use battleship_core::{BoardState, GuessResult};
+use battleship_core::{Action, ActionResult, BoardState, Coord, GuessResult, ShotResult};
+use std::fmt;
 use std::sync::mpsc::{channel, Receiver, Sender};
 
+/// Errors raised while talking to the other end of a [`Transport`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TransportError {
+    /// The other end has gone away
+    Disconnected,
+}
+
+impl fmt::Display for TransportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransportError::Disconnected => write!(f, "the other side disconnected"),
+        }
+    }
+}
+
+impl std::error::Error for TransportError {}
+
 #[async_trait]
 pub trait Transport {
-    async fn send_move(&mut self, coord: (usize, usize));
-    async fn recv_move(&mut self) -> (usize, usize);
-    async fn send_result(&mut self, result: GuessResult);
-    async fn recv_result(&mut self) -> GuessResult;
-    async fn send_board_state(&mut self, state: BoardState);
-    async fn recv_board_state(&mut self) -> BoardState;
+    async fn send_move(&mut self, coord: Coord) -> Result<(), TransportError>;
+    async fn recv_move(&mut self) -> Result<Coord, TransportError>;
+    async fn send_result(&mut self, result: GuessResult) -> Result<(), TransportError>;
+    async fn recv_result(&mut self) -> Result<GuessResult, TransportError>;
+    /// Send every shot of one turn at once.
+    async fn send_volley(&mut self, shots: Vec<Coord>) -> Result<(), TransportError>;
+    async fn recv_volley(&mut self) -> Result<Vec<Coord>, TransportError>;
+    /// Report the outcome of each shot of a volley, in the order fired.
+    async fn send_volley_results(
+        &mut self,
+        results: Vec<(Coord, ShotResult)>,
+    ) -> Result<(), TransportError>;
+    async fn recv_volley_results(&mut self) -> Result<Vec<(Coord, ShotResult)>, TransportError>;
+    /// Send a turn that may use a special weapon. In the advanced weapons
+    /// mode the engine expects this in place of a volley while the player
+    /// has special weapons left.
+    async fn send_action(&mut self, action: Action) -> Result<(), TransportError>;
+    async fn recv_action(&mut self) -> Result<Action, TransportError>;
+    /// Report what a turn sent with [`send_action`](Self::send_action) did.
+    async fn send_action_result(&mut self, result: ActionResult) -> Result<(), TransportError>;
+    async fn recv_action_result(&mut self) -> Result<ActionResult, TransportError>;
+    async fn send_board_state(&mut self, state: BoardState) -> Result<(), TransportError>;
+    async fn recv_board_state(&mut self) -> Result<BoardState, TransportError>;
 }
 
 /// Local in-memory transport using standard channels.
 ///
 /// Instances are created as connected pairs via [`LocalTransport::pair`].
 pub struct LocalTransport {
-    move_tx: Sender<(usize, usize)>,
-    move_rx: Receiver<(usize, usize)>,
+    move_tx: Sender<Coord>,
+    move_rx: Receiver<Coord>,
     result_tx: Sender<GuessResult>,
     result_rx: Receiver<GuessResult>,
+    volley_tx: Sender<Vec<Coord>>,
+    volley_rx: Receiver<Vec<Coord>>,
+    volley_result_tx: Sender<Vec<(Coord, ShotResult)>>,
+    volley_result_rx: Receiver<Vec<(Coord, ShotResult)>>,
+    action_tx: Sender<Action>,
+    action_rx: Receiver<Action>,
+    action_result_tx: Sender<ActionResult>,
+    action_result_rx: Receiver<ActionResult>,
     board_tx: Sender<BoardState>,
     board_rx: Receiver<BoardState>,
 }
@@ -34,6 +77,14 @@ impl LocalTransport {
         let (tx_move_2, rx_move_2) = channel();
         let (tx_res_1, rx_res_1) = channel();
         let (tx_res_2, rx_res_2) = channel();
+        let (tx_volley_1, rx_volley_1) = channel();
+        let (tx_volley_2, rx_volley_2) = channel();
+        let (tx_volley_res_1, rx_volley_res_1) = channel();
+        let (tx_volley_res_2, rx_volley_res_2) = channel();
+        let (tx_action_1, rx_action_1) = channel();
+        let (tx_action_2, rx_action_2) = channel();
+        let (tx_action_res_1, rx_action_res_1) = channel();
+        let (tx_action_res_2, rx_action_res_2) = channel();
         let (tx_board_1, rx_board_1) = channel();
         let (tx_board_2, rx_board_2) = channel();
 
@@ -42,6 +93,14 @@ impl LocalTransport {
             move_rx: rx_move_2,
             result_tx: tx_res_1,
             result_rx: rx_res_2,
+            volley_tx: tx_volley_1,
+            volley_rx: rx_volley_2,
+            volley_result_tx: tx_volley_res_1,
+            volley_result_rx: rx_volley_res_2,
+            action_tx: tx_action_1,
+            action_rx: rx_action_2,
+            action_result_tx: tx_action_res_1,
+            action_result_rx: rx_action_res_2,
             board_tx: tx_board_1,
             board_rx: rx_board_2,
         };
@@ -51,6 +110,14 @@ impl LocalTransport {
             move_rx: rx_move_1,
             result_tx: tx_res_2,
             result_rx: rx_res_1,
+            volley_tx: tx_volley_2,
+            volley_rx: rx_volley_1,
+            volley_result_tx: tx_volley_res_2,
+            volley_result_rx: rx_volley_res_1,
+            action_tx: tx_action_2,
+            action_rx: rx_action_1,
+            action_result_tx: tx_action_res_2,
+            action_result_rx: rx_action_res_1,
             board_tx: tx_board_2,
             board_rx: rx_board_1,
         };
@@ -61,53 +128,142 @@ impl LocalTransport {
 
 #[async_trait]
 impl Transport for LocalTransport {
-    async fn send_move(&mut self, coord: (usize, usize)) {
-        let _ = self.move_tx.send(coord);
+    async fn send_move(&mut self, coord: Coord) -> Result<(), TransportError> {
+        self.move_tx
+            .send(coord)
+            .map_err(|_| TransportError::Disconnected)
+    }
+
+    async fn recv_move(&mut self) -> Result<Coord, TransportError> {
+        self.move_rx
+            .recv()
+            .map_err(|_| TransportError::Disconnected)
+    }
+
+    async fn send_result(&mut self, result: GuessResult) -> Result<(), TransportError> {
+        self.result_tx
+            .send(result)
+            .map_err(|_| TransportError::Disconnected)
+    }
+
+    async fn recv_result(&mut self) -> Result<GuessResult, TransportError> {
+        self.result_rx
+            .recv()
+            .map_err(|_| TransportError::Disconnected)
+    }
+
+    async fn send_volley(&mut self, shots: Vec<Coord>) -> Result<(), TransportError> {
+        self.volley_tx
+            .send(shots)
+            .map_err(|_| TransportError::Disconnected)
     }
 
-    async fn recv_move(&mut self) -> (usize, usize) {
-        self.move_rx.recv().expect("transport channel closed")
+    async fn recv_volley(&mut self) -> Result<Vec<Coord>, TransportError> {
+        self.volley_rx
+            .recv()
+            .map_err(|_| TransportError::Disconnected)
     }
 
-    async fn send_result(&mut self, result: GuessResult) {
-        let _ = self.result_tx.send(result);
+    async fn send_volley_results(
+        &mut self,
+        results: Vec<(Coord, ShotResult)>,
+    ) -> Result<(), TransportError> {
+        self.volley_result_tx
+            .send(results)
+            .map_err(|_| TransportError::Disconnected)
     }
 
-    async fn recv_result(&mut self) -> GuessResult {
-        self.result_rx.recv().expect("transport channel closed")
+    async fn recv_volley_results(&mut self) -> Result<Vec<(Coord, ShotResult)>, TransportError> {
+        self.volley_result_rx
+            .recv()
+            .map_err(|_| TransportError::Disconnected)
     }
 
-    async fn send_board_state(&mut self, state: BoardState) {
-        let _ = self.board_tx.send(state);
+    async fn send_action(&mut self, action: Action) -> Result<(), TransportError> {
+        self.action_tx
+            .send(action)
+            .map_err(|_| TransportError::Disconnected)
     }
 
-    async fn recv_board_state(&mut self) -> BoardState {
-        self.board_rx.recv().expect("transport channel closed")
+    async fn recv_action(&mut self) -> Result<Action, TransportError> {
+        self.action_rx
+            .recv()
+            .map_err(|_| TransportError::Disconnected)
+    }
+
+    async fn send_action_result(&mut self, result: ActionResult) -> Result<(), TransportError> {
+        self.action_result_tx
+            .send(result)
+            .map_err(|_| TransportError::Disconnected)
+    }
+
+    async fn recv_action_result(&mut self) -> Result<ActionResult, TransportError> {
+        self.action_result_rx
+            .recv()
+            .map_err(|_| TransportError::Disconnected)
+    }
+
+    async fn send_board_state(&mut self, state: BoardState) -> Result<(), TransportError> {
+        self.board_tx
+            .send(state)
+            .map_err(|_| TransportError::Disconnected)
+    }
+
+    async fn recv_board_state(&mut self) -> Result<BoardState, TransportError> {
+        self.board_rx
+            .recv()
+            .map_err(|_| TransportError::Disconnected)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use battleship_core::{GuessError, Weapon, WeaponResult};
     use futures::executor::block_on;
 
     #[test]
     fn local_transport_pair_communication() {
         let (mut a, mut b) = LocalTransport::pair();
 
-        block_on(a.send_move((1, 2)));
-        assert_eq!(block_on(b.recv_move()), (1, 2));
+        block_on(a.send_move(Coord::new(1, 2))).unwrap();
+        assert_eq!(block_on(b.recv_move()).unwrap(), Coord::new(1, 2));
+
+        block_on(b.send_result(GuessResult::Hit)).unwrap();
+        assert_eq!(block_on(a.recv_result()).unwrap(), GuessResult::Hit);
+
+        let volley = vec![Coord::new(0, 0), Coord::new(3, 4)];
+        block_on(a.send_volley(volley.clone())).unwrap();
+        assert_eq!(block_on(b.recv_volley()).unwrap(), volley);
 
-        block_on(b.send_result(GuessResult::Hit));
-        assert_eq!(block_on(a.recv_result()), GuessResult::Hit);
+        let results = vec![
+            (volley[0], Ok(GuessResult::Miss)),
+            (volley[1], Err(GuessError::AlreadyGuessed(volley[1]))),
+        ];
+        block_on(b.send_volley_results(results.clone())).unwrap();
+        assert_eq!(block_on(a.recv_volley_results()).unwrap(), results);
 
-        let state = BoardState {
-            grid_size: 10,
-            board: "test".into(),
-            ships: "".into(),
-            state: battleship_core::PlayerState::Alive,
+        let action = Action::Special(Weapon::Sonar, Coord::new(4, 4));
+        block_on(a.send_action(action.clone())).unwrap();
+        assert_eq!(block_on(b.recv_action()).unwrap(), action);
+
+        let result = ActionResult::Special {
+            weapon: Weapon::Sonar,
+            target: Coord::new(4, 4),
+            result: Ok(WeaponResult::Sonar { contact: false }),
         };
-        block_on(a.send_board_state(state.clone()));
-        assert_eq!(block_on(b.recv_board_state()).board, state.board);
+        block_on(b.send_action_result(result.clone())).unwrap();
+        assert_eq!(block_on(a.recv_action_result()).unwrap(), result);
+
+        let state = BoardState::new(&battleship_core::Board::new(), true).with_shots(1);
+        block_on(a.send_board_state(state.clone())).unwrap();
+        assert_eq!(block_on(b.recv_board_state()).unwrap(), state);
+
+        drop(b);
+        assert_eq!(block_on(a.recv_move()), Err(TransportError::Disconnected));
+        assert_eq!(
+            block_on(a.send_move(Coord::new(0, 0))),
+            Err(TransportError::Disconnected)
+        );
     }
 }